@@ -2,15 +2,34 @@
 mod tests;
 
 use std::{
-    path::PathBuf,
-    process::{Command, ExitStatus},
+    env, io,
+    path::{Path, PathBuf},
+    process::ExitStatus,
+    time::Duration,
 };
 use thiserror::Error;
 
-use crate::util::find_program;
+use crate::conf::{ConanConf, ConanEnv, ConfEnv, ConfError, ConfValue};
+use crate::context::{ConanContext, ConanContextError};
+use crate::home::ConanHome;
+use crate::output::ConanEvent;
+use crate::util::ConanVersion;
 
 #[derive(Debug, Error)]
-pub enum ConanBuildError {}
+pub enum ConanBuildError {
+    #[error("{0} is not supported by this Conan version")]
+    UnsupportedArgument(&'static str),
+    #[error("Invalid Unicode in path")]
+    InvalidUnicodeInPath,
+    #[error("Output directory not found")]
+    OutputDirNotFound,
+    #[error(transparent)]
+    InvalidConf(#[from] ConfError),
+    #[error("I/O error: {0}")]
+    Io(#[from] io::Error),
+    #[error(transparent)]
+    Context(#[from] ConanContextError),
+}
 
 /// A command for building a Conan package.
 pub struct BuildCommand {
@@ -22,6 +41,8 @@ pub struct BuildCommand {
     should_configure: bool,
     should_build: bool,
     should_install: bool,
+    conf_env: ConfEnv,
+    context: ConanContext,
 }
 
 /// Builder pattern for creating a `BuildCommand`
@@ -35,6 +56,8 @@ pub struct BuildCommandBuilder {
     should_configure: bool,
     should_build: bool,
     should_install: bool,
+    conf_env: ConfEnv,
+    context: ConanContext,
 }
 
 impl Default for BuildCommand {
@@ -48,6 +71,8 @@ impl Default for BuildCommand {
             should_configure: false,
             should_build: false,
             should_install: false,
+            conf_env: ConfEnv::default(),
+            context: ConanContext::default(),
         }
     }
 }
@@ -97,6 +122,73 @@ impl BuildCommandBuilder {
         self
     }
 
+    /// Set a configuration item, e.g. `with_conf("tools.cmake.cmaketoolchain:generator", "Ninja")`.
+    pub fn with_conf<V: Into<ConfValue>>(mut self, name: &str, value: V) -> Self {
+        self.conf_env.conf.push(ConanConf::new(name, value));
+        self
+    }
+
+    /// Set a configuration item for the packages matching a name or pattern.
+    pub fn with_package_conf<V: Into<ConfValue>>(mut self, package: &str, name: &str, value: V) -> Self {
+        self.conf_env
+            .conf
+            .push(ConanConf::new(name, value).for_package(package));
+        self
+    }
+
+    /// Set an environment variable for the package builds, e.g. `with_env("CC", "clang")`.
+    ///
+    /// Conan 1 takes the environment of the install folder, only Conan 2 supports it here.
+    pub fn with_env(mut self, name: &str, value: &str) -> Self {
+        self.conf_env.env.push(ConanEnv::new(name, value));
+        self
+    }
+
+    /// Set an environment variable for the builds of the packages matching a name or pattern.
+    pub fn with_package_env(mut self, package: &str, name: &str, value: &str) -> Self {
+        self.conf_env.env.push(ConanEnv::new(name, value).for_package(package));
+        self
+    }
+
+    /// Run `conan build` through a shared context, keeping the timeout, output handling, home and version
+    /// set on this builder.
+    pub fn with_context(mut self, context: ConanContext) -> Self {
+        self.context = context.merged_with(self.context);
+        self
+    }
+
+    /// Kill Conan if it runs longer than `timeout`, failing `try_run` with `ConanContextError::Timeout`.
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.context = self.context.with_timeout(timeout);
+        self
+    }
+
+    /// Pass the events parsed from the Conan output to `handler`, e.g. `cargo_progress`.
+    pub fn with_output_handler<F>(mut self, handler: F) -> Self
+    where
+        F: Fn(&ConanEvent) + Send + Sync + 'static,
+    {
+        self.context = self.context.with_output_handler(handler);
+        self
+    }
+
+    /// Append the complete Conan output to this file instead of `OUT_DIR/conan.log`.
+    pub fn with_log_file(mut self, log_file: &Path) -> Self {
+        self.context = self.context.with_log_file(log_file);
+        self
+    }
+
+    /// Run Conan with an isolated home, e.g. `ConanHome::in_out_dir()?`.
+    pub fn with_conan_home(mut self, conan_home: ConanHome) -> Self {
+        self.context = self.context.with_home(conan_home);
+        self
+    }
+
+    pub fn conan_version(mut self, conan_version: ConanVersion) -> Self {
+        self.context = self.context.with_conan_version(conan_version);
+        self
+    }
+
     pub fn build(self) -> BuildCommand {
         BuildCommand {
             recipe_path: self.recipe_path,
@@ -107,6 +199,8 @@ impl BuildCommandBuilder {
             should_configure: self.should_configure,
             should_build: self.should_build,
             should_install: self.should_install,
+            conf_env: self.conf_env,
+            context: self.context,
         }
     }
 }
@@ -146,13 +240,68 @@ impl BuildCommand {
             args.push("--install");
         }
 
+        let mut conf_env_args = Vec::new();
+        if self.conf_env != ConfEnv::default() {
+            // NOTE: Conan 1 builds with the conf and environment of the install folder
+            let conan_version = self.context.conan_version();
+            if conan_version == ConanVersion::V1 {
+                return Err(ConanBuildError::UnsupportedArgument("conf and env in conan build"));
+            }
+            let env_profile = self.env_profile().ok_or(ConanBuildError::OutputDirNotFound)?;
+            let env_profile = env_profile.to_str().ok_or(ConanBuildError::InvalidUnicodeInPath)?;
+            conf_env_args = self.conf_env.args(conan_version, env_profile, true)?;
+        }
+        args.extend(conf_env_args.iter().map(String::as_str));
+
         Ok(args.iter().map(|s| s.to_string()).collect())
     }
 
+    /// Profile holding the environment for Conan 2, in `OUT_DIR` or the current directory.
+    pub fn env_profile(&self) -> Option<PathBuf> {
+        let mut env_profile = env::var("OUT_DIR")
+            .ok()
+            .map(PathBuf::from)
+            .or_else(|| env::current_dir().ok())?;
+        env_profile.push("conan-profiles");
+        env_profile.push("buildenv");
+        Some(env_profile)
+    }
+
+    fn write_env_profile(&self) -> Result<(), ConanBuildError> {
+        if self.conf_env != ConfEnv::default() {
+            let env_profile = self.env_profile().ok_or(ConanBuildError::OutputDirNotFound)?;
+            self.conf_env.write_env_profile(ConanVersion::V2, &env_profile)?;
+        }
+        Ok(())
+    }
+
+    /// Run `conan build`, returning the failure to start or finish it, e.g. `ConanContextError::Timeout`.
+    pub fn try_run(&self) -> Result<ExitStatus, ConanBuildError> {
+        let args = self.args()?;
+        self.write_env_profile()?;
+        Ok(self.context.execute(&args)?.status)
+    }
+
     pub fn run(&self) -> Option<ExitStatus> {
-        let args = self.args().ok()?;
-        let conan_bin = find_program()?;
-        let mut command = Command::new(conan_bin);
-        Some(command.args(args).status().ok()?)
+        self.try_run().ok()
+    }
+
+    /// Async `try_run`, with `tokio::process`. Dropping the future kills Conan.
+    #[cfg(feature = "tokio")]
+    pub fn try_run_async(
+        &self,
+    ) -> impl std::future::Future<Output = Result<ExitStatus, ConanBuildError>> + Send + 'static {
+        let build = self.args().and_then(|args| {
+            self.write_env_profile()?;
+            Ok(self.context.execute_async(&args))
+        });
+        async move { Ok(build?.await?.status) }
+    }
+
+    /// Async `run`, with `tokio::process`. Dropping the future kills Conan.
+    #[cfg(feature = "tokio")]
+    pub fn run_async(&self) -> impl std::future::Future<Output = Option<ExitStatus>> + Send + 'static {
+        let build = self.try_run_async();
+        async move { build.await.ok() }
     }
 }
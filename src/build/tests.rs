@@ -43,3 +43,85 @@ fn test_custom_build_command() {
 
     assert_eq!(args, expected);
 }
+
+#[test]
+fn test_build_command_with_conf() {
+    let build_command = BuildCommandBuilder::new()
+        .with_recipe_path(PathBuf::from("."))
+        .with_conf("tools.cmake.cmaketoolchain:generator", "Ninja")
+        .with_package_conf("zlib", "tools.build:jobs", 2)
+        .conan_version(ConanVersion::V2)
+        .build();
+    assert_eq!(
+        build_command.args().unwrap(),
+        [
+            "build",
+            ".",
+            "-c",
+            "tools.cmake.cmaketoolchain:generator=Ninja",
+            "-c",
+            "zlib/*:tools.build:jobs=2"
+        ]
+    );
+
+    let build_command = BuildCommandBuilder::new()
+        .with_recipe_path(PathBuf::from("."))
+        .with_env("CC", "clang")
+        .conan_version(ConanVersion::V1)
+        .build();
+    assert!(matches!(
+        build_command.args(),
+        Err(ConanBuildError::UnsupportedArgument(_))
+    ));
+}
+
+#[test]
+fn test_build_command_try_run() {
+    use crate::context::{ConanContext, ConanContextError};
+    use crate::executor::{ScriptedExecutor, ScriptedResponse};
+    use std::time::Duration;
+
+    let executor = ScriptedExecutor::new()
+        .on(&["build"], ScriptedResponse::hanging())
+        .on(&["build"], ScriptedResponse::failure(2));
+    let build_command = BuildCommandBuilder::new()
+        .with_context(
+            ConanContext::new()
+                .with_program(Path::new("conan"))
+                .with_conan_version(ConanVersion::V2)
+                .with_executor(executor.clone()),
+        )
+        .with_recipe_path(PathBuf::from("."))
+        .with_timeout(Duration::from_secs(60))
+        .build();
+
+    assert!(matches!(
+        build_command.try_run(),
+        Err(ConanBuildError::Context(ConanContextError::Timeout(_)))
+    ));
+    assert_eq!(build_command.try_run().unwrap().code(), Some(2));
+    assert_eq!(executor.invocations()[0].timeout, Some(Duration::from_secs(60)));
+}
+
+#[test]
+fn test_build_command_with_context_keeps_settings() {
+    use crate::context::ConanContext;
+    use crate::executor::RecordingExecutor;
+    use std::time::Duration;
+
+    let executor = RecordingExecutor::new();
+    let build_command = BuildCommandBuilder::new()
+        .with_timeout(Duration::from_secs(60))
+        .with_context(
+            ConanContext::new()
+                .with_program(Path::new("conan"))
+                .with_conan_version(ConanVersion::V2)
+                .with_timeout(Duration::from_secs(600))
+                .with_executor(executor.clone()),
+        )
+        .with_recipe_path(PathBuf::from("."))
+        .build();
+
+    assert!(build_command.try_run().unwrap().success());
+    assert_eq!(executor.invocations()[0].timeout, Some(Duration::from_secs(60)));
+}
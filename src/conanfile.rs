@@ -0,0 +1,216 @@
+#[cfg(test)]
+mod tests;
+
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::Path;
+use std::str::FromStr;
+
+use indexmap::IndexMap;
+use thiserror::Error;
+
+use crate::reference::{ConanReference, ReferenceError};
+
+#[derive(Debug, Error)]
+pub enum ConanfileError {
+    #[error("I/O error: {0}")]
+    Io(#[from] io::Error),
+    #[error("Line {line}: {message}")]
+    InvalidLine { line: usize, message: String },
+    #[error("Line {line}: {source}")]
+    InvalidReference { line: usize, source: ReferenceError },
+}
+
+/// Model of a `conanfile.txt` recipe
+///
+/// The `[requires]`, `[tool_requires]` (or Conan 1 `[build_requires]`), `[generators]`, `[options]`,
+/// `[imports]` and `[layout]` sections are parsed, other sections are kept as-is.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct ConanfileTxt {
+    pub requires: Vec<ConanReference>,
+    pub tool_requires: Vec<ConanReference>,
+    pub generators: Vec<String>,
+    pub options: IndexMap<String, String>,
+    pub imports: Vec<String>,
+    pub layout: Option<String>,
+    other_sections: IndexMap<String, Vec<String>>,
+    build_requires_section: bool,
+}
+
+impl ConanfileTxt {
+    pub fn new() -> Self {
+        ConanfileTxt::default()
+    }
+
+    pub fn from_file(path: &Path) -> Result<Self, ConanfileError> {
+        fs::read_to_string(path)?.parse()
+    }
+
+    pub fn write(&self, path: &Path) -> Result<(), ConanfileError> {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(path, self.to_string())?;
+        Ok(())
+    }
+
+    /// Add a requirement, replacing any requirement on the same package.
+    pub fn add_requires(&mut self, reference: ConanReference) -> &mut Self {
+        upsert_reference(&mut self.requires, reference);
+        self
+    }
+
+    /// Add a tool requirement, replacing any tool requirement on the same package.
+    pub fn add_tool_requires(&mut self, reference: ConanReference) -> &mut Self {
+        upsert_reference(&mut self.tool_requires, reference);
+        self
+    }
+
+    /// Remove the requirement on a package, returning it if it was present.
+    pub fn remove_requires(&mut self, name: &str) -> Option<ConanReference> {
+        let index = self.requires.iter().position(|x| x.name == name)?;
+        Some(self.requires.remove(index))
+    }
+
+    pub fn get_requires(&self, name: &str) -> Option<&ConanReference> {
+        self.requires.iter().find(|x| x.name == name)
+    }
+
+    pub fn add_generator(&mut self, generator: &str) -> &mut Self {
+        if !self.generators.iter().any(|x| x == generator) {
+            self.generators.push(generator.to_string());
+        }
+        self
+    }
+
+    /// Set an option, e.g. `set_option("zlib:shared", "True")` or `set_option("*:shared", "True")`.
+    pub fn set_option(&mut self, name: &str, value: &str) -> &mut Self {
+        self.options.insert(name.to_string(), value.to_string());
+        self
+    }
+
+    /// Write tool requirements in a `[build_requires]` section, understood by every Conan 1 version.
+    pub fn use_build_requires_section(&mut self, build_requires_section: bool) -> &mut Self {
+        self.build_requires_section = build_requires_section;
+        self
+    }
+}
+
+fn upsert_reference(references: &mut Vec<ConanReference>, reference: ConanReference) {
+    match references.iter_mut().find(|x| x.name == reference.name) {
+        Some(existing) => *existing = reference,
+        None => references.push(reference),
+    }
+}
+
+impl FromStr for ConanfileTxt {
+    type Err = ConanfileError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut conanfile = ConanfileTxt::new();
+        let mut section: Option<String> = None;
+
+        for (index, line) in s.lines().enumerate() {
+            let line_number = index + 1;
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            if line.starts_with('[') && line.ends_with(']') {
+                let name = line[1..line.len() - 1].trim().to_string();
+                if name == "build_requires" {
+                    conanfile.build_requires_section = true;
+                }
+                section = Some(name);
+                continue;
+            }
+
+            let parse_reference = |line: &str| {
+                line.parse::<ConanReference>()
+                    .map_err(|source| ConanfileError::InvalidReference {
+                        line: line_number,
+                        source,
+                    })
+            };
+
+            match section.as_deref() {
+                None => {
+                    return Err(ConanfileError::InvalidLine {
+                        line: line_number,
+                        message: "expected a section".to_string(),
+                    })
+                }
+                Some("requires") => conanfile.requires.push(parse_reference(line)?),
+                Some("tool_requires") | Some("build_requires") => conanfile.tool_requires.push(parse_reference(line)?),
+                Some("generators") => conanfile.generators.push(line.to_string()),
+                Some("options") => {
+                    let mut option = line.splitn(2, '=');
+                    let name = option.next().unwrap_or_default().trim();
+                    let value = option.next().ok_or_else(|| ConanfileError::InvalidLine {
+                        line: line_number,
+                        message: format!("expected an option value in \"{}\"", line),
+                    })?;
+                    conanfile.options.insert(name.to_string(), value.trim().to_string());
+                }
+                Some("imports") => conanfile.imports.push(line.to_string()),
+                Some("layout") => conanfile.layout = Some(line.to_string()),
+                Some(other) => conanfile
+                    .other_sections
+                    .entry(other.to_string())
+                    .or_default()
+                    .push(line.to_string()),
+            }
+        }
+
+        Ok(conanfile)
+    }
+}
+
+impl fmt::Display for ConanfileTxt {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let tool_requires_section = if self.build_requires_section {
+            "build_requires"
+        } else {
+            "tool_requires"
+        };
+
+        let mut sections: Vec<(&str, Vec<String>)> = vec![
+            ("requires", self.requires.iter().map(|x| x.to_string()).collect()),
+            (
+                tool_requires_section,
+                self.tool_requires.iter().map(|x| x.to_string()).collect(),
+            ),
+            ("generators", self.generators.clone()),
+            (
+                "options",
+                self.options
+                    .iter()
+                    .map(|(name, value)| format!("{}={}", name, value))
+                    .collect(),
+            ),
+            ("imports", self.imports.clone()),
+            ("layout", self.layout.iter().cloned().collect()),
+        ];
+        sections.extend(
+            self.other_sections
+                .iter()
+                .map(|(name, lines)| (name.as_str(), lines.clone())),
+        );
+
+        let mut first = true;
+        for (name, lines) in sections.iter().filter(|(_, lines)| !lines.is_empty()) {
+            if !first {
+                writeln!(f)?;
+            }
+            first = false;
+
+            writeln!(f, "[{}]", name)?;
+            for line in lines {
+                writeln!(f, "{}", line)?;
+            }
+        }
+        Ok(())
+    }
+}
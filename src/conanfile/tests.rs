@@ -0,0 +1,109 @@
+use super::*;
+
+const CONANFILE: &str = "\
+# Project dependencies
+[requires]
+zlib/1.2.13
+openssl/3.2.0@myorg/stable
+
+[build_requires]
+cmake/3.27.7
+
+[generators]
+CMakeDeps
+CMakeToolchain
+
+[options]
+zlib:shared=True
+openssl/*:no_asm = True
+
+[imports]
+bin, *.dll -> ./bin
+
+[layout]
+cmake_layout
+
+[test_requires]
+gtest/1.14.0
+";
+
+#[test]
+fn test_conanfile_txt_parse() -> Result<(), Box<dyn std::error::Error>> {
+    let conanfile: ConanfileTxt = CONANFILE.parse()?;
+    assert_eq!(conanfile.requires[0].to_string(), "zlib/1.2.13");
+    assert_eq!(conanfile.requires[1].user.as_deref(), Some("myorg"));
+    assert_eq!(conanfile.tool_requires[0].to_string(), "cmake/3.27.7");
+    assert_eq!(conanfile.generators, ["CMakeDeps", "CMakeToolchain"]);
+    assert_eq!(conanfile.options.get("zlib:shared").map(String::as_str), Some("True"));
+    assert_eq!(
+        conanfile.options.get("openssl/*:no_asm").map(String::as_str),
+        Some("True")
+    );
+    assert_eq!(conanfile.imports, ["bin, *.dll -> ./bin"]);
+    assert_eq!(conanfile.layout.as_deref(), Some("cmake_layout"));
+
+    let conanfile: ConanfileTxt = "[tool_requires]\nninja/1.11.1\n".parse()?;
+    assert_eq!(conanfile.tool_requires[0].name, "ninja");
+
+    Ok(())
+}
+
+#[test]
+fn test_conanfile_txt_parse_errors() {
+    assert!(matches!(
+        "zlib/1.2.13\n".parse::<ConanfileTxt>(),
+        Err(ConanfileError::InvalidLine { line: 1, .. })
+    ));
+    assert!(matches!(
+        "[requires]\nzlib\n".parse::<ConanfileTxt>(),
+        Err(ConanfileError::InvalidReference { line: 2, .. })
+    ));
+    assert!(matches!(
+        "[options]\n\nshared\n".parse::<ConanfileTxt>(),
+        Err(ConanfileError::InvalidLine { line: 3, .. })
+    ));
+}
+
+#[test]
+fn test_conanfile_txt_write() -> Result<(), Box<dyn std::error::Error>> {
+    let conanfile: ConanfileTxt = CONANFILE.parse()?;
+    assert_eq!(
+        conanfile.to_string(),
+        "[requires]\nzlib/1.2.13\nopenssl/3.2.0@myorg/stable\n\n\
+         [build_requires]\ncmake/3.27.7\n\n\
+         [generators]\nCMakeDeps\nCMakeToolchain\n\n\
+         [options]\nzlib:shared=True\nopenssl/*:no_asm=True\n\n\
+         [imports]\nbin, *.dll -> ./bin\n\n\
+         [layout]\ncmake_layout\n\n\
+         [test_requires]\ngtest/1.14.0\n"
+    );
+    assert_eq!(conanfile.to_string().parse::<ConanfileTxt>()?, conanfile);
+    assert_eq!(ConanfileTxt::new().to_string(), "");
+
+    Ok(())
+}
+
+#[test]
+fn test_conanfile_txt_override() -> Result<(), Box<dyn std::error::Error>> {
+    let mut conanfile: ConanfileTxt = CONANFILE.parse()?;
+    conanfile
+        .add_requires("zlib/1.3.1".parse()?)
+        .add_requires("fmt/10.2.1".parse()?)
+        .add_tool_requires("cmake/3.28.1".parse()?)
+        .add_generator("CMakeDeps")
+        .set_option("zlib:shared", "False")
+        .use_build_requires_section(false);
+
+    assert_eq!(conanfile.get_requires("zlib").unwrap().version, "1.3.1");
+    assert_eq!(conanfile.requires.len(), 3);
+    assert_eq!(conanfile.tool_requires.len(), 1);
+    assert_eq!(conanfile.generators.len(), 2);
+    assert_eq!(conanfile.options["zlib:shared"], "False");
+    assert!(conanfile.to_string().contains("[tool_requires]\ncmake/3.28.1\n"));
+
+    let removed = conanfile.remove_requires("openssl").unwrap();
+    assert_eq!(removed.name, "openssl");
+    assert!(conanfile.remove_requires("openssl").is_none());
+
+    Ok(())
+}
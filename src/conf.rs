@@ -0,0 +1,264 @@
+#[cfg(test)]
+mod tests;
+
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::Path;
+
+use lazy_static::lazy_static;
+use regex::Regex;
+use thiserror::Error;
+
+use crate::util::{package_pattern, ConanVersion};
+
+lazy_static! {
+    static ref REGEX_CONF_NAME: Regex = Regex::new(r"^[a-zA-Z0-9_.-]+:[a-zA-Z0-9_.-]+$").unwrap();
+    static ref REGEX_ENV_NAME: Regex = Regex::new(r"^[a-zA-Z_][a-zA-Z0-9_]*$").unwrap();
+}
+
+#[derive(Debug, Error, Clone, PartialEq)]
+pub enum ConfError {
+    #[error("Invalid conf name \"{0}\", expected e.g. tools.build:jobs")]
+    InvalidName(String),
+    #[error("Invalid environment variable name \"{0}\"")]
+    InvalidEnvName(String),
+    #[error("Invalid value for {0}: values can't span several lines")]
+    InvalidValue(String),
+}
+
+/// Value of a Conan configuration item
+///
+/// Conan evaluates conf values as Python literals: booleans are written `True`/`False`
+/// and lists as `["a", "b"]`, with their items quoted. Strings are passed as they are.
+#[derive(Clone, Debug, PartialEq)]
+pub enum ConfValue {
+    Bool(bool),
+    Integer(i64),
+    String(String),
+    List(Vec<String>),
+}
+
+impl fmt::Display for ConfValue {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ConfValue::Bool(true) => f.write_str("True"),
+            ConfValue::Bool(false) => f.write_str("False"),
+            ConfValue::Integer(value) => write!(f, "{}", value),
+            ConfValue::String(value) => f.write_str(value),
+            ConfValue::List(values) => {
+                let values: Vec<String> = values
+                    .iter()
+                    .map(|x| format!("\"{}\"", x.replace('\\', "\\\\").replace('"', "\\\"")))
+                    .collect();
+                write!(f, "[{}]", values.join(", "))
+            }
+        }
+    }
+}
+
+impl From<bool> for ConfValue {
+    fn from(value: bool) -> Self {
+        ConfValue::Bool(value)
+    }
+}
+
+impl From<i32> for ConfValue {
+    fn from(value: i32) -> Self {
+        ConfValue::Integer(value.into())
+    }
+}
+
+impl From<i64> for ConfValue {
+    fn from(value: i64) -> Self {
+        ConfValue::Integer(value)
+    }
+}
+
+impl From<&str> for ConfValue {
+    fn from(value: &str) -> Self {
+        ConfValue::String(value.to_string())
+    }
+}
+
+impl From<String> for ConfValue {
+    fn from(value: String) -> Self {
+        ConfValue::String(value)
+    }
+}
+
+impl From<&[&str]> for ConfValue {
+    fn from(values: &[&str]) -> Self {
+        ConfValue::List(values.iter().map(|x| x.to_string()).collect())
+    }
+}
+
+impl From<Vec<String>> for ConfValue {
+    fn from(values: Vec<String>) -> Self {
+        ConfValue::List(values)
+    }
+}
+
+/// Configuration item: `[package:]name=value`, e.g. `tools.cmake.cmaketoolchain:generator=Ninja`
+#[derive(Clone, Debug, PartialEq)]
+pub struct ConanConf {
+    pub package: Option<String>,
+    pub name: String,
+    pub value: ConfValue,
+}
+
+impl ConanConf {
+    pub fn new<V: Into<ConfValue>>(name: &str, value: V) -> Self {
+        ConanConf {
+            package: None,
+            name: name.to_string(),
+            value: value.into(),
+        }
+    }
+
+    /// Restrict the item to the packages matching a name or pattern.
+    pub fn for_package(mut self, package: &str) -> Self {
+        self.package = Some(package.to_string());
+        self
+    }
+
+    /// Format the item for the command line of the given Conan version.
+    pub fn to_cli_string(&self, conan_version: ConanVersion) -> String {
+        match &self.package {
+            Some(package) => format!(
+                "{}:{}={}",
+                package_pattern(package, conan_version),
+                self.name,
+                self.value
+            ),
+            None => format!("{}={}", self.name, self.value),
+        }
+    }
+
+    pub fn validate(&self) -> Result<(), ConfError> {
+        if !REGEX_CONF_NAME.is_match(&self.name) {
+            return Err(ConfError::InvalidName(self.name.clone()));
+        }
+        if self.value.to_string().contains(['\n', '\r'].as_ref()) {
+            return Err(ConfError::InvalidValue(self.name.clone()));
+        }
+        Ok(())
+    }
+}
+
+/// Environment variable set for the build of the packages: `[package:]NAME=value`
+#[derive(Clone, Debug, PartialEq)]
+pub struct ConanEnv {
+    pub package: Option<String>,
+    pub name: String,
+    pub value: String,
+}
+
+impl ConanEnv {
+    pub fn new(name: &str, value: &str) -> Self {
+        ConanEnv {
+            package: None,
+            name: name.to_string(),
+            value: value.to_string(),
+        }
+    }
+
+    /// Restrict the variable to the packages matching a name or pattern.
+    pub fn for_package(mut self, package: &str) -> Self {
+        self.package = Some(package.to_string());
+        self
+    }
+
+    /// Format the variable for the given Conan version: a `-e` argument for Conan 1,
+    /// a `[buildenv]` profile line for Conan 2.
+    pub fn to_cli_string(&self, conan_version: ConanVersion) -> String {
+        match &self.package {
+            Some(package) => format!(
+                "{}:{}={}",
+                package_pattern(package, conan_version),
+                self.name,
+                self.value
+            ),
+            None => format!("{}={}", self.name, self.value),
+        }
+    }
+
+    pub fn validate(&self) -> Result<(), ConfError> {
+        if !REGEX_ENV_NAME.is_match(&self.name) {
+            return Err(ConfError::InvalidEnvName(self.name.clone()));
+        }
+        if self.value.contains(['\n', '\r'].as_ref()) {
+            return Err(ConfError::InvalidValue(self.name.clone()));
+        }
+        Ok(())
+    }
+}
+
+/// Configuration and environment given to a command
+///
+/// Conan 2 has no `-e` argument, so the environment goes to the `[buildenv]` section
+/// of a profile written next to the command output, and composed with the host profile.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub(crate) struct ConfEnv {
+    pub(crate) conf: Vec<ConanConf>,
+    pub(crate) env: Vec<ConanEnv>,
+}
+
+impl ConfEnv {
+    /// Arguments for the given Conan version.
+    ///
+    /// `default_profile` adds the default host profile before the Conan 2 environment profile,
+    /// as Conan only loads it when no host profile is given.
+    pub(crate) fn args(
+        &self,
+        conan_version: ConanVersion,
+        env_profile: &str,
+        default_profile: bool,
+    ) -> Result<Vec<String>, ConfError> {
+        let mut args = Vec::new();
+        for conf in &self.conf {
+            conf.validate()?;
+            args.extend(vec!["-c".to_string(), conf.to_cli_string(conan_version)]);
+        }
+        for env in &self.env {
+            env.validate()?;
+        }
+
+        match conan_version {
+            ConanVersion::V1 => {
+                for env in &self.env {
+                    args.extend(vec!["-e".to_string(), env.to_cli_string(conan_version)]);
+                }
+            }
+            ConanVersion::V2 if !self.env.is_empty() => {
+                if default_profile {
+                    args.extend(vec!["--profile:host".to_string(), "default".to_string()]);
+                }
+                args.extend(vec!["--profile:host".to_string(), env_profile.to_string()]);
+            }
+            ConanVersion::V2 => {}
+        }
+        Ok(args)
+    }
+
+    /// Content of the Conan 2 environment profile.
+    pub(crate) fn env_profile(&self) -> String {
+        let mut profile = String::from("[buildenv]\n");
+        for env in &self.env {
+            profile.push_str(&env.to_cli_string(ConanVersion::V2));
+            profile.push('\n');
+        }
+        profile
+    }
+
+    /// Write the Conan 2 environment profile, if there is an environment.
+    pub(crate) fn write_env_profile(&self, conan_version: ConanVersion, path: &Path) -> io::Result<()> {
+        if conan_version == ConanVersion::V1 || self.env.is_empty() {
+            return Ok(());
+        }
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(path, self.env_profile())
+    }
+}
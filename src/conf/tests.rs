@@ -0,0 +1,75 @@
+use super::*;
+
+#[test]
+fn test_conf_to_cli_string() {
+    let conf = ConanConf::new("tools.cmake.cmaketoolchain:generator", "Ninja");
+    assert_eq!(
+        conf.to_cli_string(ConanVersion::V2),
+        "tools.cmake.cmaketoolchain:generator=Ninja"
+    );
+
+    let conf = ConanConf::new("tools.build:jobs", 4).for_package("zlib");
+    assert_eq!(conf.to_cli_string(ConanVersion::V1), "zlib:tools.build:jobs=4");
+    assert_eq!(conf.to_cli_string(ConanVersion::V2), "zlib/*:tools.build:jobs=4");
+
+    let conf = ConanConf::new("tools.build:cxxflags", &["-O2", "-DNAME=\"x\""][..]);
+    assert_eq!(
+        conf.to_cli_string(ConanVersion::V2),
+        r#"tools.build:cxxflags=["-O2", "-DNAME=\"x\""]"#
+    );
+    assert_eq!(
+        ConanConf::new("tools.system.package_manager:sudo", true).to_cli_string(ConanVersion::V2),
+        "tools.system.package_manager:sudo=True"
+    );
+}
+
+#[test]
+fn test_conf_validate() {
+    assert!(ConanConf::new("tools.build:jobs", 4).validate().is_ok());
+    assert_eq!(
+        ConanConf::new("jobs", 4).validate(),
+        Err(ConfError::InvalidName("jobs".to_string()))
+    );
+    assert_eq!(
+        ConanConf::new("user.team:note", "a\nb").validate(),
+        Err(ConfError::InvalidValue("user.team:note".to_string()))
+    );
+    assert_eq!(
+        ConanEnv::new("MY-VAR", "1").validate(),
+        Err(ConfError::InvalidEnvName("MY-VAR".to_string()))
+    );
+}
+
+#[test]
+fn test_conf_env_args() -> Result<(), ConfError> {
+    let conf_env = ConfEnv {
+        conf: vec![ConanConf::new("tools.build:jobs", 4)],
+        env: vec![
+            ConanEnv::new("CC", "clang"),
+            ConanEnv::new("CFLAGS", "-O2 -g").for_package("zlib"),
+        ],
+    };
+
+    assert_eq!(
+        conf_env.args(ConanVersion::V1, "buildenv", true)?,
+        ["-c", "tools.build:jobs=4", "-e", "CC=clang", "-e", "zlib:CFLAGS=-O2 -g"]
+    );
+    assert_eq!(
+        conf_env.args(ConanVersion::V2, "buildenv", true)?,
+        [
+            "-c",
+            "tools.build:jobs=4",
+            "--profile:host",
+            "default",
+            "--profile:host",
+            "buildenv"
+        ]
+    );
+    assert_eq!(
+        conf_env.args(ConanVersion::V2, "buildenv", false)?,
+        ["-c", "tools.build:jobs=4", "--profile:host", "buildenv"]
+    );
+    assert_eq!(conf_env.env_profile(), "[buildenv]\nCC=clang\nzlib/*:CFLAGS=-O2 -g\n");
+
+    Ok(())
+}
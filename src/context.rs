@@ -0,0 +1,469 @@
+#[cfg(test)]
+mod tests;
+
+use std::env;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, OnceLock};
+use std::thread;
+use std::time::Duration;
+
+use thiserror::Error;
+
+use crate::executor::{CommandOutput, Executor, Invocation, SystemExecutor, TimeoutError};
+use crate::home::{ConanHome, ConanHomeError};
+use crate::output::{ConanEvent, OutputHandler, OutputSink};
+use crate::retry::{FailureKind, RetryPolicy};
+use crate::util::{find_program, parse_version, ConanVersion};
+
+#[derive(Debug, Error)]
+pub enum ConanContextError {
+    #[error("Conan binary not found")]
+    ConanNotFound,
+    #[error(transparent)]
+    Home(#[from] ConanHomeError),
+    #[error("Failed to run Conan: {0}")]
+    Io(io::Error),
+    #[error(transparent)]
+    Timeout(TimeoutError),
+}
+
+impl From<io::Error> for ConanContextError {
+    fn from(error: io::Error) -> Self {
+        match error.get_ref().and_then(|inner| inner.downcast_ref::<TimeoutError>()) {
+            Some(timeout) => ConanContextError::Timeout(timeout.clone()),
+            None => ConanContextError::Io(error),
+        }
+    }
+}
+
+/// Conan log verbosity
+///
+/// Conan 2 takes it with `-v<level>`, Conan 1 through `CONAN_LOGGING_LEVEL`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Verbosity {
+    Quiet,
+    Error,
+    Warning,
+    Notice,
+    Status,
+    Verbose,
+    Debug,
+    Trace,
+}
+
+impl Verbosity {
+    /// Conan 2 argument.
+    fn arg(self) -> &'static str {
+        match self {
+            Verbosity::Quiet => "-vquiet",
+            Verbosity::Error => "-verror",
+            Verbosity::Warning => "-vwarning",
+            Verbosity::Notice => "-vnotice",
+            Verbosity::Status => "-vstatus",
+            Verbosity::Verbose => "-vverbose",
+            Verbosity::Debug => "-vdebug",
+            Verbosity::Trace => "-vtrace",
+        }
+    }
+
+    /// Conan 1 logging level, a Python logging level name.
+    fn logging_level(self) -> &'static str {
+        match self {
+            Verbosity::Quiet => "critical",
+            Verbosity::Error => "error",
+            Verbosity::Warning => "warning",
+            Verbosity::Notice | Verbosity::Status => "info",
+            Verbosity::Verbose | Verbosity::Debug | Verbosity::Trace => "debug",
+        }
+    }
+}
+
+/// Settings shared by the commands: Conan executable and version, home, environment,
+/// working directory, verbosity, output handling, and the executor running Conan
+///
+/// The executable and version are resolved when a command runs, unless set or detected
+/// beforehand with `detect`. A detected version is cached and shared by the clones of the context,
+/// so `conan --version` runs once.
+///
+/// ```no_run
+/// use conan::{ConanContext, ConanHome, InstallCommandBuilder, Verbosity};
+///
+/// let context = ConanContext::new()
+///     .with_home(ConanHome::in_out_dir().unwrap())
+///     .with_env("CONAN_NON_INTERACTIVE", "1")
+///     .with_verbosity(Verbosity::Verbose)
+///     .detect()
+///     .unwrap();
+/// let command = InstallCommandBuilder::new().with_context(context.clone()).build();
+/// ```
+#[derive(Clone, Debug)]
+pub struct ConanContext {
+    program: Option<PathBuf>,
+    conan_version: Option<ConanVersion>,
+    home: Option<ConanHome>,
+    env: Vec<(String, String)>,
+    working_dir: Option<PathBuf>,
+    verbosity: Option<Verbosity>,
+    timeout: Option<Duration>,
+    retry: Option<RetryPolicy>,
+    output_handler: Option<OutputHandler>,
+    log_file: Option<PathBuf>,
+    executor: Arc<dyn Executor>,
+    detected_version: Arc<OnceLock<String>>,
+}
+
+impl Default for ConanContext {
+    fn default() -> Self {
+        ConanContext {
+            program: None,
+            conan_version: None,
+            home: None,
+            env: Vec::new(),
+            working_dir: None,
+            verbosity: None,
+            timeout: None,
+            retry: None,
+            output_handler: None,
+            log_file: None,
+            executor: Arc::new(SystemExecutor),
+            detected_version: Arc::default(),
+        }
+    }
+}
+
+impl ConanContext {
+    pub fn new() -> Self {
+        ConanContext::default()
+    }
+
+    /// Conan executable, found with `find_program` by default.
+    pub fn with_program(mut self, program: &Path) -> Self {
+        self.program = Some(program.to_path_buf());
+        self.detected_version = Arc::default();
+        self
+    }
+
+    /// Conan version, detected from the executable by default.
+    pub fn with_conan_version(mut self, conan_version: ConanVersion) -> Self {
+        self.conan_version = Some(conan_version);
+        self
+    }
+
+    /// Run Conan with an isolated home.
+    pub fn with_home(mut self, home: ConanHome) -> Self {
+        self.home = Some(home);
+        self
+    }
+
+    /// Set an environment variable for the Conan process.
+    pub fn with_env(mut self, name: &str, value: &str) -> Self {
+        self.env.push((name.to_string(), value.to_string()));
+        self.detected_version = Arc::default();
+        self
+    }
+
+    /// Run Conan from this directory instead of the current one.
+    pub fn with_working_dir(mut self, working_dir: &Path) -> Self {
+        self.working_dir = Some(working_dir.to_path_buf());
+        self
+    }
+
+    pub fn with_verbosity(mut self, verbosity: Verbosity) -> Self {
+        self.verbosity = Some(verbosity);
+        self
+    }
+
+    /// Kill Conan and its child processes, and fail with `ConanContextError::Timeout`,
+    /// when a command runs longer than `timeout`.
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    /// Run the commands failing in a way accepted by the policy again, network failures by default.
+    pub fn with_retry(mut self, retry: RetryPolicy) -> Self {
+        self.retry = Some(retry);
+        self
+    }
+
+    /// Pass the events parsed from the output of the commands to `handler`, as they come.
+    ///
+    /// The handler may be called from several threads, e.g. `cargo_progress`.
+    pub fn with_output_handler<F>(mut self, handler: F) -> Self
+    where
+        F: Fn(&ConanEvent) + Send + Sync + 'static,
+    {
+        self.output_handler = Some(OutputHandler(Arc::new(handler)));
+        self
+    }
+
+    /// Append the complete output of the commands to this file, `OUT_DIR/conan.log` by default.
+    pub fn with_log_file(mut self, log_file: &Path) -> Self {
+        self.log_file = Some(log_file.to_path_buf());
+        self
+    }
+
+    /// Run Conan through this executor instead of spawning it, e.g. a `ScriptedExecutor` in tests.
+    pub fn with_executor<E: Executor + 'static>(mut self, executor: E) -> Self {
+        self.executor = Arc::new(executor);
+        self.detected_version = Arc::default();
+        self
+    }
+
+    /// Resolve the executable and its version now.
+    pub fn detect(mut self) -> Result<Self, ConanContextError> {
+        let program = self.program().ok_or(ConanContextError::ConanNotFound)?;
+        self.program = Some(program);
+        if self.conan_version.is_none() {
+            self.conan_version = self.detect_version();
+        }
+        Ok(self)
+    }
+
+    /// This context, with the settings explicitly set on `overrides` taking precedence.
+    ///
+    /// Builders run through their own context until given a shared one: what was set on the builder,
+    /// e.g. a timeout or a home, is kept over the shared context. The executor of the shared
+    /// context is kept, and the environment of `overrides` is appended to its own.
+    pub(crate) fn merged_with(mut self, overrides: ConanContext) -> ConanContext {
+        if let Some(program) = &overrides.program {
+            self = self.with_program(program);
+        }
+        if !overrides.env.is_empty() {
+            self.env.extend(overrides.env);
+            self.detected_version = Arc::default();
+        }
+        self.conan_version = overrides.conan_version.or(self.conan_version);
+        self.home = overrides.home.or(self.home);
+        self.working_dir = overrides.working_dir.or(self.working_dir);
+        self.verbosity = overrides.verbosity.or(self.verbosity);
+        self.timeout = overrides.timeout.or(self.timeout);
+        self.retry = overrides.retry.or(self.retry);
+        self.output_handler = overrides.output_handler.or(self.output_handler);
+        self.log_file = overrides.log_file.or(self.log_file);
+        self
+    }
+
+    pub fn program(&self) -> Option<PathBuf> {
+        self.program.clone().or_else(find_program)
+    }
+
+    /// Set or detected Conan version, falling back to Conan 1 syntax.
+    pub fn conan_version(&self) -> ConanVersion {
+        self.conan_version.or_else(|| self.detect_version()).unwrap_or_default()
+    }
+
+    fn detect_version(&self) -> Option<ConanVersion> {
+        ConanVersion::from_version(&self.version()?)
+    }
+
+    /// Full version of the Conan executable, e.g. `1.66.0`, from `conan --version`.
+    ///
+    /// The version is cached once detected, until the executable, environment or executor changes.
+    pub fn version(&self) -> Option<String> {
+        if let Some(version) = self.detected_version.get() {
+            return Some(version.clone());
+        }
+        let mut invocation = Invocation::new(&self.program()?, &["--version".to_string()]);
+        invocation.env = self.env.clone();
+        invocation.quiet = true;
+        let output = self.executor.execute(&invocation).ok()?;
+        let version = parse_version(&String::from_utf8_lossy(&output.stdout))?;
+        Some(self.detected_version.get_or_init(|| version).clone())
+    }
+
+    pub fn home(&self) -> Option<&ConanHome> {
+        self.home.as_ref()
+    }
+
+    pub fn env(&self) -> &[(String, String)] {
+        &self.env
+    }
+
+    pub fn working_dir(&self) -> Option<&Path> {
+        self.working_dir.as_deref()
+    }
+
+    /// Path as Conan sees it: relative paths are resolved against the working directory, if set.
+    pub(crate) fn resolve_path(&self, path: &Path) -> PathBuf {
+        match &self.working_dir {
+            Some(working_dir) => working_dir.join(path),
+            None => path.to_path_buf(),
+        }
+    }
+
+    pub fn verbosity(&self) -> Option<Verbosity> {
+        self.verbosity
+    }
+
+    pub fn timeout(&self) -> Option<Duration> {
+        self.timeout
+    }
+
+    pub fn retry(&self) -> Option<&RetryPolicy> {
+        self.retry.as_ref()
+    }
+
+    /// Log file set with `with_log_file`, or `conan.log` in `OUT_DIR` when run from a build script.
+    pub fn log_file(&self) -> Option<PathBuf> {
+        self.log_file
+            .clone()
+            .or_else(|| env::var_os("OUT_DIR").map(|out_dir| Path::new(&out_dir).join("conan.log")))
+    }
+
+    pub fn executor(&self) -> &dyn Executor {
+        self.executor.as_ref()
+    }
+
+    /// Conan invocation with the given arguments, set up with the context.
+    pub fn invocation(&self, args: &[String]) -> Result<Invocation, ConanContextError> {
+        let program = self.program().ok_or(ConanContextError::ConanNotFound)?;
+        let mut invocation = Invocation::new(&program, args);
+        if let Some(home) = &self.home {
+            invocation.env.extend(home.env_vars()?);
+        }
+        if let Some(verbosity) = self.verbosity {
+            match self.conan_version() {
+                ConanVersion::V1 => invocation
+                    .env
+                    .push(("CONAN_LOGGING_LEVEL".to_string(), verbosity.logging_level().to_string())),
+                ConanVersion::V2 => invocation.args.push(verbosity.arg().to_string()),
+            }
+        }
+        invocation.env.extend(self.env.iter().cloned());
+        invocation.working_dir = self.working_dir.clone();
+        invocation.timeout = self.timeout;
+        Ok(invocation)
+    }
+
+    /// Run an invocation through the executor.
+    ///
+    /// The isolated home, if any, is created and seeded first.
+    /// Failures accepted by the retry policy are run again.
+    pub fn run(&self, invocation: &Invocation) -> Result<CommandOutput, ConanContextError> {
+        self.prepare_home()?;
+        let mut attempt = 1;
+        loop {
+            let result = self.run_once(invocation);
+            match self.retry_delay(invocation, attempt, &result) {
+                Some(delay) => thread::sleep(delay),
+                None => return result,
+            }
+            attempt += 1;
+        }
+    }
+
+    pub(crate) fn run_once(&self, invocation: &Invocation) -> Result<CommandOutput, ConanContextError> {
+        let sink = match self.output_sink(invocation)? {
+            Some(sink) => sink,
+            None => return Ok(self.executor.execute(invocation)?),
+        };
+        let output = self
+            .executor
+            .execute_streaming(invocation, &|stream, line| sink.line(stream, line))?;
+        sink.finish(&output);
+        Ok(output)
+    }
+
+    /// Sink of the output of an invocation, if it is handled or logged.
+    fn output_sink(&self, invocation: &Invocation) -> Result<Option<OutputSink>, ConanContextError> {
+        let log_file = self.log_file();
+        if self.output_handler.is_none() && log_file.is_none() {
+            return Ok(None);
+        }
+        let sink = OutputSink::new(self.output_handler.clone(), log_file.as_deref(), invocation)?;
+        Ok(Some(sink))
+    }
+
+    /// Run Conan with the given arguments.
+    pub fn execute(&self, args: &[String]) -> Result<CommandOutput, ConanContextError> {
+        self.run(&self.invocation(args)?)
+    }
+
+    /// Run Conan with the given arguments from an async runtime, with `tokio::process`.
+    ///
+    /// The invocation is set up on the call, Conan runs when the future is polled, and is killed
+    /// if the future is dropped before it exits. Output is forwarded as it comes, like `execute`,
+    /// but the executor of the context is not used.
+    #[cfg(feature = "tokio")]
+    pub fn execute_async(
+        &self,
+        args: &[String],
+    ) -> impl std::future::Future<Output = Result<CommandOutput, ConanContextError>> + Send + 'static {
+        let context = self.clone();
+        let invocation = self.invocation(args);
+        async move {
+            let invocation = invocation?;
+            let home_context = context.clone();
+            // NOTE: Seeding the home runs Conan synchronously, once
+            tokio::task::spawn_blocking(move || home_context.prepare_home())
+                .await
+                .map_err(io::Error::from)??;
+
+            let mut attempt = 1;
+            loop {
+                let result = context.run_once_async(&invocation).await;
+                match context.retry_delay(&invocation, attempt, &result) {
+                    Some(delay) => tokio::time::sleep(delay).await,
+                    None => return result,
+                }
+                attempt += 1;
+            }
+        }
+    }
+
+    #[cfg(feature = "tokio")]
+    async fn run_once_async(&self, invocation: &Invocation) -> Result<CommandOutput, ConanContextError> {
+        let sink = self.output_sink(invocation)?.map(Arc::new);
+        let line_sink = sink.clone();
+        let on_line = Arc::new(move |stream, line: &str| {
+            if let Some(sink) = &line_sink {
+                sink.line(stream, line);
+            }
+        });
+        let output = crate::executor::execute_async(invocation, on_line).await?;
+        if let Some(sink) = &sink {
+            sink.finish(&output);
+        }
+        Ok(output)
+    }
+
+    /// Delay before running a failed invocation again, if the retry policy accepts the failure.
+    fn retry_delay(
+        &self,
+        invocation: &Invocation,
+        attempt: u32,
+        result: &Result<CommandOutput, ConanContextError>,
+    ) -> Option<Duration> {
+        let retry = self.retry.as_ref()?;
+        let kind = match result {
+            Ok(output) if output.status.success() => return None,
+            Ok(output) => FailureKind::classify(output),
+            Err(ConanContextError::Timeout(_)) => FailureKind::Timeout,
+            Err(_) => return None,
+        };
+        if !retry.should_retry(attempt, kind) {
+            return None;
+        }
+
+        let delay = retry.backoff(attempt);
+        let command = invocation.args.first().map_or("", String::as_str);
+        println!(
+            "cargo:warning=conan {} failed with a {} error (attempt {}/{}), retrying in {:?}",
+            command,
+            kind,
+            attempt,
+            retry.max_attempts(),
+            delay
+        );
+        Some(delay)
+    }
+
+    fn prepare_home(&self) -> Result<(), ConanContextError> {
+        if let Some(home) = &self.home {
+            home.prepare(self)?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,315 @@
+use super::*;
+use crate::executor::{RecordingExecutor, ScriptedExecutor, ScriptedResponse};
+use std::time::Duration;
+
+#[test]
+fn test_context_invocation() -> Result<(), ConanContextError> {
+    let context = ConanContext::new()
+        .with_program(Path::new("/usr/bin/conan"))
+        .with_conan_version(ConanVersion::V2)
+        .with_env("CONAN_NON_INTERACTIVE", "1")
+        .with_working_dir(Path::new("/tmp"))
+        .with_verbosity(Verbosity::Verbose);
+
+    let invocation = context.invocation(&["search".to_string(), "zlib".to_string()])?;
+    assert_eq!(invocation.program, Path::new("/usr/bin/conan"));
+    assert_eq!(invocation.args, ["search", "zlib", "-vverbose"]);
+    assert_eq!(invocation.env, [("CONAN_NON_INTERACTIVE".to_string(), "1".to_string())]);
+    assert_eq!(invocation.working_dir.as_deref(), Some(Path::new("/tmp")));
+
+    // NOTE: Conan 1 takes the verbosity from the environment
+    let invocation = context
+        .with_conan_version(ConanVersion::V1)
+        .invocation(&["search".to_string()])?;
+    assert_eq!(invocation.args, ["search"]);
+    assert!(invocation
+        .env
+        .contains(&("CONAN_LOGGING_LEVEL".to_string(), "debug".to_string())));
+
+    Ok(())
+}
+
+#[test]
+fn test_context_execute_with_home() -> Result<(), ConanContextError> {
+    let executor = RecordingExecutor::new();
+    let home_dir = std::env::temp_dir().join(format!("conan-rs-context-home-{}", std::process::id()));
+    let context = ConanContext::new()
+        .with_program(Path::new("conan"))
+        .with_conan_version(ConanVersion::V1)
+        .with_home(ConanHome::new(&home_dir))
+        .with_executor(executor.clone());
+
+    let output = context.execute(&["remote".to_string(), "list".to_string()])?;
+    assert!(output.status.success());
+    let invocations = executor.invocations();
+    assert_eq!(invocations.len(), 1);
+    assert_eq!(invocations[0].args, ["remote", "list"]);
+    assert!(invocations[0]
+        .env
+        .contains(&("CONAN_USER_HOME".to_string(), home_dir.to_str().unwrap().to_string())));
+
+    std::fs::remove_dir_all(&home_dir).unwrap();
+    Ok(())
+}
+
+#[test]
+fn test_context_detect_with_executor() -> Result<(), ConanContextError> {
+    let executor = ScriptedExecutor::new().on(
+        &["--version"],
+        ScriptedResponse::success().with_stdout("Conan version 2.0.5\n"),
+    );
+    let context = ConanContext::new()
+        .with_program(Path::new("conan"))
+        .with_executor(executor.clone())
+        .detect()?;
+    assert_eq!(context.conan_version(), ConanVersion::V2);
+    assert!(executor.invocations()[0].quiet);
+
+    // NOTE: Without detect, the version is detected once and shared by the clones
+    let context = ConanContext::new()
+        .with_program(Path::new("conan"))
+        .with_executor(executor.clone());
+    assert_eq!(context.conan_version(), ConanVersion::V2);
+    assert_eq!(context.clone().version().as_deref(), Some("2.0.5"));
+    assert_eq!(executor.invocations().len(), 2);
+    let context = context.with_env("CONAN_HOME", "/tmp/conan");
+    assert_eq!(context.conan_version(), ConanVersion::V2);
+    assert_eq!(executor.invocations().len(), 3);
+
+    Ok(())
+}
+
+#[cfg(unix)]
+#[test]
+fn test_context_detect() -> Result<(), ConanContextError> {
+    use crate::test_support::FakeConan;
+
+    let dir = std::env::temp_dir().join(format!("conan-rs-context-{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+    let fake = FakeConan::new(&dir).with_version("2.0.5").build().unwrap();
+    let program = fake.program().to_path_buf();
+
+    let context = ConanContext::new().with_program(&program).detect()?;
+    assert_eq!(context.program(), Some(program.clone()));
+    assert_eq!(context.conan_version(), ConanVersion::V2);
+
+    // NOTE: A set version is kept
+    let context = ConanContext::new()
+        .with_program(&program)
+        .with_conan_version(ConanVersion::V1)
+        .detect()?;
+    assert_eq!(context.conan_version(), ConanVersion::V1);
+    assert_eq!(fake.invocations().unwrap().len(), 1);
+
+    std::fs::remove_dir_all(&dir).unwrap();
+    Ok(())
+}
+
+#[cfg(all(unix, feature = "tokio"))]
+#[tokio::test]
+async fn test_context_execute_async() -> Result<(), ConanContextError> {
+    let dir = std::env::temp_dir().join(format!("conan-rs-context-async-{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(&dir).unwrap();
+
+    let context = ConanContext::new()
+        .with_program(Path::new("/bin/sh"))
+        .with_conan_version(ConanVersion::V2)
+        .with_working_dir(&dir);
+    let output = context
+        .execute_async(&["-c".to_string(), "echo installed".to_string()])
+        .await?;
+    assert!(output.status.success());
+    assert_eq!(output.stdout, b"installed\n");
+
+    // NOTE: Dropping the future kills the process before it writes its marker
+    let slow = context.execute_async(&["-c".to_string(), "sleep 1; touch marker".to_string()]);
+    assert!(tokio::time::timeout(Duration::from_millis(100), slow).await.is_err());
+    tokio::time::sleep(Duration::from_millis(1500)).await;
+    assert!(!dir.join("marker").exists());
+
+    std::fs::remove_dir_all(&dir).unwrap();
+    Ok(())
+}
+
+#[test]
+fn test_context_timeout() {
+    let executor = ScriptedExecutor::new().on(&["install"], ScriptedResponse::hanging().with_stderr("Downloading"));
+    let context = ConanContext::new()
+        .with_program(Path::new("conan"))
+        .with_conan_version(ConanVersion::V2)
+        .with_timeout(Duration::from_secs(30))
+        .with_executor(executor.clone());
+
+    match context.execute(&["install".to_string()]) {
+        Err(ConanContextError::Timeout(timeout)) => {
+            assert_eq!(timeout.timeout, Duration::from_secs(30));
+            assert_eq!(timeout.stderr, b"Downloading");
+        }
+        result => panic!("unexpected result {:?}", result),
+    }
+    assert_eq!(executor.invocations()[0].timeout, Some(Duration::from_secs(30)));
+}
+
+#[cfg(all(unix, feature = "tokio"))]
+#[tokio::test]
+async fn test_context_execute_async_timeout() {
+    let context = ConanContext::new()
+        .with_program(Path::new("/bin/sh"))
+        .with_conan_version(ConanVersion::V2)
+        .with_timeout(Duration::from_millis(300));
+
+    let start = std::time::Instant::now();
+    let result = context
+        .execute_async(&["-c".to_string(), "echo partial; sleep 10".to_string()])
+        .await;
+    assert!(start.elapsed() < Duration::from_secs(5));
+    match result {
+        Err(ConanContextError::Timeout(timeout)) => assert_eq!(timeout.stdout, b"partial\n"),
+        result => panic!("unexpected result {:?}", result),
+    }
+}
+
+#[test]
+fn test_context_retry() -> Result<(), ConanContextError> {
+    use crate::retry::{FailureKind, RetryPolicy};
+
+    let network_error = "ERROR: 503 Server Error: Service Unavailable for url: https://center.conan.io";
+    let executor = ScriptedExecutor::new()
+        .on(&["install"], ScriptedResponse::failure(1).with_stderr(network_error))
+        .on(&["install"], ScriptedResponse::failure(1).with_stderr(network_error))
+        .on(&["install"], ScriptedResponse::success())
+        .on(
+            &["create"],
+            ScriptedResponse::failure(1).with_stderr("ERROR: Unable to find 'zlib/1.2.99'"),
+        );
+    let retry = RetryPolicy::new(3).with_backoff(Duration::ZERO, Duration::ZERO);
+    let context = ConanContext::new()
+        .with_program(Path::new("conan"))
+        .with_conan_version(ConanVersion::V2)
+        .with_retry(retry.clone())
+        .with_executor(executor.clone());
+
+    assert!(context.execute(&["install".to_string()])?.status.success());
+    assert_eq!(executor.invocations().len(), 3);
+
+    // NOTE: Recipe errors are not retried
+    assert!(!context.execute(&["create".to_string()])?.status.success());
+    assert_eq!(executor.invocations().len(), 4);
+
+    // NOTE: The last failure is returned once the attempts are exhausted
+    let executor = ScriptedExecutor::new().on(&["upload"], ScriptedResponse::hanging());
+    let context = context
+        .with_timeout(Duration::from_secs(1))
+        .with_retry(retry.retry_if(|kind| kind == FailureKind::Timeout))
+        .with_executor(executor.clone());
+    assert!(matches!(
+        context.execute(&["upload".to_string()]),
+        Err(ConanContextError::Timeout(_))
+    ));
+    assert_eq!(executor.invocations().len(), 3);
+
+    Ok(())
+}
+
+#[test]
+fn test_context_output_handler_and_log() -> Result<(), ConanContextError> {
+    use crate::output::ConanEvent;
+    use std::sync::Mutex;
+
+    let log_file = std::env::temp_dir()
+        .join(format!("conan-rs-context-log-{}", std::process::id()))
+        .join("conan.log");
+    let _ = std::fs::remove_file(&log_file);
+    let executor = ScriptedExecutor::new().on(
+        &["install"],
+        ScriptedResponse::success()
+            .with_stdout("zlib/1.3.1: Retrieving package abc123 from remote 'conancenter'\n")
+            .with_stderr("WARN: slow remote\n"),
+    );
+    let events = Arc::new(Mutex::new(Vec::new()));
+    let handled = events.clone();
+    let context = ConanContext::new()
+        .with_program(Path::new("conan"))
+        .with_conan_version(ConanVersion::V2)
+        .with_output_handler(move |event| handled.lock().unwrap().push(event.clone()))
+        .with_log_file(&log_file)
+        .with_executor(executor);
+    assert_eq!(context.log_file(), Some(log_file.clone()));
+
+    context.execute(&["install".to_string(), ".".to_string()])?;
+    let events = events.lock().unwrap();
+    assert_eq!(events.len(), 3);
+    assert_eq!(events[0], ConanEvent::DownloadStarted("zlib/1.3.1".to_string()));
+    assert_eq!(events[1], ConanEvent::Warning("WARN: slow remote".to_string()));
+    match &events[2] {
+        ConanEvent::Finished(summary) => {
+            assert!(summary.success);
+            assert_eq!(summary.warnings.len(), 1);
+        }
+        event => panic!("unexpected event {:?}", event),
+    }
+
+    let log = std::fs::read_to_string(&log_file).unwrap();
+    assert!(log.starts_with("$ conan install .\nzlib/1.3.1: Retrieving package abc123"));
+    assert!(log.contains("WARN: slow remote\n"));
+
+    std::fs::remove_dir_all(log_file.parent().unwrap()).unwrap();
+    Ok(())
+}
+
+#[cfg(all(unix, feature = "tokio"))]
+#[tokio::test]
+async fn test_context_execute_async_output_handler() -> Result<(), ConanContextError> {
+    use crate::output::ConanEvent;
+    use std::sync::Mutex;
+
+    let events = Arc::new(Mutex::new(Vec::new()));
+    let handled = events.clone();
+    let context = ConanContext::new()
+        .with_program(Path::new("/bin/sh"))
+        .with_conan_version(ConanVersion::V2)
+        .with_output_handler(move |event| handled.lock().unwrap().push(event.clone()));
+    context
+        .execute_async(&["-c".to_string(), "echo 'fmt/10.2.1: Building from source'".to_string()])
+        .await?;
+
+    let events = events.lock().unwrap();
+    assert_eq!(events[0], ConanEvent::BuildingFromSource("fmt/10.2.1".to_string()));
+    assert!(matches!(&events[1], ConanEvent::Finished(summary) if summary.built == ["fmt/10.2.1"]));
+    Ok(())
+}
+
+#[test]
+fn test_context_merged_with() -> Result<(), ConanContextError> {
+    let executor = RecordingExecutor::new();
+    let shared = ConanContext::new()
+        .with_program(Path::new("conan"))
+        .with_conan_version(ConanVersion::V2)
+        .with_env("CONAN_NON_INTERACTIVE", "1")
+        .with_timeout(Duration::from_secs(600))
+        .with_executor(executor.clone());
+    let builder = ConanContext::new()
+        .with_env("CONAN_CPU_COUNT", "2")
+        .with_timeout(Duration::from_secs(60))
+        .with_working_dir(Path::new("/tmp"));
+
+    let context = shared.merged_with(builder);
+    assert_eq!(context.program(), Some(PathBuf::from("conan")));
+    assert_eq!(context.conan_version(), ConanVersion::V2);
+    assert_eq!(context.timeout(), Some(Duration::from_secs(60)));
+    assert_eq!(context.working_dir(), Some(Path::new("/tmp")));
+
+    context.execute(&["search".to_string()])?;
+    let invocations = executor.invocations();
+    assert_eq!(invocations.len(), 1);
+    assert_eq!(
+        invocations[0].env,
+        [
+            ("CONAN_NON_INTERACTIVE".to_string(), "1".to_string()),
+            ("CONAN_CPU_COUNT".to_string(), "2".to_string()),
+        ]
+    );
+
+    Ok(())
+}
@@ -0,0 +1,465 @@
+#[cfg(test)]
+mod tests;
+
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::ExitStatus;
+use std::time::Duration;
+
+use serde_json::Value;
+use thiserror::Error;
+
+use crate::conf::{ConanConf, ConanEnv, ConfEnv, ConfError, ConfValue};
+use crate::context::{ConanContext, ConanContextError};
+use crate::home::ConanHome;
+use crate::install::{build_info::BuildSettings, BuildPolicy};
+use crate::reference::{ConanReference, PackageReference, ReferenceError};
+use crate::util::ConanVersion;
+
+#[derive(Debug, Error)]
+pub enum ConanCreateError {
+    #[error("Conan binary not found")]
+    ConanNotFound,
+    #[error("Invalid Unicode in path")]
+    InvalidUnicodeInPath,
+    #[error("Conan 1 requires a package name to override the version")]
+    MissingName,
+    #[error("Conan 1 requires a package version to override the name")]
+    MissingVersion,
+    #[error("Conan 1 requires both a user and a channel")]
+    IncompleteUserChannel,
+    #[error("Build policy not supported by this Conan version")]
+    UnsupportedBuildPolicy,
+    #[error("Output directory not found")]
+    OutputDirNotFound,
+    #[error("Conan create failed with {0}")]
+    CommandFailed(ExitStatus),
+    #[error("I/O error: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("Failed to parse Conan JSON output: {0}")]
+    Json(#[from] serde_json::Error),
+    #[error("Unexpected Conan JSON output: {0}")]
+    InvalidOutput(String),
+    #[error(transparent)]
+    InvalidReference(#[from] ReferenceError),
+    #[error(transparent)]
+    InvalidConf(#[from] ConfError),
+    #[error(transparent)]
+    Context(#[from] ConanContextError),
+}
+
+/// Package created by "conan create"
+#[derive(Clone, Debug, PartialEq)]
+pub struct CreatedPackage {
+    pub reference: ConanReference,
+    pub package_id: String,
+}
+
+impl CreatedPackage {
+    pub fn package_reference(&self) -> Result<PackageReference, ReferenceError> {
+        PackageReference::new(self.reference.clone(), &self.package_id)
+    }
+
+    /// Parse the file written by `conan create --json` (Conan 1).
+    pub fn from_v1_json(json: &str) -> Result<Self, ConanCreateError> {
+        let value: Value = serde_json::from_str(json)?;
+        let installed = value["installed"]
+            .as_array()
+            .ok_or_else(|| ConanCreateError::InvalidOutput("missing \"installed\" list".to_string()))?;
+
+        // NOTE: Dependencies are listed too, the created recipe is the one that is not a dependency
+        let created = installed
+            .iter()
+            .find(|x| x["recipe"]["dependency"] == Value::Bool(false))
+            .or_else(|| installed.last())
+            .ok_or_else(|| ConanCreateError::InvalidOutput("no installed recipe".to_string()))?;
+
+        let reference = created["recipe"]["id"]
+            .as_str()
+            .ok_or_else(|| ConanCreateError::InvalidOutput("missing recipe id".to_string()))?;
+        let package_id = created["packages"][0]["id"]
+            .as_str()
+            .ok_or_else(|| ConanCreateError::InvalidOutput("missing package id".to_string()))?;
+
+        Ok(CreatedPackage {
+            reference: reference.parse()?,
+            package_id: package_id.to_string(),
+        })
+    }
+
+    /// Parse the graph printed by `conan create --format=json` (Conan 2).
+    pub fn from_v2_json(json: &str) -> Result<Self, ConanCreateError> {
+        let value: Value = serde_json::from_str(json)?;
+        let nodes = value["graph"]["nodes"]
+            .as_object()
+            .ok_or_else(|| ConanCreateError::InvalidOutput("missing graph nodes".to_string()))?;
+
+        // NOTE: Node "0" is the consumer (test package or virtual), the created package is its direct dependency
+        let created_id = nodes
+            .get("0")
+            .and_then(|root| root["dependencies"].as_object())
+            .and_then(|deps| {
+                deps.iter()
+                    .filter(|(_, dep)| dep["direct"] == Value::Bool(true))
+                    .filter_map(|(id, _)| id.parse::<u32>().ok())
+                    .min()
+            })
+            .map(|id| id.to_string())
+            .unwrap_or_else(|| "1".to_string());
+
+        let created = nodes
+            .get(&created_id)
+            .ok_or_else(|| ConanCreateError::InvalidOutput(format!("missing node {}", created_id)))?;
+
+        let reference = created["ref"]
+            .as_str()
+            .ok_or_else(|| ConanCreateError::InvalidOutput("missing node reference".to_string()))?;
+        let package_id = created["package_id"]
+            .as_str()
+            .ok_or_else(|| ConanCreateError::InvalidOutput("missing package id".to_string()))?;
+
+        Ok(CreatedPackage {
+            reference: reference.parse()?,
+            package_id: package_id.to_string(),
+        })
+    }
+}
+
+/// "conan create" command runner
+pub struct CreateCommand<'a> {
+    recipe_path: PathBuf,
+    name: Option<&'a str>,
+    version: Option<&'a str>,
+    user: Option<&'a str>,
+    channel: Option<&'a str>,
+    profile_host: Option<&'a str>,
+    profile_build: Option<&'a str>,
+    build_settings: BuildSettings,
+    build_options: Vec<&'a str>,
+    conf_env: ConfEnv,
+    build_policy: Option<BuildPolicy>,
+    test_folder: Option<PathBuf>,
+    output_dir: Option<PathBuf>,
+    context: ConanContext,
+}
+
+/// "conan create" command arguments builder
+pub struct CreateCommandBuilder<'a> {
+    recipe_path: PathBuf,
+    name: Option<&'a str>,
+    version: Option<&'a str>,
+    user: Option<&'a str>,
+    channel: Option<&'a str>,
+    profile_host: Option<&'a str>,
+    profile_build: Option<&'a str>,
+    build_settings: Option<BuildSettings>,
+    build_options: Vec<&'a str>,
+    conf_env: ConfEnv,
+    build_policy: Option<BuildPolicy>,
+    test_folder: Option<PathBuf>,
+    output_dir: Option<PathBuf>,
+    context: ConanContext,
+}
+
+impl<'a> Default for CreateCommandBuilder<'a> {
+    fn default() -> Self {
+        CreateCommandBuilder {
+            recipe_path: PathBuf::from("."),
+            name: None,
+            version: None,
+            user: None,
+            channel: None,
+            profile_host: None,
+            profile_build: None,
+            build_settings: None,
+            build_options: Vec::new(),
+            conf_env: ConfEnv::default(),
+            build_policy: None,
+            test_folder: None,
+            output_dir: None,
+            context: ConanContext::default(),
+        }
+    }
+}
+
+impl<'a> CreateCommandBuilder<'a> {
+    pub fn new() -> CreateCommandBuilder<'a> {
+        CreateCommandBuilder::default()
+    }
+
+    pub fn recipe_path(mut self, recipe_path: &Path) -> Self {
+        self.recipe_path = recipe_path.to_path_buf();
+        self
+    }
+
+    /// Override the name, version, user and channel declared in the recipe.
+    pub fn with_reference(mut self, reference: &'a ConanReference) -> Self {
+        self.name = Some(&reference.name);
+        self.version = Some(&reference.version);
+        self.user = reference.user.as_deref();
+        self.channel = reference.channel.as_deref();
+        self
+    }
+
+    /// Override the package name declared in the recipe.
+    pub fn with_name(mut self, name: &'a str) -> Self {
+        self.name = Some(name);
+        self
+    }
+
+    /// Override the package version declared in the recipe.
+    pub fn with_version(mut self, version: &'a str) -> Self {
+        self.version = Some(version);
+        self
+    }
+
+    pub fn with_user(mut self, user: &'a str) -> Self {
+        self.user = Some(user);
+        self
+    }
+
+    pub fn with_channel(mut self, channel: &'a str) -> Self {
+        self.channel = Some(channel);
+        self
+    }
+
+    /// Apply the specified profile to the host machine.
+    pub fn with_profile(self, profile: &'a str) -> Self {
+        self.with_host_profile(profile)
+    }
+
+    /// Apply the specified profile to the host machine.
+    pub fn with_host_profile(mut self, profile: &'a str) -> Self {
+        self.profile_host = Some(profile);
+        self
+    }
+
+    /// Apply the specified profile to the build machine.
+    pub fn with_build_profile(mut self, profile: &'a str) -> Self {
+        self.profile_build = Some(profile);
+        self
+    }
+
+    pub fn build_settings(mut self, build_settings: BuildSettings) -> Self {
+        self.build_settings = Some(build_settings);
+        self
+    }
+
+    pub fn build_policy<P: Into<BuildPolicy>>(mut self, build_policy: P) -> Self {
+        self.build_policy = Some(build_policy.into());
+        self
+    }
+
+    pub fn with_options(mut self, opts: &[&'a str]) -> Self {
+        self.build_options.extend(opts);
+        self
+    }
+
+    /// Set a configuration item, e.g. `with_conf("tools.cmake.cmaketoolchain:generator", "Ninja")`.
+    pub fn with_conf<V: Into<ConfValue>>(mut self, name: &str, value: V) -> Self {
+        self.conf_env.conf.push(ConanConf::new(name, value));
+        self
+    }
+
+    /// Set a configuration item for the packages matching a name or pattern.
+    pub fn with_package_conf<V: Into<ConfValue>>(mut self, package: &str, name: &str, value: V) -> Self {
+        self.conf_env
+            .conf
+            .push(ConanConf::new(name, value).for_package(package));
+        self
+    }
+
+    /// Set an environment variable for the package builds, e.g. `with_env("CC", "clang")`.
+    ///
+    /// Conan 1 takes it with `-e`, Conan 2 from the `[buildenv]` of a generated profile.
+    pub fn with_env(mut self, name: &str, value: &str) -> Self {
+        self.conf_env.env.push(ConanEnv::new(name, value));
+        self
+    }
+
+    /// Set an environment variable for the builds of the packages matching a name or pattern.
+    pub fn with_package_env(mut self, package: &str, name: &str, value: &str) -> Self {
+        self.conf_env.env.push(ConanEnv::new(name, value).for_package(package));
+        self
+    }
+
+    /// Folder of the test package, relative to the recipe.
+    pub fn with_test_folder(mut self, test_folder: &Path) -> Self {
+        self.test_folder = Some(test_folder.to_path_buf());
+        self
+    }
+
+    /// Directory receiving the Conan 1 JSON report, defaults to `OUT_DIR`.
+    pub fn output_dir(mut self, output_dir: &Path) -> Self {
+        self.output_dir = Some(output_dir.to_path_buf());
+        self
+    }
+
+    /// Run `conan create` through a shared context, keeping the timeout, home and version set on this builder.
+    pub fn with_context(mut self, context: ConanContext) -> Self {
+        self.context = context.merged_with(self.context);
+        self
+    }
+
+    /// Kill Conan and fail with a timeout error if it runs longer than `timeout`.
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.context = self.context.with_timeout(timeout);
+        self
+    }
+
+    /// Run Conan with an isolated home, e.g. `ConanHome::in_out_dir()?`.
+    pub fn with_conan_home(mut self, conan_home: ConanHome) -> Self {
+        self.context = self.context.with_home(conan_home);
+        self
+    }
+
+    pub fn conan_version(mut self, conan_version: ConanVersion) -> Self {
+        self.context = self.context.with_conan_version(conan_version);
+        self
+    }
+
+    pub fn build(self) -> CreateCommand<'a> {
+        CreateCommand {
+            recipe_path: self.recipe_path,
+            name: self.name,
+            version: self.version,
+            user: self.user,
+            channel: self.channel,
+            profile_host: self.profile_host,
+            profile_build: self.profile_build,
+            build_settings: self.build_settings.unwrap_or_default(),
+            build_options: self.build_options,
+            conf_env: self.conf_env,
+            build_policy: self.build_policy,
+            test_folder: self.test_folder,
+            output_dir: self.output_dir,
+            context: self.context,
+        }
+    }
+}
+
+impl<'a> CreateCommand<'a> {
+    pub fn args(&self) -> Result<Vec<String>, ConanCreateError> {
+        let conan_version = self.context.conan_version();
+        let mut args: Vec<String> = vec!["create".to_string(), path_str(&self.recipe_path)?.to_string()];
+
+        match conan_version {
+            ConanVersion::V1 => {
+                let user_channel = match (self.user, self.channel) {
+                    (Some(user), Some(channel)) => format!("{}/{}", user, channel),
+                    (None, None) => String::new(),
+                    _ => return Err(ConanCreateError::IncompleteUserChannel),
+                };
+                match (self.name, self.version) {
+                    (Some(name), Some(version)) => args.push(format!("{}/{}@{}", name, version, user_channel)),
+                    (None, Some(_)) => return Err(ConanCreateError::MissingName),
+                    (Some(_), None) => return Err(ConanCreateError::MissingVersion),
+                    _ if !user_channel.is_empty() => args.push(user_channel),
+                    _ => {}
+                }
+            }
+            ConanVersion::V2 => {
+                let fields = [
+                    ("--name", self.name),
+                    ("--version", self.version),
+                    ("--user", self.user),
+                    ("--channel", self.channel),
+                ];
+                for (flag, value) in fields.iter() {
+                    if let Some(value) = value {
+                        args.extend(vec![flag.to_string(), value.to_string()]);
+                    }
+                }
+            }
+        }
+
+        if let Some(profile) = &self.profile_host {
+            args.extend(vec!["--profile:host".to_string(), profile.to_string()]);
+        }
+
+        if let Some(profile) = &self.profile_build {
+            args.extend(vec!["--profile:build".to_string(), profile.to_string()]);
+        }
+
+        let env_profile = self.env_profile().ok_or(ConanCreateError::OutputDirNotFound)?;
+        args.extend(
+            self.conf_env
+                .args(conan_version, path_str(&env_profile)?, self.profile_host.is_none())?,
+        );
+
+        if let Some(build_policy) = &self.build_policy {
+            let policy_args = build_policy
+                .args(conan_version)
+                .ok_or(ConanCreateError::UnsupportedBuildPolicy)?;
+            args.extend(policy_args);
+        }
+
+        args.extend(
+            self.build_options
+                .iter()
+                .flat_map(|x| ["-o".to_string(), x.to_string()]),
+        );
+        args.extend(self.build_settings.args());
+
+        if let Some(test_folder) = &self.test_folder {
+            args.extend(vec!["--test-folder".to_string(), path_str(test_folder)?.to_string()]);
+        }
+
+        match conan_version {
+            ConanVersion::V1 => {
+                let output_file = self.output_file().ok_or(ConanCreateError::OutputDirNotFound)?;
+                args.extend(vec!["--json".to_string(), path_str(&output_file)?.to_string()]);
+            }
+            ConanVersion::V2 => args.push("--format=json".to_string()),
+        }
+
+        Ok(args)
+    }
+
+    pub fn output_dir(&self) -> Option<PathBuf> {
+        self.output_dir
+            .clone()
+            .or_else(|| env::var("OUT_DIR").ok().map(PathBuf::from))
+            .or_else(|| env::current_dir().ok())
+    }
+
+    /// JSON report written by Conan 1.
+    pub fn output_file(&self) -> Option<PathBuf> {
+        let mut output_file = self.output_dir()?;
+        output_file.push("conan_create.json");
+        Some(output_file)
+    }
+
+    /// Profile holding the environment for Conan 2.
+    pub fn env_profile(&self) -> Option<PathBuf> {
+        let mut env_profile = self.output_dir()?;
+        env_profile.push("conan-profiles");
+        env_profile.push("buildenv");
+        Some(env_profile)
+    }
+
+    pub fn run(&self) -> Result<CreatedPackage, ConanCreateError> {
+        let conan_version = self.context.conan_version();
+        let args = self.args()?;
+        let env_profile = self.env_profile().ok_or(ConanCreateError::OutputDirNotFound)?;
+        self.conf_env.write_env_profile(conan_version, &env_profile)?;
+        let output = self.context.execute(&args)?;
+        if !output.status.success() {
+            return Err(ConanCreateError::CommandFailed(output.status));
+        }
+
+        match conan_version {
+            ConanVersion::V1 => {
+                let output_file = self.output_file().ok_or(ConanCreateError::OutputDirNotFound)?;
+                CreatedPackage::from_v1_json(&fs::read_to_string(output_file)?)
+            }
+            // NOTE: Conan 2 prints the JSON graph on stdout and its logs on stderr
+            ConanVersion::V2 => CreatedPackage::from_v2_json(&String::from_utf8_lossy(&output.stdout)),
+        }
+    }
+}
+
+fn path_str(path: &Path) -> Result<&str, ConanCreateError> {
+    path.to_str().ok_or(ConanCreateError::InvalidUnicodeInPath)
+}
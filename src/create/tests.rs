@@ -0,0 +1,220 @@
+use super::*;
+use crate::install::build_info::build_settings::BuildType;
+use crate::install::BuildRule;
+
+#[test]
+fn test_create_args_v1() {
+    let command = CreateCommandBuilder::new()
+        .recipe_path(Path::new("conanfile.py"))
+        .with_name("bridge")
+        .with_version("0.1.0")
+        .with_user("devolutions")
+        .with_channel("stable")
+        .with_profile("linux-x86_64")
+        .build_settings(BuildSettings::new().build_type(BuildType::Release))
+        .build_policy(BuildRule::Missing)
+        .with_options(&["bridge:shared=True"])
+        .with_test_folder(Path::new("test_package"))
+        .output_dir(Path::new("/tmp/out"))
+        .conan_version(ConanVersion::V1)
+        .build();
+
+    let output_file = command.output_file().unwrap();
+    assert_eq!(
+        command.args().unwrap(),
+        [
+            "create",
+            "conanfile.py",
+            "bridge/0.1.0@devolutions/stable",
+            "--profile:host",
+            "linux-x86_64",
+            "-b",
+            "missing",
+            "-o",
+            "bridge:shared=True",
+            "-s",
+            "build_type=Release",
+            "--test-folder",
+            "test_package",
+            "--json",
+            output_file.to_str().unwrap()
+        ]
+    );
+}
+
+#[test]
+fn test_create_args_v1_user_channel_only() {
+    let command = CreateCommandBuilder::new()
+        .with_user("devolutions")
+        .with_channel("stable")
+        .build_settings(BuildSettings::new().build_type(BuildType::Debug))
+        .output_dir(Path::new("/tmp/out"))
+        .conan_version(ConanVersion::V1)
+        .build();
+
+    let args = command.args().unwrap();
+    assert_eq!(args[..3], ["create", ".", "devolutions/stable"]);
+}
+
+#[test]
+fn test_create_args_v1_version_without_name() {
+    let command = CreateCommandBuilder::new()
+        .with_version("1.0")
+        .conan_version(ConanVersion::V1)
+        .build();
+
+    assert!(matches!(command.args(), Err(ConanCreateError::MissingName)));
+}
+
+#[test]
+fn test_create_args_v1_name_without_version() {
+    let command = CreateCommandBuilder::new()
+        .with_name("bridge")
+        .with_user("devolutions")
+        .with_channel("stable")
+        .conan_version(ConanVersion::V1)
+        .build();
+
+    assert!(matches!(command.args(), Err(ConanCreateError::MissingVersion)));
+}
+
+#[test]
+fn test_create_args_v1_user_without_channel() {
+    let command = CreateCommandBuilder::new()
+        .with_user("devolutions")
+        .conan_version(ConanVersion::V1)
+        .build();
+
+    assert!(matches!(command.args(), Err(ConanCreateError::IncompleteUserChannel)));
+}
+
+#[test]
+fn test_create_args_v2() {
+    let command = CreateCommandBuilder::new()
+        .with_version("0.2.0")
+        .with_user("devolutions")
+        .with_host_profile("windows-x86_64")
+        .with_build_profile("linux-x86_64")
+        .build_settings(BuildSettings::new().build_type(BuildType::Release))
+        .build_policy(BuildRule::Always)
+        .conan_version(ConanVersion::V2)
+        .build();
+
+    assert_eq!(
+        command.args().unwrap(),
+        [
+            "create",
+            ".",
+            "--version",
+            "0.2.0",
+            "--user",
+            "devolutions",
+            "--profile:host",
+            "windows-x86_64",
+            "--profile:build",
+            "linux-x86_64",
+            "--build=*",
+            "-s",
+            "build_type=Release",
+            "--format=json"
+        ]
+    );
+
+    let command = CreateCommandBuilder::new()
+        .build_policy(BuildRule::Outdated)
+        .conan_version(ConanVersion::V2)
+        .build();
+    assert!(matches!(command.args(), Err(ConanCreateError::UnsupportedBuildPolicy)));
+}
+
+#[test]
+fn test_created_package_from_json() {
+    let created = CreatedPackage::from_v1_json(include_str!("../../test/conan_create_v1.json")).unwrap();
+    assert_eq!(created.reference.to_string(), "bridge/0.1.0@devolutions/stable");
+    assert_eq!(created.package_id, "3fb49604f9c2f729b85ba3115852006824e72cab");
+
+    let created = CreatedPackage::from_v2_json(include_str!("../../test/conan_create_v2.json")).unwrap();
+    assert_eq!(
+        created.reference.to_string(),
+        "bridge/0.1.0@devolutions/stable#a2b7c3d0f1e4e5a6b7c8d9e0f1a2b3c4"
+    );
+    assert_eq!(created.package_id, "3fb49604f9c2f729b85ba3115852006824e72cab");
+
+    assert!(CreatedPackage::from_v2_json("{}").is_err());
+}
+
+#[test]
+fn test_create_args_with_reference() {
+    let reference = ConanReference::new("bridge", "0.1.0")
+        .unwrap()
+        .with_user_channel("devolutions", "stable")
+        .unwrap();
+    let command = CreateCommandBuilder::new()
+        .with_reference(&reference)
+        .conan_version(ConanVersion::V2)
+        .build();
+
+    assert_eq!(
+        command.args().unwrap()[..10],
+        [
+            "create",
+            ".",
+            "--name",
+            "bridge",
+            "--version",
+            "0.1.0",
+            "--user",
+            "devolutions",
+            "--channel",
+            "stable"
+        ]
+    );
+
+    let created = CreatedPackage::from_v1_json(include_str!("../../test/conan_create_v1.json")).unwrap();
+    assert_eq!(
+        created.package_reference().unwrap().to_string(),
+        "bridge/0.1.0@devolutions/stable:3fb49604f9c2f729b85ba3115852006824e72cab"
+    );
+}
+
+#[test]
+fn test_create_args_with_conf_and_env() {
+    let command = CreateCommandBuilder::new()
+        .with_conf("tools.cmake.cmaketoolchain:generator", "Ninja")
+        .with_package_env("zlib", "CC", "clang")
+        .output_dir(Path::new("/tmp/out"))
+        .conan_version(ConanVersion::V1)
+        .build();
+    assert_eq!(
+        command.args().unwrap(),
+        [
+            "create",
+            ".",
+            "-c",
+            "tools.cmake.cmaketoolchain:generator=Ninja",
+            "-e",
+            "zlib:CC=clang",
+            "--json",
+            "/tmp/out/conan_create.json"
+        ]
+    );
+
+    let command = CreateCommandBuilder::new()
+        .with_host_profile("linux-x86_64")
+        .with_env("CC", "clang")
+        .output_dir(Path::new("/tmp/out"))
+        .conan_version(ConanVersion::V2)
+        .build();
+    assert_eq!(
+        command.args().unwrap(),
+        [
+            "create",
+            ".",
+            "--profile:host",
+            "linux-x86_64",
+            "--profile:host",
+            "/tmp/out/conan-profiles/buildenv",
+            "--format=json"
+        ]
+    );
+}
@@ -0,0 +1,624 @@
+#[cfg(test)]
+mod tests;
+
+use std::collections::VecDeque;
+use std::fmt;
+use std::fs;
+use std::io::{self, Read, Write};
+use std::path::{Path, PathBuf};
+use std::process::{Child, Command, ExitStatus, Stdio};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use thiserror::Error;
+
+/// Conan process to spawn: executable, arguments, environment and working directory
+///
+/// `Debug` redacts the values of the credential variables, `CONAN_PASSWORD*` and `CONAN_LOGIN_*`.
+#[derive(Clone, Default, PartialEq, Eq)]
+pub struct Invocation {
+    pub program: PathBuf,
+    pub args: Vec<String>,
+    /// Variables added to the inherited environment.
+    pub env: Vec<(String, String)>,
+    pub working_dir: Option<PathBuf>,
+    /// Capture the output without forwarding it, e.g. for `conan --version`.
+    pub quiet: bool,
+    /// Kill the process tree and fail with a `TimeoutError` past this duration.
+    pub timeout: Option<Duration>,
+}
+
+impl Invocation {
+    pub fn new(program: &Path, args: &[String]) -> Self {
+        Invocation {
+            program: program.to_path_buf(),
+            args: args.to_vec(),
+            ..Invocation::default()
+        }
+    }
+}
+
+impl fmt::Debug for Invocation {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let env: Vec<(&str, &str)> = self
+            .env
+            .iter()
+            .map(|(name, value)| match name.as_str() {
+                name if name.starts_with("CONAN_PASSWORD") || name.starts_with("CONAN_LOGIN_") => (name, "<redacted>"),
+                name => (name, value.as_str()),
+            })
+            .collect();
+        f.debug_struct("Invocation")
+            .field("program", &self.program)
+            .field("args", &self.args)
+            .field("env", &env)
+            .field("working_dir", &self.working_dir)
+            .field("quiet", &self.quiet)
+            .field("timeout", &self.timeout)
+            .finish()
+    }
+}
+
+impl fmt::Display for Invocation {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.program.display())?;
+        for arg in &self.args {
+            write!(f, " {}", arg)?;
+        }
+        Ok(())
+    }
+}
+
+/// Exit status and captured output of an invocation
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CommandOutput {
+    pub status: ExitStatus,
+    pub stdout: Vec<u8>,
+    pub stderr: Vec<u8>,
+}
+
+/// Error of an invocation killed once its timeout expired, with the output captured until then
+///
+/// Executors return it wrapped in an `io::Error` of kind `TimedOut`.
+#[derive(Clone, Debug, PartialEq, Eq, Error)]
+#[error("Conan was killed after running for {timeout:?}")]
+pub struct TimeoutError {
+    pub timeout: Duration,
+    pub stdout: Vec<u8>,
+    pub stderr: Vec<u8>,
+}
+
+impl From<TimeoutError> for io::Error {
+    fn from(error: TimeoutError) -> Self {
+        io::Error::new(io::ErrorKind::TimedOut, error)
+    }
+}
+
+/// Standard stream of the Conan process
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum OutputStream {
+    Stdout,
+    Stderr,
+}
+
+/// Receives the output of an invocation line by line, without the line endings
+pub type LineHandler<'a> = dyn Fn(OutputStream, &str) + Send + Sync + 'a;
+
+/// Runs the Conan invocations of the commands
+///
+/// `SystemExecutor` spawns Conan, `RecordingExecutor` and `ScriptedExecutor` stand in for it
+/// in tests. Set one with `ConanContext::with_executor`.
+pub trait Executor: fmt::Debug + Send + Sync {
+    fn execute(&self, invocation: &Invocation) -> io::Result<CommandOutput>;
+
+    /// Run an invocation, passing its output lines to `on_line` as they come.
+    ///
+    /// By default the lines are passed once the invocation completes, the standard output first.
+    fn execute_streaming(&self, invocation: &Invocation, on_line: &LineHandler) -> io::Result<CommandOutput> {
+        let output = self.execute(invocation)?;
+        for line in lines(&output.stdout) {
+            on_line(OutputStream::Stdout, &line);
+        }
+        for line in lines(&output.stderr) {
+            on_line(OutputStream::Stderr, &line);
+        }
+        Ok(output)
+    }
+}
+
+/// Lines of a captured output, without the line endings.
+pub(crate) fn lines(output: &[u8]) -> Vec<String> {
+    let mut lines = Vec::new();
+    let mut splitter = LineSplitter::default();
+    splitter.push(output, |line| lines.push(line.to_string()));
+    splitter.finish(|line| lines.push(line.to_string()));
+    lines
+}
+
+/// Splits output read in chunks into lines, holding back the last incomplete one
+#[derive(Debug, Default)]
+struct LineSplitter {
+    pending: Vec<u8>,
+}
+
+impl LineSplitter {
+    fn push<F: FnMut(&str)>(&mut self, data: &[u8], mut on_line: F) {
+        self.pending.extend_from_slice(data);
+        let mut start = 0;
+        while let Some(end) = self.pending[start..].iter().position(|&byte| byte == b'\n') {
+            let line = &self.pending[start..start + end];
+            on_line(String::from_utf8_lossy(line.strip_suffix(b"\r").unwrap_or(line)).as_ref());
+            start += end + 1;
+        }
+        self.pending.drain(..start);
+    }
+
+    fn finish<F: FnMut(&str)>(&mut self, mut on_line: F) {
+        if !self.pending.is_empty() {
+            on_line(String::from_utf8_lossy(&self.pending).as_ref());
+            self.pending.clear();
+        }
+    }
+}
+
+/// Executor spawning the Conan process
+///
+/// The output is captured, and forwarded to the standard output and error as it comes,
+/// unless the invocation is quiet. With a timeout, Conan runs in its own process group
+/// on Unix, so that the builds it started are killed along with it.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct SystemExecutor;
+
+impl Executor for SystemExecutor {
+    fn execute(&self, invocation: &Invocation) -> io::Result<CommandOutput> {
+        self.execute_streaming(invocation, &|_, _| {})
+    }
+
+    fn execute_streaming(&self, invocation: &Invocation, on_line: &LineHandler) -> io::Result<CommandOutput> {
+        let mut command = Command::new(&invocation.program);
+        command
+            .args(&invocation.args)
+            .envs(invocation.env.iter().map(|(name, value)| (name, value)))
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped());
+        if let Some(working_dir) = &invocation.working_dir {
+            command.current_dir(working_dir);
+        }
+        #[cfg(unix)]
+        if invocation.timeout.is_some() {
+            use std::os::unix::process::CommandExt;
+            command.process_group(0);
+        }
+
+        let mut child = command.spawn()?;
+        let quiet = invocation.quiet;
+        let stdout_pipe = child.stdout.take();
+        let stderr_pipe = child.stderr.take();
+        thread::scope(|scope| {
+            let stdout = stdout_pipe.map(|pipe| {
+                scope.spawn(move || tee(pipe, io::stdout(), quiet, |line| on_line(OutputStream::Stdout, line)))
+            });
+            let stderr = stderr_pipe.map(|pipe| {
+                scope.spawn(move || tee(pipe, io::stderr(), quiet, |line| on_line(OutputStream::Stderr, line)))
+            });
+
+            let status = match invocation.timeout {
+                Some(timeout) => match wait_timeout(&mut child, timeout)? {
+                    Some(status) => status,
+                    None => {
+                        if !kill_tree(child.id()) {
+                            let _ = child.kill();
+                        }
+                        child.wait()?;
+                        return Err(TimeoutError {
+                            timeout,
+                            stdout: join(stdout)?,
+                            stderr: join(stderr)?,
+                        }
+                        .into());
+                    }
+                },
+                None => child.wait()?,
+            };
+
+            Ok(CommandOutput {
+                status,
+                stdout: join(stdout)?,
+                stderr: join(stderr)?,
+            })
+        })
+    }
+}
+
+/// Wait for the child to exit, for at most `timeout`.
+fn wait_timeout(child: &mut Child, timeout: Duration) -> io::Result<Option<ExitStatus>> {
+    let deadline = Instant::now() + timeout;
+    loop {
+        if let Some(status) = child.try_wait()? {
+            return Ok(Some(status));
+        }
+        let now = Instant::now();
+        if now >= deadline {
+            return Ok(None);
+        }
+        thread::sleep((deadline - now).min(Duration::from_millis(20)));
+    }
+}
+
+/// Kill a process and its descendants, returning whether it succeeded.
+///
+/// On Unix the process must lead its own process group.
+fn kill_tree(pid: u32) -> bool {
+    #[cfg(unix)]
+    let mut command = {
+        let mut command = Command::new("kill");
+        command.args(["-s", "KILL", "--", &format!("-{}", pid)]);
+        command
+    };
+    #[cfg(windows)]
+    let mut command = {
+        let mut command = Command::new("taskkill");
+        command.args(["/T", "/F", "/PID", &pid.to_string()]);
+        command
+    };
+    command
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .status()
+        .is_ok_and(|status| status.success())
+}
+
+/// Read a pipe to the end, forwarding it to `sink` unless `quiet`, and its lines to `on_line`.
+fn tee<R, W, F>(mut pipe: R, mut sink: W, quiet: bool, mut on_line: F) -> io::Result<Vec<u8>>
+where
+    R: Read,
+    W: Write,
+    F: FnMut(&str),
+{
+    let mut captured = Vec::new();
+    let mut splitter = LineSplitter::default();
+    let mut buffer = [0; 8192];
+    loop {
+        let read = match pipe.read(&mut buffer) {
+            Ok(0) => break,
+            Ok(read) => read,
+            Err(error) if error.kind() == io::ErrorKind::Interrupted => continue,
+            Err(error) => return Err(error),
+        };
+        captured.extend_from_slice(&buffer[..read]);
+        if !quiet {
+            // NOTE: A closed standard stream must not lose the captured output
+            let _ = sink.write_all(&buffer[..read]).and_then(|_| sink.flush());
+        }
+        splitter.push(&buffer[..read], &mut on_line);
+    }
+    splitter.finish(on_line);
+    Ok(captured)
+}
+
+fn join(handle: Option<thread::ScopedJoinHandle<io::Result<Vec<u8>>>>) -> io::Result<Vec<u8>> {
+    match handle {
+        Some(handle) => handle
+            .join()
+            .unwrap_or_else(|_| Err(io::Error::other("output reader panicked"))),
+        None => Ok(Vec::new()),
+    }
+}
+
+/// Spawn the Conan process with `tokio::process`, like `SystemExecutor`.
+///
+/// The process is killed if the future is dropped before it exits.
+#[cfg(feature = "tokio")]
+pub(crate) async fn execute_async(
+    invocation: &Invocation,
+    on_line: Arc<LineHandler<'static>>,
+) -> io::Result<CommandOutput> {
+    let mut command = tokio::process::Command::new(&invocation.program);
+    command
+        .args(&invocation.args)
+        .envs(invocation.env.iter().map(|(name, value)| (name, value)))
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .kill_on_drop(true);
+    if let Some(working_dir) = &invocation.working_dir {
+        command.current_dir(working_dir);
+    }
+    #[cfg(unix)]
+    if invocation.timeout.is_some() {
+        command.process_group(0);
+    }
+
+    let mut child = command.spawn()?;
+    let quiet = invocation.quiet;
+    let stdout_handler = on_line.clone();
+    let stdout = child.stdout.take().map(|pipe| {
+        tokio::spawn(tee_async(pipe, io::stdout(), quiet, move |line| {
+            stdout_handler(OutputStream::Stdout, line)
+        }))
+    });
+    let stderr = child.stderr.take().map(|pipe| {
+        tokio::spawn(tee_async(pipe, io::stderr(), quiet, move |line| {
+            on_line(OutputStream::Stderr, line)
+        }))
+    });
+
+    let status = match invocation.timeout {
+        Some(timeout) => match tokio::time::timeout(timeout, child.wait()).await {
+            Ok(status) => status?,
+            Err(_) => {
+                if !child.id().is_some_and(kill_tree) {
+                    let _ = child.start_kill();
+                }
+                child.wait().await?;
+                return Err(TimeoutError {
+                    timeout,
+                    stdout: join_async(stdout).await?,
+                    stderr: join_async(stderr).await?,
+                }
+                .into());
+            }
+        },
+        None => child.wait().await?,
+    };
+
+    Ok(CommandOutput {
+        status,
+        stdout: join_async(stdout).await?,
+        stderr: join_async(stderr).await?,
+    })
+}
+
+#[cfg(feature = "tokio")]
+async fn join_async(handle: Option<tokio::task::JoinHandle<io::Result<Vec<u8>>>>) -> io::Result<Vec<u8>> {
+    match handle {
+        Some(handle) => handle.await?,
+        None => Ok(Vec::new()),
+    }
+}
+
+/// Read a pipe to the end, forwarding it to `sink` as it comes unless `quiet`, and its lines to `on_line`.
+#[cfg(feature = "tokio")]
+async fn tee_async<R, W, F>(mut pipe: R, mut sink: W, quiet: bool, mut on_line: F) -> io::Result<Vec<u8>>
+where
+    R: tokio::io::AsyncRead + Unpin,
+    W: Write,
+    F: FnMut(&str),
+{
+    use tokio::io::AsyncReadExt;
+
+    let mut captured = Vec::new();
+    let mut splitter = LineSplitter::default();
+    let mut buffer = [0; 8192];
+    loop {
+        let read = pipe.read(&mut buffer).await?;
+        if read == 0 {
+            break;
+        }
+        captured.extend_from_slice(&buffer[..read]);
+        if !quiet {
+            let _ = sink.write_all(&buffer[..read]).and_then(|_| sink.flush());
+        }
+        splitter.push(&buffer[..read], &mut on_line);
+    }
+    splitter.finish(on_line);
+    Ok(captured)
+}
+
+/// Exit status with the given code.
+pub(crate) fn exit_status(code: i32) -> ExitStatus {
+    #[cfg(unix)]
+    {
+        use std::os::unix::process::ExitStatusExt;
+        // NOTE: The raw status of a process that exited holds the code in its second byte
+        ExitStatus::from_raw((code & 0xff) << 8)
+    }
+    #[cfg(windows)]
+    {
+        use std::os::windows::process::ExitStatusExt;
+        ExitStatus::from_raw(code as u32)
+    }
+}
+
+/// Executor recording the invocations, which all succeed without output
+///
+/// Clones share the record, so a clone kept aside sees the invocations of the commands.
+#[derive(Clone, Debug, Default)]
+pub struct RecordingExecutor {
+    invocations: Arc<Mutex<Vec<Invocation>>>,
+}
+
+impl RecordingExecutor {
+    pub fn new() -> Self {
+        RecordingExecutor::default()
+    }
+
+    pub fn invocations(&self) -> Vec<Invocation> {
+        self.invocations.lock().unwrap_or_else(|e| e.into_inner()).clone()
+    }
+}
+
+impl Executor for RecordingExecutor {
+    fn execute(&self, invocation: &Invocation) -> io::Result<CommandOutput> {
+        self.invocations
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .push(invocation.clone());
+        ScriptedResponse::success().apply(invocation)
+    }
+}
+
+/// Scripted result of an invocation: exit code, output, and files Conan would write
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ScriptedResponse {
+    code: i32,
+    stdout: Vec<u8>,
+    stderr: Vec<u8>,
+    files: Vec<(PathBuf, Vec<u8>)>,
+    hanging: bool,
+}
+
+impl ScriptedResponse {
+    pub fn success() -> Self {
+        ScriptedResponse::failure(0)
+    }
+
+    pub fn failure(code: i32) -> Self {
+        ScriptedResponse {
+            code,
+            stdout: Vec::new(),
+            stderr: Vec::new(),
+            files: Vec::new(),
+            hanging: false,
+        }
+    }
+
+    /// Never exit: invocations with a timeout fail with a `TimeoutError` carrying the output,
+    /// the others with `io::ErrorKind::WouldBlock`.
+    pub fn hanging() -> Self {
+        ScriptedResponse {
+            hanging: true,
+            ..ScriptedResponse::success()
+        }
+    }
+
+    pub fn with_stdout(mut self, stdout: &str) -> Self {
+        self.stdout = stdout.as_bytes().to_vec();
+        self
+    }
+
+    pub fn with_stderr(mut self, stderr: &str) -> Self {
+        self.stderr = stderr.as_bytes().to_vec();
+        self
+    }
+
+    /// Write a file when answering, e.g. the `conanbuildinfo.json` of `conan install`.
+    ///
+    /// Relative paths are resolved against the working directory of the invocation.
+    pub fn with_file(mut self, path: &Path, contents: &[u8]) -> Self {
+        self.files.push((path.to_path_buf(), contents.to_vec()));
+        self
+    }
+
+    fn apply(&self, invocation: &Invocation) -> io::Result<CommandOutput> {
+        for (path, contents) in &self.files {
+            let path = match &invocation.working_dir {
+                Some(working_dir) => working_dir.join(path),
+                None => path.clone(),
+            };
+            if let Some(parent) = path.parent() {
+                fs::create_dir_all(parent)?;
+            }
+            fs::write(path, contents)?;
+        }
+        if self.hanging {
+            return match invocation.timeout {
+                Some(timeout) => Err(TimeoutError {
+                    timeout,
+                    stdout: self.stdout.clone(),
+                    stderr: self.stderr.clone(),
+                }
+                .into()),
+                None => Err(io::Error::new(
+                    io::ErrorKind::WouldBlock,
+                    format!("{} hangs without a timeout", invocation),
+                )),
+            };
+        }
+        Ok(CommandOutput {
+            status: exit_status(self.code),
+            stdout: self.stdout.clone(),
+            stderr: self.stderr.clone(),
+        })
+    }
+}
+
+#[derive(Debug, Default)]
+struct Script {
+    rules: Vec<(Vec<String>, VecDeque<ScriptedResponse>)>,
+    invocations: Vec<Invocation>,
+}
+
+/// Executor answering the invocations from a script, and recording them
+///
+/// An invocation gets the response of the rule with the longest matching argument prefix.
+/// A rule given several responses answers with them in turn, repeating the last one.
+/// Invocations without a rule fail with `io::ErrorKind::NotFound`.
+///
+/// ```
+/// use conan::{ConanContext, InstallCommandBuilder, ScriptedExecutor, ScriptedResponse};
+/// use std::path::Path;
+///
+/// let executor = ScriptedExecutor::new()
+///     .on(&["--version"], ScriptedResponse::success().with_stdout("Conan version 1.66.0\n"))
+///     .on(&["install"], ScriptedResponse::failure(1));
+/// let context = ConanContext::new()
+///     .with_program(Path::new("conan"))
+///     .with_executor(executor.clone())
+///     .detect()
+///     .unwrap();
+/// let command = InstallCommandBuilder::new()
+///     .with_context(context)
+///     .output_dir(&std::env::temp_dir().join("scripted-install"))
+///     .build();
+/// assert!(command.generate().is_none());
+/// assert_eq!(executor.invocations()[1].args[0], "install");
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct ScriptedExecutor {
+    script: Arc<Mutex<Script>>,
+}
+
+impl ScriptedExecutor {
+    pub fn new() -> Self {
+        ScriptedExecutor::default()
+    }
+
+    /// Answer the invocations whose arguments start with `args`.
+    pub fn on(self, args: &[&str], response: ScriptedResponse) -> Self {
+        {
+            let mut script = self.script.lock().unwrap_or_else(|e| e.into_inner());
+            let args: Vec<String> = args.iter().map(|arg| arg.to_string()).collect();
+            match script.rules.iter_mut().find(|(prefix, _)| *prefix == args) {
+                Some((_, responses)) => responses.push_back(response),
+                None => script.rules.push((args, vec![response].into())),
+            }
+        }
+        self
+    }
+
+    pub fn invocations(&self) -> Vec<Invocation> {
+        self.script
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .invocations
+            .clone()
+    }
+}
+
+impl Executor for ScriptedExecutor {
+    fn execute(&self, invocation: &Invocation) -> io::Result<CommandOutput> {
+        let response = {
+            let mut script = self.script.lock().unwrap_or_else(|e| e.into_inner());
+            script.invocations.push(invocation.clone());
+            let responses = script
+                .rules
+                .iter_mut()
+                .filter(|(prefix, _)| invocation.args.starts_with(prefix))
+                .max_by_key(|(prefix, _)| prefix.len())
+                .map(|(_, responses)| responses)
+                .ok_or_else(|| {
+                    io::Error::new(
+                        io::ErrorKind::NotFound,
+                        format!("No scripted response for {}", invocation),
+                    )
+                })?;
+            if responses.len() > 1 {
+                responses.pop_front().unwrap()
+            } else {
+                responses[0].clone()
+            }
+        };
+        response.apply(invocation)
+    }
+}
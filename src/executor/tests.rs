@@ -0,0 +1,198 @@
+use super::*;
+
+fn invocation(args: &[&str]) -> Invocation {
+    let args: Vec<String> = args.iter().map(|arg| arg.to_string()).collect();
+    Invocation::new(Path::new("conan"), &args)
+}
+
+#[test]
+fn test_invocation_display() {
+    assert_eq!(invocation(&["install", "."]).to_string(), "conan install .");
+}
+
+#[test]
+fn test_invocation_debug_redacts_credentials() {
+    let mut invocation = invocation(&["remote", "login", "artifactory"]);
+    invocation.env = vec![
+        ("CONAN_LOGIN_USERNAME_ARTIFACTORY".to_string(), "ci".to_string()),
+        ("CONAN_PASSWORD_ARTIFACTORY".to_string(), "s3cret".to_string()),
+        ("CONAN_NON_INTERACTIVE".to_string(), "1".to_string()),
+    ];
+    let debug = format!("{:?}", invocation);
+    assert!(!debug.contains("s3cret"));
+    assert!(!debug.contains("\"ci\""));
+    assert!(debug.contains("(\"CONAN_PASSWORD_ARTIFACTORY\", \"<redacted>\")"));
+    assert!(debug.contains("(\"CONAN_NON_INTERACTIVE\", \"1\")"));
+}
+
+#[test]
+fn test_exit_status() {
+    assert!(exit_status(0).success());
+    assert_eq!(exit_status(0).code(), Some(0));
+    assert_eq!(exit_status(3).code(), Some(3));
+}
+
+#[test]
+fn test_recording_executor() -> io::Result<()> {
+    let executor = RecordingExecutor::new();
+    let recorded = executor.clone();
+    let output = executor.execute(&invocation(&["search", "zlib"]))?;
+    assert!(output.status.success());
+    assert!(output.stdout.is_empty());
+    assert_eq!(recorded.invocations(), [invocation(&["search", "zlib"])]);
+    Ok(())
+}
+
+#[test]
+fn test_scripted_executor() -> io::Result<()> {
+    let dir = std::env::temp_dir().join(format!("conan-rs-executor-{}", std::process::id()));
+    let executor = ScriptedExecutor::new()
+        .on(&["remote"], ScriptedResponse::success().with_stdout("conancenter"))
+        .on(&["remote", "login"], ScriptedResponse::failure(1).with_stderr("denied"))
+        .on(&["remote", "login"], ScriptedResponse::success())
+        .on(
+            &["install"],
+            ScriptedResponse::success().with_file(Path::new("build/info.json"), b"{}"),
+        );
+
+    // NOTE: The longest prefix wins, and a rule repeats its last response
+    let output = executor.execute(&invocation(&["remote", "list"]))?;
+    assert_eq!(output.stdout, b"conancenter");
+    let output = executor.execute(&invocation(&["remote", "login", "artifactory"]))?;
+    assert_eq!(output.status.code(), Some(1));
+    assert_eq!(output.stderr, b"denied");
+    assert!(executor.execute(&invocation(&["remote", "login"]))?.status.success());
+    assert!(executor.execute(&invocation(&["remote", "login"]))?.status.success());
+
+    let mut install = invocation(&["install", "."]);
+    install.working_dir = Some(dir.clone());
+    executor.execute(&install)?;
+    assert_eq!(fs::read_to_string(dir.join("build/info.json"))?, "{}");
+
+    let error = executor.execute(&invocation(&["upload"])).unwrap_err();
+    assert_eq!(error.kind(), io::ErrorKind::NotFound);
+    assert_eq!(executor.invocations().len(), 6);
+
+    fs::remove_dir_all(&dir)?;
+    Ok(())
+}
+
+#[cfg(unix)]
+#[test]
+fn test_system_executor() -> io::Result<()> {
+    let mut invocation = Invocation::new(
+        Path::new("/bin/sh"),
+        &[
+            "-c".to_string(),
+            "echo \"$GREETING\"; echo oops >&2; exit 2".to_string(),
+        ],
+    );
+    invocation.env.push(("GREETING".to_string(), "hello".to_string()));
+    invocation.quiet = true;
+
+    let output = SystemExecutor.execute(&invocation)?;
+    assert_eq!(output.status.code(), Some(2));
+    assert_eq!(output.stdout, b"hello\n");
+    assert_eq!(output.stderr, b"oops\n");
+    Ok(())
+}
+
+#[test]
+fn test_scripted_executor_hanging() {
+    let executor = ScriptedExecutor::new().on(&["install"], ScriptedResponse::hanging().with_stdout("Installing"));
+
+    let mut install = invocation(&["install"]);
+    let error = executor.execute(&install).unwrap_err();
+    assert_eq!(error.kind(), io::ErrorKind::WouldBlock);
+
+    install.timeout = Some(Duration::from_secs(60));
+    let error = executor.execute(&install).unwrap_err();
+    assert_eq!(error.kind(), io::ErrorKind::TimedOut);
+    let timeout = error.get_ref().unwrap().downcast_ref::<TimeoutError>().unwrap();
+    assert_eq!(timeout.timeout, Duration::from_secs(60));
+    assert_eq!(timeout.stdout, b"Installing");
+}
+
+#[cfg(unix)]
+#[test]
+fn test_system_executor_timeout() {
+    // NOTE: The shell waits on a child holding the output pipes, which must be killed too
+    let mut invocation = Invocation::new(
+        Path::new("/bin/sh"),
+        &["-c".to_string(), "echo partial; sleep 10; echo done".to_string()],
+    );
+    invocation.quiet = true;
+    invocation.timeout = Some(Duration::from_millis(300));
+
+    let start = Instant::now();
+    let error = SystemExecutor.execute(&invocation).unwrap_err();
+    assert!(start.elapsed() < Duration::from_secs(5));
+    let timeout = error.get_ref().unwrap().downcast_ref::<TimeoutError>().unwrap();
+    assert_eq!(timeout.stdout, b"partial\n");
+
+    invocation.args[1] = "echo fast".to_string();
+    assert_eq!(SystemExecutor.execute(&invocation).unwrap().stdout, b"fast\n");
+}
+
+#[test]
+fn test_lines() {
+    assert_eq!(lines(b"one\r\ntwo\n\nthree"), ["one", "two", "", "three"]);
+    assert!(lines(b"").is_empty());
+
+    let mut splitter = LineSplitter::default();
+    let mut split = Vec::new();
+    splitter.push(b"par", |line| split.push(line.to_string()));
+    splitter.push(b"tial\nnext", |line| split.push(line.to_string()));
+    splitter.finish(|line| split.push(line.to_string()));
+    assert_eq!(split, ["partial", "next"]);
+}
+
+#[test]
+fn test_execute_streaming_replays_output() -> io::Result<()> {
+    let executor = ScriptedExecutor::new().on(
+        &["install"],
+        ScriptedResponse::success()
+            .with_stdout("one\ntwo\n")
+            .with_stderr("oops\n"),
+    );
+    let streamed = Mutex::new(Vec::new());
+    executor.execute_streaming(&invocation(&["install"]), &|stream, line| {
+        streamed.lock().unwrap().push((stream, line.to_string()))
+    })?;
+    assert_eq!(
+        streamed.into_inner().unwrap(),
+        [
+            (OutputStream::Stdout, "one".to_string()),
+            (OutputStream::Stdout, "two".to_string()),
+            (OutputStream::Stderr, "oops".to_string()),
+        ]
+    );
+    Ok(())
+}
+
+#[cfg(unix)]
+#[test]
+fn test_system_executor_streaming() -> io::Result<()> {
+    let mut invocation = Invocation::new(
+        Path::new("/bin/sh"),
+        &["-c".to_string(), "echo one; echo oops >&2; printf two".to_string()],
+    );
+    invocation.quiet = true;
+
+    let streamed = Mutex::new(Vec::new());
+    let output = SystemExecutor.execute_streaming(&invocation, &|stream, line| {
+        streamed.lock().unwrap().push((stream, line.to_string()))
+    })?;
+    assert_eq!(output.stdout, b"one\ntwo");
+    let mut streamed = streamed.into_inner().unwrap();
+    streamed.sort();
+    assert_eq!(
+        streamed,
+        [
+            (OutputStream::Stdout, "one".to_string()),
+            (OutputStream::Stdout, "two".to_string()),
+            (OutputStream::Stderr, "oops".to_string()),
+        ]
+    );
+    Ok(())
+}
@@ -0,0 +1,433 @@
+#[cfg(test)]
+mod tests;
+
+use std::io;
+use std::path::{Path, PathBuf};
+use std::process::ExitStatus;
+use std::time::Duration;
+
+use lazy_static::lazy_static;
+use regex::Regex;
+use thiserror::Error;
+
+use crate::context::{ConanContext, ConanContextError};
+use crate::home::ConanHome;
+use crate::install::build_info::BuildSettings;
+use crate::reference::{ConanReference, ReferenceError};
+use crate::util::ConanVersion;
+
+lazy_static! {
+    static ref REGEX_EXPORTED: Regex = Regex::new(r"^(\S+): Exported(?: revision)?: (\S+)").unwrap();
+    static ref REGEX_PACKAGE_CREATED: Regex = Regex::new(r"^\S+: Package '([0-9a-f]+)' created").unwrap();
+}
+
+#[derive(Debug, Error)]
+pub enum ConanExportError {
+    #[error("Invalid Unicode in path")]
+    InvalidUnicodeInPath,
+    #[error("Conan binary not found")]
+    ConanNotFound,
+    #[error("{0} is not supported by this Conan version")]
+    UnsupportedArgument(&'static str),
+    #[error("Conan 1 requires both a user and a channel")]
+    IncompleteUserChannel,
+    #[error("Conan export failed with {0}")]
+    CommandFailed(ExitStatus),
+    #[error("Exported reference not found in Conan output")]
+    ReferenceNotFound,
+    #[error(transparent)]
+    InvalidReference(#[from] ReferenceError),
+    #[error("I/O error: {0}")]
+    Io(#[from] io::Error),
+    #[error(transparent)]
+    Context(#[from] ConanContextError),
+}
+
+/// Recipe, and optionally binary package, exported into the local cache
+#[derive(Clone, Debug, PartialEq)]
+pub struct ExportedPackage {
+    /// Full reference including the recipe revision
+    pub reference: ConanReference,
+    /// Package ID, only known for "conan export-pkg"
+    pub package_id: Option<String>,
+}
+
+impl ExportedPackage {
+    /// Parse the log of "conan export" or "conan export-pkg", which has the same format on Conan 1 and 2.
+    ///
+    /// Returns `Ok(None)` when the log has no exported reference.
+    pub fn from_output(output: &str) -> Result<Option<Self>, ReferenceError> {
+        let mut reference = None;
+        let mut package_id = None;
+
+        for line in output.lines().map(str::trim) {
+            if let Some(captures) = REGEX_EXPORTED.captures(line) {
+                // Conan 1: "pkg/1.0@user/channel: Exported revision: <rrev>"
+                // Conan 2: "pkg/1.0@user/channel: Exported: pkg/1.0@user/channel#<rrev> (<time>)"
+                let reference_str = if captures[2].contains('/') {
+                    captures[2].to_string()
+                } else {
+                    format!("{}#{}", &captures[1], &captures[2])
+                };
+                reference = Some(reference_str.parse()?);
+            } else if let Some(captures) = REGEX_PACKAGE_CREATED.captures(line) {
+                package_id = Some(captures[1].to_string());
+            }
+        }
+
+        Ok(reference.map(|reference| ExportedPackage { reference, package_id }))
+    }
+}
+
+/// "conan export" command runner
+pub struct ExportCommand {
+    recipe_path: Option<PathBuf>,
+    user: Option<String>,
+    channel: Option<String>,
+    context: ConanContext,
+}
+
+/// Command arguments builder for "conan export"
+pub struct ExportCommandBuilder {
+    recipe_path: Option<PathBuf>,
+    user: Option<String>,
+    channel: Option<String>,
+    context: ConanContext,
+}
+
+impl Default for ExportCommandBuilder {
+    fn default() -> Self {
+        ExportCommandBuilder {
+            recipe_path: Some(PathBuf::from(".")),
+            user: None,
+            channel: None,
+            context: ConanContext::default(),
+        }
+    }
+}
+
+impl ExportCommandBuilder {
+    pub fn new() -> Self {
+        ExportCommandBuilder::default()
+    }
+
+    pub fn with_recipe_path(mut self, recipe_path: PathBuf) -> Self {
+        self.recipe_path = Some(recipe_path);
+        self
+    }
+
+    pub fn with_user(mut self, user: &str) -> Self {
+        self.user = Some(user.to_string());
+        self
+    }
+
+    pub fn with_channel(mut self, channel: &str) -> Self {
+        self.channel = Some(channel.to_string());
+        self
+    }
+
+    /// Run `conan export` through a shared context, keeping the timeout, home and version set on this builder.
+    pub fn with_context(mut self, context: ConanContext) -> Self {
+        self.context = context.merged_with(self.context);
+        self
+    }
+
+    /// Kill Conan and fail with a timeout error if it runs longer than `timeout`.
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.context = self.context.with_timeout(timeout);
+        self
+    }
+
+    /// Run Conan with an isolated home, e.g. `ConanHome::in_out_dir()?`.
+    pub fn with_conan_home(mut self, conan_home: ConanHome) -> Self {
+        self.context = self.context.with_home(conan_home);
+        self
+    }
+
+    pub fn conan_version(mut self, conan_version: ConanVersion) -> Self {
+        self.context = self.context.with_conan_version(conan_version);
+        self
+    }
+
+    pub fn build(self) -> ExportCommand {
+        ExportCommand {
+            recipe_path: self.recipe_path,
+            user: self.user,
+            channel: self.channel,
+            context: self.context,
+        }
+    }
+}
+
+impl ExportCommand {
+    pub fn args(&self) -> Result<Vec<String>, ConanExportError> {
+        let conan_version = self.context.conan_version();
+        let mut args: Vec<String> = vec!["export".to_string()];
+
+        args.push(path_str(self.recipe_path.as_deref().unwrap_or_else(|| Path::new(".")))?);
+        args.extend(user_channel_args(conan_version, &self.user, &self.channel)?);
+
+        Ok(args)
+    }
+
+    pub fn run(&self) -> Result<ExportedPackage, ConanExportError> {
+        run_export(&self.context, self.args()?)
+    }
+}
+
+/// "conan export-pkg" command runner
+pub struct ExportPkgCommand {
+    recipe_path: Option<PathBuf>,
+    user: Option<String>,
+    channel: Option<String>,
+    build_path: Option<PathBuf>,
+    package_path: Option<PathBuf>,
+    source_path: Option<PathBuf>,
+    install_path: Option<PathBuf>,
+    profile_host: Option<String>,
+    profile_build: Option<String>,
+    build_settings: Option<BuildSettings>,
+    force: bool,
+    context: ConanContext,
+}
+
+/// Command arguments builder for "conan export-pkg"
+pub struct ExportPkgCommandBuilder {
+    recipe_path: Option<PathBuf>,
+    user: Option<String>,
+    channel: Option<String>,
+    build_path: Option<PathBuf>,
+    package_path: Option<PathBuf>,
+    source_path: Option<PathBuf>,
+    install_path: Option<PathBuf>,
+    profile_host: Option<String>,
+    profile_build: Option<String>,
+    build_settings: Option<BuildSettings>,
+    force: bool,
+    context: ConanContext,
+}
+
+impl Default for ExportPkgCommandBuilder {
+    fn default() -> Self {
+        ExportPkgCommandBuilder {
+            recipe_path: Some(PathBuf::from(".")),
+            user: None,
+            channel: None,
+            build_path: None,
+            package_path: None,
+            source_path: None,
+            install_path: None,
+            profile_host: None,
+            profile_build: None,
+            build_settings: None,
+            force: false,
+            context: ConanContext::default(),
+        }
+    }
+}
+
+impl ExportPkgCommandBuilder {
+    pub fn new() -> Self {
+        ExportPkgCommandBuilder::default()
+    }
+
+    pub fn with_recipe_path(mut self, recipe_path: PathBuf) -> Self {
+        self.recipe_path = Some(recipe_path);
+        self
+    }
+
+    pub fn with_user(mut self, user: &str) -> Self {
+        self.user = Some(user.to_string());
+        self
+    }
+
+    pub fn with_channel(mut self, channel: &str) -> Self {
+        self.channel = Some(channel.to_string());
+        self
+    }
+
+    /// Conan 1 only: folder containing the build artifacts.
+    pub fn with_build_path(mut self, build_path: PathBuf) -> Self {
+        self.build_path = Some(build_path);
+        self
+    }
+
+    /// Conan 1 only: folder containing an already packaged binary.
+    pub fn with_package_path(mut self, package_path: PathBuf) -> Self {
+        self.package_path = Some(package_path);
+        self
+    }
+
+    /// Conan 1 only: folder containing the sources.
+    pub fn with_source_path(mut self, source_path: PathBuf) -> Self {
+        self.source_path = Some(source_path);
+        self
+    }
+
+    /// Conan 1 only: folder containing the files generated by "conan install".
+    pub fn with_install_path(mut self, install_path: PathBuf) -> Self {
+        self.install_path = Some(install_path);
+        self
+    }
+
+    /// Apply the specified profile to the host machine.
+    pub fn with_profile(self, profile: &str) -> Self {
+        self.with_host_profile(profile)
+    }
+
+    /// Apply the specified profile to the host machine.
+    pub fn with_host_profile(mut self, profile: &str) -> Self {
+        self.profile_host = Some(profile.to_string());
+        self
+    }
+
+    /// Apply the specified profile to the build machine.
+    pub fn with_build_profile(mut self, profile: &str) -> Self {
+        self.profile_build = Some(profile.to_string());
+        self
+    }
+
+    pub fn build_settings(mut self, build_settings: BuildSettings) -> Self {
+        self.build_settings = Some(build_settings);
+        self
+    }
+
+    /// Conan 1 only: overwrite an existing package.
+    pub fn force(mut self, force: bool) -> Self {
+        self.force = force;
+        self
+    }
+
+    /// Run `conan export-pkg` through a shared context, keeping the timeout, home and version set on this builder.
+    pub fn with_context(mut self, context: ConanContext) -> Self {
+        self.context = context.merged_with(self.context);
+        self
+    }
+
+    /// Kill Conan and fail with a timeout error if it runs longer than `timeout`.
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.context = self.context.with_timeout(timeout);
+        self
+    }
+
+    /// Run Conan with an isolated home, e.g. `ConanHome::in_out_dir()?`.
+    pub fn with_conan_home(mut self, conan_home: ConanHome) -> Self {
+        self.context = self.context.with_home(conan_home);
+        self
+    }
+
+    pub fn conan_version(mut self, conan_version: ConanVersion) -> Self {
+        self.context = self.context.with_conan_version(conan_version);
+        self
+    }
+
+    pub fn build(self) -> ExportPkgCommand {
+        ExportPkgCommand {
+            recipe_path: self.recipe_path,
+            user: self.user,
+            channel: self.channel,
+            build_path: self.build_path,
+            package_path: self.package_path,
+            source_path: self.source_path,
+            install_path: self.install_path,
+            profile_host: self.profile_host,
+            profile_build: self.profile_build,
+            build_settings: self.build_settings,
+            force: self.force,
+            context: self.context,
+        }
+    }
+}
+
+impl ExportPkgCommand {
+    pub fn args(&self) -> Result<Vec<String>, ConanExportError> {
+        let conan_version = self.context.conan_version();
+        let mut args: Vec<String> = vec!["export-pkg".to_string()];
+
+        args.push(path_str(self.recipe_path.as_deref().unwrap_or_else(|| Path::new(".")))?);
+        args.extend(user_channel_args(conan_version, &self.user, &self.channel)?);
+
+        let folders = [
+            ("--build-folder", &self.build_path),
+            ("--package-folder", &self.package_path),
+            ("--source-folder", &self.source_path),
+            ("--install-folder", &self.install_path),
+        ];
+        for (flag, path) in folders.iter() {
+            if let Some(path) = path {
+                if conan_version == ConanVersion::V2 {
+                    return Err(ConanExportError::UnsupportedArgument(flag));
+                }
+                args.extend(vec![flag.to_string(), path_str(path)?]);
+            }
+        }
+
+        if let Some(profile) = &self.profile_host {
+            args.extend(vec!["--profile:host".to_string(), profile.clone()]);
+        }
+
+        if let Some(profile) = &self.profile_build {
+            args.extend(vec!["--profile:build".to_string(), profile.clone()]);
+        }
+
+        if let Some(build_settings) = &self.build_settings {
+            args.extend(build_settings.args());
+        }
+
+        if self.force {
+            if conan_version == ConanVersion::V2 {
+                return Err(ConanExportError::UnsupportedArgument("--force"));
+            }
+            args.push("--force".to_string());
+        }
+
+        Ok(args)
+    }
+
+    pub fn run(&self) -> Result<ExportedPackage, ConanExportError> {
+        run_export(&self.context, self.args()?)
+    }
+}
+
+/// Conan 1 takes "user/channel" as a positional argument, Conan 2 takes separate flags.
+fn user_channel_args(
+    conan_version: ConanVersion,
+    user: &Option<String>,
+    channel: &Option<String>,
+) -> Result<Vec<String>, ConanExportError> {
+    match conan_version {
+        ConanVersion::V1 => match (user, channel) {
+            (Some(user), Some(channel)) => Ok(vec![format!("{}/{}", user, channel)]),
+            (None, None) => Ok(Vec::new()),
+            _ => Err(ConanExportError::IncompleteUserChannel),
+        },
+        ConanVersion::V2 => {
+            let mut args = Vec::new();
+            if let Some(user) = user {
+                args.extend(vec!["--user".to_string(), user.clone()]);
+            }
+            if let Some(channel) = channel {
+                args.extend(vec!["--channel".to_string(), channel.clone()]);
+            }
+            Ok(args)
+        }
+    }
+}
+
+fn run_export(context: &ConanContext, args: Vec<String>) -> Result<ExportedPackage, ConanExportError> {
+    let output = context.execute(&args)?;
+
+    if !output.status.success() {
+        return Err(ConanExportError::CommandFailed(output.status));
+    }
+
+    let mut log = String::from_utf8_lossy(&output.stdout).into_owned();
+    log.push_str(&String::from_utf8_lossy(&output.stderr));
+    ExportedPackage::from_output(&log)?.ok_or(ConanExportError::ReferenceNotFound)
+}
+
+fn path_str(path: &Path) -> Result<String, ConanExportError> {
+    path.to_str()
+        .map(str::to_string)
+        .ok_or(ConanExportError::InvalidUnicodeInPath)
+}
@@ -0,0 +1,164 @@
+use super::*;
+use crate::install::build_info::build_settings::BuildType;
+
+#[test]
+fn test_export_args() {
+    let command = ExportCommandBuilder::new().conan_version(ConanVersion::V1).build();
+    assert_eq!(command.args().unwrap(), ["export", "."]);
+
+    let command = ExportCommandBuilder::new()
+        .with_recipe_path(PathBuf::from("path/to/recipe"))
+        .with_user("devolutions")
+        .with_channel("stable")
+        .conan_version(ConanVersion::V1)
+        .build();
+    assert_eq!(
+        command.args().unwrap(),
+        ["export", "path/to/recipe", "devolutions/stable"]
+    );
+
+    let command = ExportCommandBuilder::new()
+        .with_recipe_path(PathBuf::from("path/to/recipe"))
+        .with_user("devolutions")
+        .with_channel("stable")
+        .conan_version(ConanVersion::V2)
+        .build();
+    assert_eq!(
+        command.args().unwrap(),
+        [
+            "export",
+            "path/to/recipe",
+            "--user",
+            "devolutions",
+            "--channel",
+            "stable"
+        ]
+    );
+
+    // NOTE: Conan 1 rejects a user without a channel
+    let command = ExportCommandBuilder::new()
+        .with_user("devolutions")
+        .conan_version(ConanVersion::V1)
+        .build();
+    assert!(matches!(command.args(), Err(ConanExportError::IncompleteUserChannel)));
+    let command = ExportPkgCommandBuilder::new()
+        .with_user("devolutions")
+        .conan_version(ConanVersion::V1)
+        .build();
+    assert!(matches!(command.args(), Err(ConanExportError::IncompleteUserChannel)));
+}
+
+#[test]
+fn test_export_pkg_args_v1() {
+    let command = ExportPkgCommandBuilder::new()
+        .with_recipe_path(PathBuf::from("path/to/recipe"))
+        .with_user("devolutions")
+        .with_channel("stable")
+        .with_build_path(PathBuf::from("path/to/build"))
+        .with_package_path(PathBuf::from("path/to/package"))
+        .with_profile("linux-x86_64")
+        .build_settings(BuildSettings::new().build_type(BuildType::Release))
+        .force(true)
+        .conan_version(ConanVersion::V1)
+        .build();
+
+    assert_eq!(
+        command.args().unwrap(),
+        [
+            "export-pkg",
+            "path/to/recipe",
+            "devolutions/stable",
+            "--build-folder",
+            "path/to/build",
+            "--package-folder",
+            "path/to/package",
+            "--profile:host",
+            "linux-x86_64",
+            "-s",
+            "build_type=Release",
+            "--force"
+        ]
+    );
+}
+
+#[test]
+fn test_export_pkg_args_v2() {
+    let command = ExportPkgCommandBuilder::new()
+        .with_user("devolutions")
+        .with_host_profile("windows-x86_64")
+        .with_build_profile("linux-x86_64")
+        .conan_version(ConanVersion::V2)
+        .build();
+
+    assert_eq!(
+        command.args().unwrap(),
+        [
+            "export-pkg",
+            ".",
+            "--user",
+            "devolutions",
+            "--profile:host",
+            "windows-x86_64",
+            "--profile:build",
+            "linux-x86_64"
+        ]
+    );
+
+    let command = ExportPkgCommandBuilder::new()
+        .with_build_path(PathBuf::from("build"))
+        .conan_version(ConanVersion::V2)
+        .build();
+    assert!(matches!(
+        command.args(),
+        Err(ConanExportError::UnsupportedArgument("--build-folder"))
+    ));
+
+    let command = ExportPkgCommandBuilder::new()
+        .force(true)
+        .conan_version(ConanVersion::V2)
+        .build();
+    assert!(matches!(
+        command.args(),
+        Err(ConanExportError::UnsupportedArgument("--force"))
+    ));
+}
+
+#[test]
+fn test_exported_package_from_output() {
+    let output_v1 = "\
+bridge/0.1.0@devolutions/stable: Exporting package recipe
+bridge/0.1.0@devolutions/stable: A new conanfile.py version was exported
+bridge/0.1.0@devolutions/stable: Exported revision: 5c3e1f0a9b8d7c6e5f4a3b2c1d0e9f8a
+bridge/0.1.0@devolutions/stable: Package '3fb49604f9c2f729b85ba3115852006824e72cab' created
+bridge/0.1.0@devolutions/stable: Created package revision 0a1b2c3d4e5f6a7b8c9d0e1f2a3b4c5d
+";
+    let exported = ExportedPackage::from_output(output_v1).unwrap().unwrap();
+    assert_eq!(
+        exported.reference.to_string(),
+        "bridge/0.1.0@devolutions/stable#5c3e1f0a9b8d7c6e5f4a3b2c1d0e9f8a"
+    );
+    assert_eq!(
+        exported.package_id.as_deref(),
+        Some("3fb49604f9c2f729b85ba3115852006824e72cab")
+    );
+
+    let output_v2 = "\
+======== Exporting recipe to the cache ========
+bridge/0.1.0: Exporting package recipe: /src/bridge/conanfile.py
+bridge/0.1.0: Copied 1 '.py' file: conanfile.py
+bridge/0.1.0: Exported to cache folder: /home/ci/.conan2/p/bridge1a2b3c4d5e6f7/e
+bridge/0.1.0: Exported: bridge/0.1.0#5c3e1f0a9b8d7c6e5f4a3b2c1d0e9f8a (2023-11-02 14:21:09 UTC)
+";
+    let exported = ExportedPackage::from_output(output_v2).unwrap().unwrap();
+    assert_eq!(
+        exported.reference.to_string(),
+        "bridge/0.1.0#5c3e1f0a9b8d7c6e5f4a3b2c1d0e9f8a"
+    );
+    assert_eq!(exported.package_id, None);
+
+    assert_eq!(ExportedPackage::from_output("ERROR: something went wrong"), Ok(None));
+    assert!(matches!(
+        ExportedPackage::from_output("bridge: Exported revision: 5c3e1f0a9b8d7c6e5f4a3b2c1d0e9f8a"),
+        Err(ReferenceError::MissingVersion(_))
+    ));
+}
@@ -0,0 +1,98 @@
+#[cfg(test)]
+mod tests;
+
+use std::env;
+
+use crate::options::{ConanOption, OptionError, OptionValue};
+use crate::reference::ConanReference;
+
+/// Whether a Cargo feature of the crate being built is enabled, from its `CARGO_FEATURE_<NAME>` variable.
+pub fn feature_enabled(feature: &str) -> bool {
+    env::var_os(feature_env_var(feature)).is_some()
+}
+
+/// Name of the variable Cargo sets for an enabled feature: upper-cased, with `-` replaced by `_`.
+pub fn feature_env_var(feature: &str) -> String {
+    format!("CARGO_FEATURE_{}", feature.replace('-', "_").to_uppercase())
+}
+
+/// Conan options and requirements that depend on a Cargo feature
+///
+/// ```
+/// use conan::FeatureMapping;
+///
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// let ssl = FeatureMapping::new("ssl")
+///     .with_option("libcurl:with_ssl", "openssl")?
+///     .with_requires("openssl/3.2.0".parse()?);
+/// let shared = FeatureMapping::new("shared").with_bool_option("libcurl:shared")?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone, Debug, PartialEq)]
+pub struct FeatureMapping {
+    feature: String,
+    options: Vec<ConanOption>,
+    bool_options: Vec<ConanOption>,
+    requires: Vec<ConanReference>,
+}
+
+impl FeatureMapping {
+    pub fn new(feature: &str) -> Self {
+        FeatureMapping {
+            feature: feature.to_string(),
+            options: Vec::new(),
+            bool_options: Vec::new(),
+            requires: Vec::new(),
+        }
+    }
+
+    pub fn feature(&self) -> &str {
+        &self.feature
+    }
+
+    /// Set an option, e.g. `libcurl:with_ssl`, when the feature is enabled.
+    pub fn with_option<V: Into<OptionValue>>(mut self, name: &str, value: V) -> Result<Self, OptionError> {
+        self.options.push(ConanOption::from_qualified_name(name, value.into())?);
+        Ok(self)
+    }
+
+    /// Set a boolean option to `True` when the feature is enabled, and to `False` otherwise.
+    pub fn with_bool_option(mut self, name: &str) -> Result<Self, OptionError> {
+        self.bool_options
+            .push(ConanOption::from_qualified_name(name, OptionValue::Bool(true))?);
+        Ok(self)
+    }
+
+    /// Require a package when the feature is enabled.
+    pub fn with_requires(mut self, reference: ConanReference) -> Self {
+        self.requires.push(reference);
+        self
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        feature_enabled(&self.feature)
+    }
+
+    /// Options to pass for the given state of the feature.
+    pub fn options(&self, enabled: bool) -> Vec<ConanOption> {
+        let bool_options = self.bool_options.iter().map(|option| ConanOption {
+            value: OptionValue::Bool(enabled),
+            ..option.clone()
+        });
+        if enabled {
+            self.options.iter().cloned().chain(bool_options).collect()
+        } else {
+            bool_options.collect()
+        }
+    }
+
+    /// Requirements to add for the given state of the feature.
+    pub fn requires(&self, enabled: bool) -> &[ConanReference] {
+        if enabled {
+            &self.requires
+        } else {
+            &[]
+        }
+    }
+}
@@ -0,0 +1,46 @@
+use super::*;
+
+#[test]
+fn test_feature_env_var() {
+    assert_eq!(feature_env_var("ssl"), "CARGO_FEATURE_SSL");
+    assert_eq!(feature_env_var("vendored-openssl"), "CARGO_FEATURE_VENDORED_OPENSSL");
+
+    env::set_var("CARGO_FEATURE_CONAN_RS_TEST_ENABLED", "1");
+    assert!(feature_enabled("conan-rs-test-enabled"));
+    assert!(!feature_enabled("conan-rs-test-disabled"));
+}
+
+#[test]
+fn test_feature_mapping() -> Result<(), Box<dyn std::error::Error>> {
+    let openssl: ConanReference = "openssl/3.2.0".parse()?;
+    let mapping = FeatureMapping::new("ssl")
+        .with_option("libcurl:with_ssl", "openssl")?
+        .with_bool_option("libcurl:with_zstd")?
+        .with_requires(openssl.clone());
+
+    assert_eq!(
+        mapping.options(true),
+        [
+            "libcurl:with_ssl=openssl".parse::<ConanOption>()?,
+            "libcurl:with_zstd=True".parse()?,
+        ]
+    );
+    assert_eq!(mapping.requires(true), [openssl]);
+
+    assert_eq!(
+        mapping.options(false),
+        ["libcurl:with_zstd=False".parse::<ConanOption>()?]
+    );
+    assert!(mapping.requires(false).is_empty());
+
+    assert_eq!(
+        FeatureMapping::new("ssl").with_option("libcurl:with ssl", "openssl"),
+        Err(OptionError::InvalidName("with ssl".to_string()))
+    );
+    assert_eq!(
+        FeatureMapping::new("zstd").with_bool_option("lib curl:with_zstd"),
+        Err(OptionError::InvalidPackage("lib curl".to_string()))
+    );
+
+    Ok(())
+}
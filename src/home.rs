@@ -0,0 +1,165 @@
+#[cfg(test)]
+mod tests;
+
+use std::env;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::process::ExitStatus;
+
+use thiserror::Error;
+
+use crate::context::{ConanContext, ConanContextError};
+use crate::install::fingerprint::Fingerprint;
+use crate::util::ConanVersion;
+
+/// Variable pointing Conan 1 to the directory holding its `.conan` folder
+pub const CONAN_USER_HOME_ENV: &str = "CONAN_USER_HOME";
+/// Variable pointing Conan 2 to its home folder
+pub const CONAN_HOME_ENV: &str = "CONAN_HOME";
+
+/// File recording the fingerprint of the configuration a home was seeded from
+const CONFIG_MARKER: &str = ".conan-rs-config";
+
+#[derive(Debug, Error)]
+pub enum ConanHomeError {
+    #[error("OUT_DIR is not set")]
+    OutDirNotFound,
+    #[error("Invalid Unicode in path")]
+    InvalidUnicodeInPath,
+    #[error("Failed to set up the Conan home: {0}")]
+    Io(#[from] io::Error),
+    #[error("conan config install failed with {0}")]
+    ConfigInstallFailed(ExitStatus),
+    #[error("conan profile detect failed with {0}")]
+    ProfileDetectFailed(ExitStatus),
+}
+
+/// Conan home isolated from the user one, e.g. to keep concurrent CI jobs from sharing a cache
+///
+/// The home is created on first use and reused afterwards. When a configuration is given,
+/// a directory or an archive, it is installed with `conan config install` the first time,
+/// and again when its path or contents change. Conan 2 homes also get a detected default profile.
+///
+/// Both `CONAN_USER_HOME` (Conan 1, `<home>/.conan`) and `CONAN_HOME` (Conan 2, `<home>/.conan2`)
+/// are passed to the commands, so the same home works with both versions.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ConanHome {
+    path: PathBuf,
+    config: Option<PathBuf>,
+}
+
+impl ConanHome {
+    pub fn new(path: &Path) -> Self {
+        ConanHome {
+            path: path.to_path_buf(),
+            config: None,
+        }
+    }
+
+    /// Home in `OUT_DIR/conan-home`.
+    pub fn in_out_dir() -> Result<Self, ConanHomeError> {
+        let out_dir = env::var_os("OUT_DIR").ok_or(ConanHomeError::OutDirNotFound)?;
+        Ok(ConanHome::new(&Path::new(&out_dir).join("conan-home")))
+    }
+
+    /// Seed the home from a configuration directory or archive.
+    pub fn with_config(mut self, config: &Path) -> Self {
+        self.config = Some(config.to_path_buf());
+        self
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Folder Conan uses as its home: `<home>/.conan` for Conan 1, `<home>/.conan2` for Conan 2.
+    pub fn conan_dir(&self, conan_version: ConanVersion) -> PathBuf {
+        match conan_version {
+            ConanVersion::V1 => self.path.join(".conan"),
+            ConanVersion::V2 => self.path.join(".conan2"),
+        }
+    }
+
+    /// Environment variables to pass to Conan.
+    pub fn env(&self) -> Vec<(&'static str, PathBuf)> {
+        vec![
+            (CONAN_USER_HOME_ENV, self.path.clone()),
+            (CONAN_HOME_ENV, self.conan_dir(ConanVersion::V2)),
+        ]
+    }
+
+    /// Environment variables as strings, for an invocation.
+    pub(crate) fn env_vars(&self) -> Result<Vec<(String, String)>, ConanHomeError> {
+        self.env()
+            .into_iter()
+            .map(|(name, value)| {
+                let value = value.to_str().ok_or(ConanHomeError::InvalidUnicodeInPath)?;
+                Ok((name.to_string(), value.to_string()))
+            })
+            .collect()
+    }
+
+    /// Create the home, and seed it if it is new or its configuration changed.
+    ///
+    /// Conan runs with this home, and the executable, environment, working directory and timeout
+    /// of the context.
+    pub fn prepare(&self, context: &ConanContext) -> Result<(), ConanContextError> {
+        fs::create_dir_all(&self.path).map_err(ConanHomeError::Io)?;
+        let context = &context.clone().with_home(self.clone());
+
+        if let Some(config) = &self.config {
+            let config_str = config.to_str().ok_or(ConanHomeError::InvalidUnicodeInPath)?;
+            let marker = self.path.join(CONFIG_MARKER);
+            let fingerprint = config_fingerprint(config).map_err(ConanHomeError::Io)?;
+            if fs::read_to_string(&marker).ok().as_deref() != Some(fingerprint.as_str()) {
+                let status = run(context, &["config", "install", config_str])?;
+                if !status.success() {
+                    return Err(ConanHomeError::ConfigInstallFailed(status).into());
+                }
+                fs::write(&marker, fingerprint).map_err(ConanHomeError::Io)?;
+            }
+        }
+
+        // NOTE: Conan 1 detects the default profile by itself, Conan 2 fails without one
+        let conan_version = context.conan_version();
+        let default_profile = self.conan_dir(conan_version).join("profiles").join("default");
+        if conan_version == ConanVersion::V2 && !default_profile.is_file() {
+            let status = run(context, &["profile", "detect"])?;
+            if !status.success() {
+                return Err(ConanHomeError::ProfileDetectFailed(status).into());
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Run Conan through the context, without preparing the home again.
+fn run(context: &ConanContext, args: &[&str]) -> Result<ExitStatus, ConanContextError> {
+    let args: Vec<String> = args.iter().map(|arg| arg.to_string()).collect();
+    Ok(context.run_once(&context.invocation(&args)?)?.status)
+}
+
+/// Fingerprint of a configuration path and of the files it holds, an archive or a directory tree.
+///
+/// A path that doesn't exist, e.g. a URL, only contributes its name.
+fn config_fingerprint(config: &Path) -> io::Result<String> {
+    let mut fingerprint = Fingerprint::new();
+    fingerprint.add("config", config.to_string_lossy().as_bytes());
+    let mut pending = vec![config.to_path_buf()];
+    while let Some(path) = pending.pop() {
+        if path.is_dir() {
+            let mut entries = fs::read_dir(&path)?
+                .map(|entry| entry.map(|entry| entry.path()))
+                .collect::<io::Result<Vec<_>>>()?;
+            // NOTE: Sorted in reverse, so that popping visits the entries in order
+            entries.sort_by(|a, b| b.cmp(a));
+            pending.extend(entries);
+        } else if path.is_file() {
+            let name = path.strip_prefix(config).unwrap_or(&path);
+            fingerprint.add(&name.to_string_lossy(), &fs::read(&path)?);
+        }
+    }
+    Ok(fingerprint.to_hex())
+}
@@ -0,0 +1,71 @@
+use super::*;
+use crate::executor::{ScriptedExecutor, ScriptedResponse};
+use std::time::Duration;
+
+#[test]
+fn test_conan_home_env() {
+    let home = ConanHome::new(Path::new("/tmp/conan-home"));
+    assert_eq!(
+        home.env(),
+        [
+            (CONAN_USER_HOME_ENV, PathBuf::from("/tmp/conan-home")),
+            (CONAN_HOME_ENV, PathBuf::from("/tmp/conan-home/.conan2")),
+        ]
+    );
+    assert_eq!(home.conan_dir(ConanVersion::V1), Path::new("/tmp/conan-home/.conan"));
+}
+
+#[test]
+fn test_conan_home_prepare() {
+    let dir = env::temp_dir().join(format!("conan-rs-home-{}", std::process::id()));
+    let _ = fs::remove_dir_all(&dir);
+
+    // NOTE: Conan 2 detects a default profile, which keeps it from being detected again
+    let home_dir = dir.join("home");
+    let conan_dir = home_dir.join(".conan2");
+    let executor = ScriptedExecutor::new()
+        .on(&["config", "install"], ScriptedResponse::success())
+        .on(
+            &["profile", "detect"],
+            ScriptedResponse::success().with_file(&conan_dir.join("profiles/default"), b""),
+        );
+    let context = ConanContext::new()
+        .with_program(Path::new("conan"))
+        .with_conan_version(ConanVersion::V2)
+        .with_env("CONAN_NON_INTERACTIVE", "1")
+        .with_timeout(Duration::from_secs(60))
+        .with_executor(executor.clone());
+    let home = ConanHome::new(&home_dir).with_config(Path::new("/etc/conan-config"));
+    home.prepare(&context).unwrap();
+    home.prepare(&context).unwrap();
+    let invocations = executor.invocations();
+    let args: Vec<String> = invocations.iter().map(|x| x.args.join(" ")).collect();
+    assert_eq!(args, ["config install /etc/conan-config", "profile detect"]);
+    let home_env = ("CONAN_HOME".to_string(), conan_dir.to_str().unwrap().to_string());
+    assert!(invocations.iter().all(|x| x.env.contains(&home_env)));
+    // NOTE: The invocations get the environment and timeout of the context
+    let env = ("CONAN_NON_INTERACTIVE".to_string(), "1".to_string());
+    assert!(invocations.iter().all(|x| x.env.contains(&env)));
+    assert!(invocations.iter().all(|x| x.timeout == Some(Duration::from_secs(60))));
+
+    // NOTE: A new configuration is installed again, the detected profile is kept
+    let home = home.with_config(Path::new("/etc/conan-config-2"));
+    home.prepare(&context).unwrap();
+    let invocations = executor.invocations();
+    assert_eq!(invocations.len(), 3);
+    assert_eq!(invocations[2].args, ["config", "install", "/etc/conan-config-2"]);
+
+    // NOTE: So is a configuration whose contents changed
+    let config = dir.join("config");
+    fs::create_dir_all(config.join("profiles")).unwrap();
+    fs::write(config.join("profiles/linux"), "[settings]\nos=Linux\n").unwrap();
+    let home = home.with_config(&config);
+    home.prepare(&context).unwrap();
+    home.prepare(&context).unwrap();
+    assert_eq!(executor.invocations().len(), 4);
+    fs::write(config.join("profiles/linux"), "[settings]\nos=Linux\narch=x86_64\n").unwrap();
+    home.prepare(&context).unwrap();
+    assert_eq!(executor.invocations().len(), 5);
+
+    fs::remove_dir_all(&dir).unwrap();
+}
@@ -3,16 +3,50 @@ mod test;
 
 pub mod build_info;
 
+pub mod build_policy;
+
+pub mod remote;
+
+pub(crate) mod fingerprint;
 mod profile;
-mod remote;
 
-use crate::util::find_program;
+use crate::conanfile::{ConanfileError, ConanfileTxt};
+use crate::conf::{ConanConf, ConanEnv, ConfEnv, ConfError, ConfValue};
+use crate::context::{ConanContext, ConanContextError};
+use crate::features::FeatureMapping;
+use crate::home::ConanHome;
+use crate::options::{ConanOption, OptionError, OptionValue};
+use crate::output::ConanEvent;
+use crate::reference::ConanReference;
+use crate::retry::RetryPolicy;
+use crate::util::ConanVersion;
+use std::cell::Cell;
 use std::env;
+use std::fs;
 use std::path::{Path, PathBuf};
-use std::process::Command;
+use std::process::ExitStatus;
+use std::time::Duration;
 use thiserror::Error;
 
+pub use build_policy::{BuildPolicy, BuildPolicyError, BuildRule};
+
 use build_info::{build_settings::BuildSettings, BuildInfo};
+use fingerprint::Fingerprint;
+use remote::{CredentialsProvider, RemoteCredentials, RemoteLoginCommandBuilder};
+
+/// Variables read by the command or Conan, besides the other `CONAN*` ones that are set
+const INPUT_ENV: &[&str] = &[
+    "CONAN",
+    "CONAN_HOME",
+    "CONAN_USER_HOME",
+    "CONAN_DEFAULT_PROFILE",
+    "CONAN_DEFAULT_BUILD_PROFILE",
+    "CONAN_DEFAULT_PROFILE_PATH",
+    // NOTE: The default Conan home, and its profiles, are in the user home
+    "HOME",
+    "USERPROFILE",
+    "PROFILE",
+];
 
 #[derive(Debug, Error)]
 pub enum ConanInstallError {
@@ -24,30 +58,58 @@ pub enum ConanInstallError {
     ConanInstallDirNotFound,
     #[error("Failed to convert output to UTF-8: {0}")]
     Utf8Error(#[from] std::string::FromUtf8Error),
+    #[error("No credentials available for remote {0}")]
+    MissingCredentials(String),
+    #[error("Failed to log into remote {0}")]
+    RemoteLoginFailed(String),
+    #[error("Build policy not supported by this Conan version")]
+    UnsupportedBuildPolicy,
+    #[error("Requirements can't be combined with a recipe path")]
+    RequiresWithRecipe,
+    #[error("conanbuildinfo.json is only generated by Conan 1")]
+    BuildInfoUnsupported,
+    #[error("Conan install failed with {0}")]
+    CommandFailed(ExitStatus),
+    #[error("Build info not found in the output directory")]
+    BuildInfoNotFound,
+    #[error("Failed to write conanfile.txt: {0}")]
+    ConanfileWriteFailed(#[from] ConanfileError),
+    #[error("Invalid Unicode in path")]
+    InvalidUnicodeInPath,
+    #[error(transparent)]
+    InvalidConf(#[from] ConfError),
+    #[error(transparent)]
+    Context(#[from] ConanContextError),
     #[error("{0}")]
     Other(String),
 }
 
-/// Conan build policy
-#[derive(Clone, PartialEq)]
-pub enum BuildPolicy {
-    Never,
-    Always,
-    Missing,
-    Outdated,
-}
-
 /// "conan install" command runner
+///
+/// `BuildInfo` is only supported with Conan 1, from its `json` generator: with Conan 2,
+/// `generate` and the other `generate*` methods fail with `ConanInstallError::BuildInfoUnsupported`.
+/// `args` supports both versions.
 pub struct InstallCommand<'a> {
     profile_host: Option<&'a str>,
     profile_build: Option<&'a str>,
     remote: Option<&'a str>,
     build_settings: BuildSettings,
     build_options: Option<Vec<&'a str>>,
+    options: Vec<ConanOption>,
+    conf_env: ConfEnv,
     build_policy: Option<BuildPolicy>,
     recipe_path: Option<PathBuf>,
     output_dir: Option<PathBuf>,
     update_check: bool,
+    remote_auth: bool,
+    remote_credentials: Option<CredentialsProvider<'a>>,
+    remote_authenticated: Cell<bool>,
+    requires: Vec<ConanReference>,
+    tool_requires: Vec<ConanReference>,
+    conanfile: Option<ConanfileTxt>,
+    lockfile: Option<PathBuf>,
+    lockfile_out: Option<PathBuf>,
+    context: ConanContext,
 }
 
 /// "conan install" command arguments builder
@@ -58,10 +120,21 @@ pub struct InstallCommandBuilder<'a> {
     remote: Option<&'a str>,
     build_settings: Option<BuildSettings>,
     build_options: Option<Vec<&'a str>>,
+    options: Vec<ConanOption>,
+    features: Vec<FeatureMapping>,
+    conf_env: ConfEnv,
     build_policy: Option<BuildPolicy>,
     recipe_path: Option<PathBuf>,
     output_dir: Option<PathBuf>,
     update_check: bool,
+    remote_auth: bool,
+    remote_credentials: Option<CredentialsProvider<'a>>,
+    requires: Vec<ConanReference>,
+    tool_requires: Vec<ConanReference>,
+    conanfile: Option<ConanfileTxt>,
+    lockfile: Option<PathBuf>,
+    lockfile_out: Option<PathBuf>,
+    context: ConanContext,
 }
 
 impl<'a> InstallCommandBuilder<'a> {
@@ -91,13 +164,32 @@ impl<'a> InstallCommandBuilder<'a> {
         self
     }
 
+    /// Log into the remote before the first install, using the credentials from the
+    /// `CONAN_LOGIN_USERNAME_<REMOTE>` and `CONAN_PASSWORD_<REMOTE>` environment variables.
+    pub fn authenticate_remote(mut self) -> Self {
+        self.remote_auth = true;
+        self
+    }
+
+    /// Log into the remote before the first install, using the credentials returned by the callback.
+    /// The environment variables are used when the callback returns `None`.
+    pub fn with_remote_credentials<F>(mut self, provider: F) -> Self
+    where
+        F: Fn(&str) -> Option<RemoteCredentials> + 'a,
+    {
+        self.remote_auth = true;
+        self.remote_credentials = Some(Box::new(provider));
+        self
+    }
+
     pub fn build_settings(mut self, build_settings: BuildSettings) -> Self {
         self.build_settings = Some(build_settings);
         self
     }
 
-    pub fn build_policy(mut self, build_policy: BuildPolicy) -> Self {
-        self.build_policy = Some(build_policy);
+    /// Set the build policy, e.g. `BuildRule::Missing` or `"missing,!openssl".parse()?`.
+    pub fn build_policy<P: Into<BuildPolicy>>(mut self, build_policy: P) -> Self {
+        self.build_policy = Some(build_policy.into());
         self
     }
 
@@ -110,11 +202,98 @@ impl<'a> InstallCommandBuilder<'a> {
         self
     }
 
+    /// Set an option, e.g. `with_option("libcurl:with_ssl", "openssl")` or `with_option("zlib:shared", true)`.
+    pub fn with_option<V: Into<OptionValue>>(mut self, name: &str, value: V) -> Result<Self, OptionError> {
+        self.options.push(ConanOption::from_qualified_name(name, value.into())?);
+        Ok(self)
+    }
+
+    pub fn with_conan_options(mut self, options: &[ConanOption]) -> Self {
+        self.options.extend_from_slice(options);
+        self
+    }
+
+    /// Set the options and requirements of a Cargo feature, depending on whether it is enabled.
+    ///
+    /// The requirements of enabled features are installed like the ones given to `with_requires`.
+    pub fn with_feature(mut self, feature: FeatureMapping) -> Self {
+        self.features.push(feature);
+        self
+    }
+
+    /// Set a configuration item, e.g. `with_conf("tools.cmake.cmaketoolchain:generator", "Ninja")`.
+    pub fn with_conf<V: Into<ConfValue>>(mut self, name: &str, value: V) -> Self {
+        self.conf_env.conf.push(ConanConf::new(name, value));
+        self
+    }
+
+    /// Set a configuration item for the packages matching a name or pattern.
+    pub fn with_package_conf<V: Into<ConfValue>>(mut self, package: &str, name: &str, value: V) -> Self {
+        self.conf_env
+            .conf
+            .push(ConanConf::new(name, value).for_package(package));
+        self
+    }
+
+    /// Set an environment variable for the package builds, e.g. `with_env("CC", "clang")`.
+    ///
+    /// Conan 1 takes it with `-e`, Conan 2 from the `[buildenv]` of a generated profile.
+    pub fn with_env(mut self, name: &str, value: &str) -> Self {
+        self.conf_env.env.push(ConanEnv::new(name, value));
+        self
+    }
+
+    /// Set an environment variable for the builds of the packages matching a name or pattern.
+    pub fn with_package_env(mut self, package: &str, name: &str, value: &str) -> Self {
+        self.conf_env.env.push(ConanEnv::new(name, value).for_package(package));
+        self
+    }
+
     pub fn recipe_path(mut self, recipe_path: &Path) -> Self {
         self.recipe_path = Some(recipe_path.to_path_buf());
         self
     }
 
+    /// Install these packages instead of the requirements of a recipe file.
+    ///
+    /// Conan 1 installs them from a `conanfile.txt` generated in the output directory,
+    /// Conan 2 takes them with `--requires`.
+    pub fn with_requires(mut self, requires: &[ConanReference]) -> Self {
+        self.requires.extend_from_slice(requires);
+        self
+    }
+
+    /// Install these tool packages instead of the tool requirements of a recipe file.
+    pub fn with_tool_requires(mut self, tool_requires: &[ConanReference]) -> Self {
+        self.tool_requires.extend_from_slice(tool_requires);
+        self
+    }
+
+    /// Install from a copy of this `conanfile.txt` written in the output directory.
+    ///
+    /// Requirements given to `with_requires` and `with_tool_requires` are added to the copy,
+    /// replacing the ones on the same packages. Relative paths in the file, e.g. in `[imports]`,
+    /// are resolved from the output directory.
+    pub fn with_conanfile_txt(mut self, conanfile: ConanfileTxt) -> Self {
+        self.conanfile = Some(conanfile);
+        self
+    }
+
+    /// Resolve the dependencies from a lockfile, e.g. a checked-in `conan.lock`.
+    ///
+    /// Conan fails when a requirement is missing from the lockfile. Conan 1 takes the profiles,
+    /// settings, options, conf and environment from the lockfile, so the ones given to the builder are ignored.
+    pub fn with_lockfile(mut self, lockfile: &Path) -> Self {
+        self.lockfile = Some(lockfile.to_path_buf());
+        self
+    }
+
+    /// Write the lockfile of the resolved dependencies.
+    pub fn lockfile_out(mut self, lockfile_out: &Path) -> Self {
+        self.lockfile_out = Some(lockfile_out.to_path_buf());
+        self
+    }
+
     pub fn output_dir(mut self, output_dir: &Path) -> Self {
         self.output_dir = Some(output_dir.to_path_buf());
         self
@@ -125,83 +304,275 @@ impl<'a> InstallCommandBuilder<'a> {
         self
     }
 
+    /// Run `conan install` through a shared context, keeping the timeout, output handling, retry policy,
+    /// home and version set on this builder.
+    pub fn with_context(mut self, context: ConanContext) -> Self {
+        self.context = context.merged_with(self.context);
+        self
+    }
+
+    /// Kill Conan if it runs longer than `timeout`, failing `try_generate` with `ConanContextError::Timeout`.
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.context = self.context.with_timeout(timeout);
+        self
+    }
+
+    /// Pass the events parsed from the Conan output to `handler`, e.g. `cargo_progress`.
+    pub fn with_output_handler<F>(mut self, handler: F) -> Self
+    where
+        F: Fn(&ConanEvent) + Send + Sync + 'static,
+    {
+        self.context = self.context.with_output_handler(handler);
+        self
+    }
+
+    /// Append the complete Conan output to this file instead of `OUT_DIR/conan.log`.
+    pub fn with_log_file(mut self, log_file: &Path) -> Self {
+        self.context = self.context.with_log_file(log_file);
+        self
+    }
+
+    /// Run the command again on network failures, see `RetryPolicy`.
+    pub fn with_retry(mut self, retry: RetryPolicy) -> Self {
+        self.context = self.context.with_retry(retry);
+        self
+    }
+
+    /// Run Conan with an isolated home, e.g. `ConanHome::in_out_dir()?`.
+    pub fn with_conan_home(mut self, conan_home: ConanHome) -> Self {
+        self.context = self.context.with_home(conan_home);
+        self
+    }
+
+    pub fn conan_version(mut self, conan_version: ConanVersion) -> Self {
+        self.context = self.context.with_conan_version(conan_version);
+        self
+    }
+
     pub fn build(self) -> InstallCommand<'a> {
+        let mut options = self.options;
+        let mut requires = self.requires;
+        for feature in &self.features {
+            let enabled = feature.is_enabled();
+            options.extend(feature.options(enabled));
+            for reference in feature.requires(enabled) {
+                if !requires.contains(reference) {
+                    requires.push(reference.clone());
+                }
+            }
+        }
+
         InstallCommand {
             profile_host: self.profile_host,
             profile_build: self.profile_build,
             remote: self.remote,
             build_settings: self.build_settings.unwrap_or_default(),
             build_options: self.build_options,
+            options,
+            conf_env: self.conf_env,
             build_policy: self.build_policy,
             recipe_path: self.recipe_path,
             output_dir: self.output_dir,
             update_check: self.update_check,
+            remote_auth: self.remote_auth,
+            remote_credentials: self.remote_credentials,
+            remote_authenticated: Cell::new(false),
+            requires,
+            tool_requires: self.tool_requires,
+            conanfile: self.conanfile,
+            lockfile: self.lockfile,
+            lockfile_out: self.lockfile_out,
+            context: self.context,
         }
     }
 }
 
 impl<'a> InstallCommand<'a> {
     pub fn args(&self) -> Result<Vec<String>, ConanInstallError> {
+        let conan_version = self.context.conan_version();
         let mut args: Vec<&str> = Vec::new();
 
         args.push("install");
-        args.extend(&["-g", "json"]);
+        if conan_version == ConanVersion::V1 {
+            args.extend(&["-g", "json"]);
+        }
+
+        // NOTE: Conan 1 refuses profiles, settings, options, conf and env along with a lockfile
+        let locked_config = conan_version == ConanVersion::V1 && self.lockfile.is_some();
 
-        if let Some(profile) = &self.profile_host {
+        if let Some(profile) = self.profile_host.filter(|_| !locked_config) {
             args.extend(&["--profile:host", profile]);
         }
 
-        if let Some(profile) = &self.profile_build {
+        if let Some(profile) = self.profile_build.filter(|_| !locked_config) {
             args.extend(&["--profile:build", profile]);
         }
 
+        let env_profile = self.env_profile().ok_or(ConanInstallError::ConanInstallDirNotFound)?;
+        let env_profile = env_profile.to_str().ok_or(ConanInstallError::InvalidUnicodeInPath)?;
+        let conf_env_args = if locked_config {
+            Vec::new()
+        } else {
+            self.conf_env
+                .args(conan_version, env_profile, self.profile_host.is_none())?
+        };
+        args.extend(conf_env_args.iter().map(String::as_str));
+
         if let Some(remote) = &self.remote {
             args.extend(&["-r", remote]);
         }
 
         if self.update_check {
-            args.push("-u");
+            args.push(match conan_version {
+                ConanVersion::V1 => "-u",
+                ConanVersion::V2 => "--update",
+            });
         }
 
-        if let Some(build_policy) = &self.build_policy {
-            match build_policy {
-                BuildPolicy::Never => {
-                    args.extend(&["-b", "never"]);
-                }
-                BuildPolicy::Always => {
-                    args.extend(&["-b"]);
-                }
-                BuildPolicy::Missing => {
-                    args.extend(&["-b", "missing"]);
-                }
-                BuildPolicy::Outdated => {
-                    args.extend(&["-b", "outdated"]);
-                }
-            }
-        }
+        let policy_args = match &self.build_policy {
+            Some(build_policy) => build_policy
+                .args(conan_version)
+                .ok_or(ConanInstallError::UnsupportedBuildPolicy)?,
+            None => Vec::new(),
+        };
+        args.extend(policy_args.iter().map(String::as_str));
 
-        if let Some(build_options) = &self.build_options {
-            args.extend(build_options.iter().map(|x| ["-o", *x]).flatten());
+        if let Some(build_options) = self.build_options.as_ref().filter(|_| !locked_config) {
+            args.extend(build_options.iter().flat_map(|x| ["-o", *x]));
         }
 
+        let option_args: Vec<String> = if locked_config {
+            Vec::new()
+        } else {
+            self.options.iter().map(|x| x.to_cli_string(conan_version)).collect()
+        };
+        args.extend(option_args.iter().flat_map(|x| ["-o", x.as_str()]));
+
+        let lockfile_args = self.lockfile_args()?;
+        args.extend(lockfile_args.iter().map(String::as_str));
+
         let output_dir = self.output_dir();
         if let Some(output_dir) = &output_dir {
             let current_dir = env::current_dir()?.to_path_buf();
             if output_dir != &current_dir {
-                args.extend(&["-if", output_dir.to_str().unwrap()]);
+                let output_dir = output_dir.to_str().ok_or(ConanInstallError::InvalidUnicodeInPath)?;
+                match conan_version {
+                    ConanVersion::V1 => args.extend(&["-if", output_dir]),
+                    ConanVersion::V2 => args.extend(&["--output-folder", output_dir]),
+                }
             }
         }
 
-        let build_settings_args = self.build_settings.args();
+        let build_settings_args = if locked_config {
+            Vec::new()
+        } else {
+            self.build_settings.args()
+        };
         args.extend(build_settings_args.iter().map(String::as_str));
 
+        let has_requires = !self.requires.is_empty() || !self.tool_requires.is_empty();
+        if (has_requires || self.conanfile.is_some()) && self.recipe_path.is_some() {
+            return Err(ConanInstallError::RequiresWithRecipe);
+        }
+
+        let requires_file = self.requires_file();
+        let requires_args: Vec<String> = self
+            .requires
+            .iter()
+            .map(|x| format!("--requires={}", x))
+            .chain(self.tool_requires.iter().map(|x| format!("--tool-requires={}", x)))
+            .collect();
+
         if let Some(recipe_path) = &self.recipe_path {
-            args.push(recipe_path.to_str().unwrap());
+            args.push(recipe_path.to_str().ok_or(ConanInstallError::InvalidUnicodeInPath)?);
+        } else if self.generated_conanfile(conan_version).is_some() {
+            let requires_file = requires_file
+                .as_ref()
+                .ok_or(ConanInstallError::ConanInstallDirNotFound)?;
+            args.push(requires_file.to_str().ok_or(ConanInstallError::InvalidUnicodeInPath)?);
+        } else if has_requires {
+            args.extend(requires_args.iter().map(String::as_str));
         }
 
         Ok(args.iter().map(|x| x.to_string()).collect())
     }
 
+    fn lockfile_args(&self) -> Result<Vec<String>, ConanInstallError> {
+        let mut args = Vec::new();
+        if let Some(lockfile) = &self.lockfile {
+            let lockfile = lockfile.to_str().ok_or(ConanInstallError::InvalidUnicodeInPath)?;
+            args.push(format!("--lockfile={}", lockfile));
+        }
+        if let Some(lockfile_out) = &self.lockfile_out {
+            let lockfile_out = lockfile_out.to_str().ok_or(ConanInstallError::InvalidUnicodeInPath)?;
+            args.push(format!("--lockfile-out={}", lockfile_out));
+        }
+        Ok(args)
+    }
+
+    /// Lockfile the dependencies are resolved from.
+    pub fn lockfile(&self) -> Option<&Path> {
+        self.lockfile.as_deref()
+    }
+
+    /// `conanfile.txt` generated from `with_conanfile_txt`, or for Conan 1 from `with_requires`.
+    pub fn requires_file(&self) -> Option<PathBuf> {
+        let mut requires_file = self.output_dir()?;
+        requires_file.push("conan-requires");
+        requires_file.push("conanfile.txt");
+        Some(requires_file)
+    }
+
+    /// Recipe to install from, when the command generates one.
+    ///
+    /// Conan 2 takes the requirements on the command line, unless a `conanfile.txt` was given.
+    fn generated_conanfile(&self, conan_version: ConanVersion) -> Option<ConanfileTxt> {
+        let has_requires = !self.requires.is_empty() || !self.tool_requires.is_empty();
+        let mut conanfile = match &self.conanfile {
+            Some(conanfile) => conanfile.clone(),
+            None if has_requires && conan_version == ConanVersion::V1 => {
+                let mut conanfile = ConanfileTxt::new();
+                // NOTE: Conan 1 only knows "[tool_requires]" since 1.37
+                conanfile.use_build_requires_section(true);
+                conanfile
+            }
+            None => return None,
+        };
+
+        for reference in &self.requires {
+            conanfile.add_requires(reference.clone());
+        }
+        for reference in &self.tool_requires {
+            conanfile.add_tool_requires(reference.clone());
+        }
+        Some(conanfile)
+    }
+
+    /// Profile holding the environment for Conan 2.
+    pub fn env_profile(&self) -> Option<PathBuf> {
+        let mut env_profile = self.output_dir()?;
+        env_profile.push("conan-profiles");
+        env_profile.push("buildenv");
+        Some(env_profile)
+    }
+
+    /// Write the generated `conanfile.txt` and environment profile, if any.
+    fn write_requires_file(&self) -> Result<(), ConanInstallError> {
+        let conan_version = self.context.conan_version();
+        if let Some(conanfile) = self.generated_conanfile(conan_version) {
+            let requires_file = self.requires_file().ok_or(ConanInstallError::ConanInstallDirNotFound)?;
+            conanfile.write(&requires_file)?;
+        }
+        let env_profile = self.env_profile().ok_or(ConanInstallError::ConanInstallDirNotFound)?;
+        self.conf_env.write_env_profile(conan_version, &env_profile)?;
+        Ok(())
+    }
+
+    /// Typed options passed to Conan, to check with `BuildInfo::check_options` after the install.
+    pub fn options(&self) -> &[ConanOption] {
+        &self.options
+    }
+
     pub fn output_dir(&self) -> Option<PathBuf> {
         self.output_dir
             .clone()
@@ -215,19 +586,233 @@ impl<'a> InstallCommand<'a> {
         Some(output_file)
     }
 
+    /// Fingerprint of the inputs, stored next to the build info by `generate_if_changed`.
+    pub fn fingerprint_file(&self) -> Option<PathBuf> {
+        let mut fingerprint_file = self.output_dir()?;
+        fingerprint_file.push("conanbuildinfo.fingerprint");
+        Some(fingerprint_file)
+    }
+
+    /// Files read by `conan install`: the recipe, the profiles and the lockfile.
+    ///
+    /// Profiles are given by path, or by name from the profiles of the Conan home,
+    /// the default one standing in for a missing profile.
+    pub fn input_files(&self) -> Vec<PathBuf> {
+        let resolve = |path: &Path| self.context.resolve_path(path);
+        let mut files = Vec::new();
+        if let Some(recipe_path) = &self.recipe_path {
+            let recipe_path = resolve(recipe_path);
+            if recipe_path.is_dir() {
+                let recipes = ["conanfile.py", "conanfile.txt"]
+                    .iter()
+                    .map(|name| recipe_path.join(name));
+                files.extend(recipes.filter(|recipe| recipe.is_file()));
+            } else {
+                files.push(recipe_path);
+            }
+        }
+        for profile in [self.profile_host, self.profile_build] {
+            let profile = profile::profile_path(&self.context, profile.unwrap_or("default"));
+            if let Some(profile) = profile.filter(|profile| !files.contains(profile)) {
+                files.push(profile);
+            }
+        }
+        if let Some(lockfile) = &self.lockfile {
+            files.push(resolve(lockfile));
+        }
+        files
+    }
+
+    /// Environment variables read by the command or Conan: the executable, the Conan and user homes,
+    /// the default profiles, the Cargo `PROFILE`, the remote credentials, and the other `CONAN*` ones that are set.
+    pub fn input_env(&self) -> Vec<String> {
+        let mut names: Vec<String> = INPUT_ENV.iter().map(|name| name.to_string()).collect();
+        if self.output_dir.is_none() {
+            names.push("OUT_DIR".to_string());
+        }
+        if let Some(remote) = self.remote.filter(|_| self.remote_auth) {
+            let suffix = remote::remote_env_suffix(remote);
+            for prefix in ["CONAN_LOGIN_USERNAME", "CONAN_PASSWORD"] {
+                names.push(format!("{}_{}", prefix, suffix));
+                names.push(prefix.to_string());
+            }
+        }
+        let conan_vars = env::vars_os().filter_map(|(name, _)| name.into_string().ok());
+        names.extend(conan_vars.filter(|name| name.starts_with("CONAN")));
+        names.sort();
+        names.dedup();
+        names
+    }
+
+    /// Tell Cargo to run the build script again when an input of the install changes,
+    /// with `cargo:rerun-if-changed` for the `input_files` and `cargo:rerun-if-env-changed`
+    /// for the `input_env`.
+    ///
+    /// `generate` and the other `generate*` methods call it. Cargo then no longer runs the build
+    /// script again on any change in the package, so the other inputs of the script, like `build.rs`,
+    /// must be emitted too.
+    pub fn cargo_emit_inputs(&self) {
+        for file in self.input_files() {
+            println!("cargo:rerun-if-changed={}", file.display());
+        }
+        for name in self.input_env() {
+            println!("cargo:rerun-if-env-changed={}", name);
+        }
+    }
+
+    /// Fingerprint of the inputs of the install: arguments, recipe, profiles, lockfile,
+    /// generated `conanfile.txt` and environment profile, Conan version, and the `input_env`.
+    ///
+    /// Files included by the recipe or the profiles are not taken into account.
+    pub fn fingerprint(&self) -> Result<String, ConanInstallError> {
+        let args = self.args()?;
+        let mut fingerprint = Fingerprint::new();
+        fingerprint.add("args", args.join("\0").as_bytes());
+        fingerprint.add("version", self.context.version().unwrap_or_default().as_bytes());
+        for file in self.input_files() {
+            // NOTE: A missing file is hashed as empty, Conan will fail on it anyway
+            fingerprint.add(&file.to_string_lossy(), &fs::read(&file).unwrap_or_default());
+        }
+        if let Some(conanfile) = self.generated_conanfile(self.context.conan_version()) {
+            fingerprint.add("conanfile.txt", conanfile.to_string().as_bytes());
+        }
+        fingerprint.add("buildenv", self.conf_env.env_profile().as_bytes());
+
+        for name in self.input_env() {
+            if let Some(value) = env::var_os(&name) {
+                fingerprint.add(&name, value.to_string_lossy().as_bytes());
+            }
+        }
+        for (name, value) in self.context.invocation(&args)?.env {
+            fingerprint.add(&name, value.as_bytes());
+        }
+        Ok(fingerprint.to_hex())
+    }
+
+    /// Log into the configured remote, once per command, if remote authentication was requested.
+    pub fn authenticate(&self) -> Result<(), ConanInstallError> {
+        let remote = match self.remote {
+            Some(remote) if self.remote_auth && !self.remote_authenticated.get() => remote,
+            _ => return Ok(()),
+        };
+
+        let mut login = RemoteLoginCommandBuilder::new(remote).with_context(self.context.clone());
+        if let Some(provider) = &self.remote_credentials {
+            login = login.with_credentials_provider(move |remote| provider(remote));
+        }
+        login.build().run()?;
+
+        self.remote_authenticated.set(true);
+        Ok(())
+    }
+
+    /// Fail on Conan 2, which has no `json` generator to write `conanbuildinfo.json`.
+    fn check_build_info(&self) -> Result<(), ConanInstallError> {
+        match self.context.conan_version() {
+            ConanVersion::V1 => Ok(()),
+            ConanVersion::V2 => Err(ConanInstallError::BuildInfoUnsupported),
+        }
+    }
+
+    /// Log into the remote and write the generated files needed by `conan install`.
+    fn prepare(&self) -> Result<(), ConanInstallError> {
+        self.check_build_info()?;
+        self.authenticate()?;
+        self.write_requires_file()
+    }
+
+    /// Run `conan install` and read the build info it generated.
+    ///
+    /// Unlike `generate`, the failure is returned, e.g. `ConanContextError::Timeout` when Conan
+    /// runs longer than `with_timeout`. The inputs of the install are emitted first, with `cargo_emit_inputs`.
+    pub fn try_generate(&self) -> Result<BuildInfo, ConanInstallError> {
+        self.cargo_emit_inputs();
+        self.install()
+    }
+
+    /// Run `conan install` and read the build info it generated, without emitting the inputs.
+    fn install(&self) -> Result<BuildInfo, ConanInstallError> {
+        self.prepare()?;
+        let args = self.args()?;
+        let output_file = self.output_file().ok_or(ConanInstallError::ConanInstallDirNotFound)?;
+        let output = self.context.execute(&args)?;
+        read_build_info(output.status, &output_file)
+    }
+
     pub fn generate(&self) -> Option<BuildInfo> {
-        let args = self.args().ok()?;
-        let program = find_program()?;
-        let output_file = self.output_file()?;
-        let mut command = Command::new(program);
-        if command.args(args).status().is_ok() {
-            BuildInfo::from_file(output_file.as_path())
-        } else {
-            None
+        self.try_generate().map_err(|error| eprintln!("{}", error)).ok()
+    }
+
+    /// Async `try_generate`, with `tokio::process`.
+    ///
+    /// The remote login, requires file and arguments are handled on the call, `conan install`
+    /// runs when the future is polled. Dropping the future kills Conan.
+    #[cfg(feature = "tokio")]
+    pub fn try_generate_async(
+        &self,
+    ) -> impl std::future::Future<Output = Result<BuildInfo, ConanInstallError>> + Send + 'static {
+        self.cargo_emit_inputs();
+        let install = self.prepare().and_then(|_| {
+            let args = self.args()?;
+            let output_file = self.output_file().ok_or(ConanInstallError::ConanInstallDirNotFound)?;
+            Ok((self.context.execute_async(&args), output_file))
+        });
+        async move {
+            let (install, output_file) = install?;
+            let output = install.await?;
+            read_build_info(output.status, &output_file)
         }
     }
 
+    /// Async `generate`, with `tokio::process`, see `try_generate_async`.
+    #[cfg(feature = "tokio")]
+    pub fn generate_async(&self) -> impl std::future::Future<Output = Option<BuildInfo>> + Send + 'static {
+        let install = self.try_generate_async();
+        async move { install.await.map_err(|error| eprintln!("{}", error)).ok() }
+    }
+
     pub fn generate_if_no_buildinfo(&self) -> Option<BuildInfo> {
-        BuildInfo::from_file(self.output_file()?.as_path()).or_else(|| self.generate())
+        self.cargo_emit_inputs();
+        BuildInfo::from_file(self.output_file()?.as_path()).or_else(|| self.install_or_log())
+    }
+
+    /// Run `conan install` unless the build info was generated from the same inputs,
+    /// according to the `fingerprint` stored next to it.
+    pub fn generate_if_changed(&self) -> Option<BuildInfo> {
+        self.cargo_emit_inputs();
+        let fingerprint = match self.check_build_info().and_then(|_| self.fingerprint()) {
+            Ok(fingerprint) => fingerprint,
+            Err(error) => {
+                eprintln!("{}", error);
+                return None;
+            }
+        };
+        let fingerprint_file = self.fingerprint_file()?;
+        let output_file = self.output_file()?;
+        if fs::read_to_string(&fingerprint_file).is_ok_and(|stored| stored.trim() == fingerprint) {
+            if let Some(build_info) = BuildInfo::from_file(&output_file) {
+                return Some(build_info);
+            }
+        }
+
+        // NOTE: A failed install must not leave the previous build info behind
+        let _ = fs::remove_file(&fingerprint_file);
+        let _ = fs::remove_file(&output_file);
+        let build_info = self.install_or_log()?;
+        if let Err(error) = fs::write(&fingerprint_file, &fingerprint) {
+            eprintln!("Failed to write {}: {}", fingerprint_file.display(), error);
+        }
+        Some(build_info)
+    }
+
+    fn install_or_log(&self) -> Option<BuildInfo> {
+        self.install().map_err(|error| eprintln!("{}", error)).ok()
+    }
+}
+
+fn read_build_info(status: ExitStatus, output_file: &Path) -> Result<BuildInfo, ConanInstallError> {
+    if !status.success() {
+        return Err(ConanInstallError::CommandFailed(status));
     }
+    BuildInfo::from_file(output_file).ok_or(ConanInstallError::BuildInfoNotFound)
 }
@@ -4,11 +4,17 @@ mod test;
 pub mod build_dependency;
 pub mod build_settings;
 
+use std::collections::BTreeMap;
 use std::fs::File;
 use std::path::Path;
 
 use serde::{Deserialize, Serialize};
 
+use crate::lock::{ConanLockError, Lockfile};
+use crate::options::{ConanOption, OptionError};
+use crate::reference::ConanReference;
+use crate::version::{Version, VersionError, VersionRange};
+
 pub use build_dependency::BuildDependency;
 pub use build_settings::BuildSettings;
 
@@ -17,11 +23,14 @@ pub use build_settings::BuildSettings;
 pub struct BuildInfo {
     pub(crate) dependencies: Vec<BuildDependency>,
     pub(crate) settings: BuildSettings,
+    #[serde(default)]
+    pub(crate) options: BTreeMap<String, BTreeMap<String, String>>,
 }
 
 impl BuildInfo {
+    #[allow(clippy::should_implement_trait)]
     pub fn from_str(json: &str) -> Option<Self> {
-        let result = serde_json::from_str(&json);
+        let result = serde_json::from_str(json);
         if let Err(error) = result {
             eprintln!("failed to parse conan build info: {:?}", error);
             return None;
@@ -45,6 +54,99 @@ impl BuildInfo {
         &self.dependencies
     }
 
+    /// Check that the resolved version of each required package satisfies its declared version or range.
+    ///
+    /// This catches a transitive dependency upgrading a package past the range a build script relies on.
+    pub fn check_versions(&self, requirements: &[ConanReference]) -> Result<(), VersionError> {
+        for requirement in requirements {
+            let dependency = self
+                .get_dependency(&requirement.name)
+                .ok_or_else(|| VersionError::MissingDependency(requirement.name.clone()))?;
+            let version: Version = dependency.version.parse()?;
+
+            let satisfied = if VersionRange::is_range(&requirement.version) {
+                requirement.version.parse::<VersionRange>()?.contains(&version)
+            } else {
+                version == requirement.version.parse::<Version>()?
+            };
+
+            if !satisfied {
+                return Err(VersionError::Unsatisfied {
+                    name: dependency.name.clone(),
+                    version: dependency.version.clone(),
+                    range: requirement.version.clone(),
+                });
+            }
+        }
+        Ok(())
+    }
+
+    /// Check that every installed package has the version pinned by the lockfile.
+    ///
+    /// Conan already refuses requirements missing from a strict lockfile, this also catches
+    /// an install that ran without it, e.g. after the lockfile was updated.
+    pub fn check_lockfile(&self, lockfile: &Lockfile) -> Result<(), ConanLockError> {
+        for dependency in &self.dependencies {
+            let locked = lockfile
+                .get(&dependency.name)
+                .ok_or_else(|| ConanLockError::Unlocked(dependency.name.clone()))?;
+            if locked.reference.version != dependency.version {
+                return Err(ConanLockError::Deviation {
+                    name: dependency.name.clone(),
+                    locked: locked.reference.version.clone(),
+                    resolved: dependency.version.clone(),
+                });
+            }
+        }
+        Ok(())
+    }
+
+    /// Options the package was installed with.
+    pub fn get_options(&self, package: &str) -> Option<&BTreeMap<String, String>> {
+        self.options.get(package)
+    }
+
+    /// Check that the installed packages have the requested option values.
+    ///
+    /// Options without a package are skipped, as they apply to the consumer recipe.
+    /// A pattern matching no package is accepted, a package name matching none is not.
+    pub fn check_options(&self, options: &[ConanOption]) -> Result<(), OptionError> {
+        for option in options {
+            let pattern = match &option.package {
+                Some(pattern) => pattern,
+                None => continue,
+            };
+
+            let mut matched = false;
+            for (package, values) in &self.options {
+                let version = self.get_dependency(package).map(|x| x.version.as_str());
+                if !option.matches_package(package, version) {
+                    continue;
+                }
+                matched = true;
+
+                let actual = values.get(&option.name).ok_or_else(|| OptionError::UnknownOption {
+                    package: package.clone(),
+                    name: option.name.clone(),
+                })?;
+                let expected = option.value.to_string();
+                if actual != &expected {
+                    return Err(OptionError::Mismatch {
+                        package: package.clone(),
+                        name: option.name.clone(),
+                        expected,
+                        actual: actual.clone(),
+                    });
+                }
+            }
+
+            if !matched && !pattern.contains(['*', '?'].as_ref()) {
+                return Err(OptionError::UnknownPackage(pattern.clone()));
+            }
+        }
+        Ok(())
+    }
+
     pub fn cargo_emit(&self) {
         for dependency in &self.dependencies {
             for lib_path in &dependency.lib_paths {
@@ -64,8 +166,6 @@ impl BuildInfo {
             for include_path in &dependency.include_paths {
                 println!("cargo:include={}", include_path);
             }
-
-            println!("cargo:rerun-if-env-changed=CONAN");
         }
     }
 }
@@ -1,3 +1,4 @@
+use crate::reference::{ConanReference, ReferenceError};
 use serde::de::{SeqAccess, Visitor};
 use serde::{Deserialize, Deserializer, Serialize};
 use std::fmt;
@@ -65,12 +66,25 @@ pub struct BuildDependency {
 }
 
 impl BuildDependency {
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn version(&self) -> &str {
+        &self.version
+    }
+
+    /// Reference of the dependency, without user and channel which are not part of the build info.
+    pub fn reference(&self) -> Result<ConanReference, ReferenceError> {
+        ConanReference::new(&self.name, &self.version)
+    }
+
     pub fn get_root_dir(&self) -> Option<&str> {
         Some(self.rootpath.as_str())
     }
 
     pub fn get_library_dir(&self) -> Option<&str> {
-        self.lib_paths.get(0).map(|x| &**x)
+        self.lib_paths.first().map(|x| &**x)
     }
 
     pub fn get_include_dirs(&self) -> Vec<&str> {
@@ -78,6 +92,6 @@ impl BuildDependency {
     }
 
     pub fn get_binary_dir(&self) -> Option<&str> {
-        self.bin_paths.get(0).map(|x| &**x)
+        self.bin_paths.first().map(|x| &**x)
     }
 }
@@ -1,4 +1,5 @@
 use std::env;
+use std::fmt;
 
 use serde::{Deserialize, Serialize};
 
@@ -13,20 +14,20 @@ pub enum BuildType {
     MinSizeRel,
 }
 
-impl ToString for BuildType {
-    fn to_string(&self) -> String {
-        self.as_str().to_string()
+impl fmt::Display for BuildType {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(self.as_str())
     }
 }
 
 impl BuildType {
     pub fn as_str(&self) -> &str {
         match self {
-            &BuildType::None => "None",
-            &BuildType::Debug => "Debug",
-            &BuildType::Release => "Release",
-            &BuildType::RelWithDebInfo => "RelWithDebInfo",
-            &BuildType::MinSizeRel => "MinSizeRel",
+            BuildType::None => "None",
+            BuildType::Debug => "Debug",
+            BuildType::Release => "Release",
+            BuildType::RelWithDebInfo => "RelWithDebInfo",
+            BuildType::MinSizeRel => "MinSizeRel",
         }
     }
 }
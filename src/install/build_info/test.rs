@@ -1,4 +1,7 @@
 use super::*;
+use crate::options::{ConanOption, OptionError};
+use crate::reference::ConanReference;
+use crate::version::VersionError;
 
 #[test]
 fn test_conan_build_info() {
@@ -80,3 +83,97 @@ fn test_cargo_build_info_syslibs() {
     let build_info = BuildInfo::from_str(include_str!("../../../test/conanbuildinfo5.json")).unwrap();
     build_info.cargo_emit();
 }
+
+#[test]
+fn test_build_info_check_versions() {
+    let build_info = BuildInfo::from_str(include_str!("../../../test/conanbuildinfo2.json")).unwrap();
+
+    let requirements: Vec<ConanReference> = vec!["curl/[>=7.50 <8]".parse().unwrap()];
+    assert!(build_info.check_versions(&requirements).is_ok());
+
+    let requirements: Vec<ConanReference> = vec!["curl/7.58.0".parse().unwrap()];
+    assert!(build_info.check_versions(&requirements).is_ok());
+
+    let requirements: Vec<ConanReference> = vec!["curl/[~7.60]".parse().unwrap()];
+    assert_eq!(
+        build_info.check_versions(&requirements),
+        Err(VersionError::Unsatisfied {
+            name: "curl".to_string(),
+            version: "7.58.0".to_string(),
+            range: "[~7.60]".to_string(),
+        })
+    );
+
+    let requirements: Vec<ConanReference> = vec!["zlib/[*]".parse().unwrap()];
+    assert_eq!(
+        build_info.check_versions(&requirements),
+        Err(VersionError::MissingDependency("zlib".to_string()))
+    );
+}
+
+#[test]
+fn test_build_info_check_options() -> Result<(), Box<dyn std::error::Error>> {
+    let mut build_info = BuildInfo::from_str(include_str!("../../../test/conanbuildinfo2.json")).unwrap();
+    assert!(build_info.get_options("curl").unwrap().is_empty());
+
+    let curl_options = build_info.options.get_mut("curl").unwrap();
+    curl_options.insert("shared".to_string(), "False".to_string());
+    curl_options.insert("with_ssl".to_string(), "mbedtls".to_string());
+    let mbedtls_options = build_info.options.get_mut("mbedtls").unwrap();
+    mbedtls_options.insert("shared".to_string(), "False".to_string());
+
+    let options: Vec<ConanOption> = vec![
+        "curl:with_ssl=mbedtls".parse()?,
+        "*:shared=False".parse()?,
+        "fPIC=True".parse()?,
+        "openssl*:shared=True".parse()?,
+    ];
+    build_info.check_options(&options)?;
+
+    assert_eq!(
+        build_info.check_options(&["curl:shared=True".parse()?]),
+        Err(OptionError::Mismatch {
+            package: "curl".to_string(),
+            name: "shared".to_string(),
+            expected: "True".to_string(),
+            actual: "False".to_string(),
+        })
+    );
+    assert_eq!(
+        build_info.check_options(&["mbedtls:with_ssl=openssl".parse()?]),
+        Err(OptionError::UnknownOption {
+            package: "mbedtls".to_string(),
+            name: "with_ssl".to_string(),
+        })
+    );
+    assert_eq!(
+        build_info.check_options(&["openssl:shared=True".parse()?]),
+        Err(OptionError::UnknownPackage("openssl".to_string()))
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_check_lockfile() -> Result<(), Box<dyn std::error::Error>> {
+    let build_info = BuildInfo::from_str(include_str!("../../../test/conanbuildinfo1.json")).unwrap();
+
+    let lockfile: Lockfile =
+        r#"{"version": "0.5", "requires": ["openssl/1.1.1b-2#c4dbdcb5b1c6f2ec1a4fbd8b2a3e6d11"]}"#.parse()?;
+    build_info.check_lockfile(&lockfile)?;
+
+    let lockfile: Lockfile = r#"{"version": "0.5", "requires": ["openssl/1.1.1w"]}"#.parse()?;
+    assert!(matches!(
+        build_info.check_lockfile(&lockfile),
+        Err(ConanLockError::Deviation { name, locked, resolved })
+            if name == "openssl" && locked == "1.1.1w" && resolved == "1.1.1b-2"
+    ));
+
+    let lockfile: Lockfile = r#"{"version": "0.5", "requires": ["zlib/1.3.1"]}"#.parse()?;
+    assert!(matches!(
+        build_info.check_lockfile(&lockfile),
+        Err(ConanLockError::Unlocked(name)) if name == "openssl"
+    ));
+
+    Ok(())
+}
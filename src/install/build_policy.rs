@@ -0,0 +1,214 @@
+#[cfg(test)]
+mod test;
+
+use std::env;
+use std::fmt;
+use std::str::FromStr;
+
+use thiserror::Error;
+
+use crate::util::{package_pattern, ConanVersion};
+
+/// Environment variable read by `BuildPolicy::from_default_env`
+pub const BUILD_POLICY_ENV: &str = "CONAN_BUILD_POLICY";
+
+#[derive(Debug, Error, Clone, PartialEq)]
+pub enum BuildPolicyError {
+    #[error("Invalid build rule \"{0}\"")]
+    InvalidRule(String),
+    #[error("Invalid Unicode in {0}")]
+    InvalidEnv(String),
+}
+
+/// Rule of a build policy, rendered as one `--build` argument
+#[derive(Clone, Debug, PartialEq)]
+pub enum BuildRule {
+    /// Never build from sources
+    Never,
+    /// Build every package from sources
+    Always,
+    /// Build the packages without a binary for the current configuration
+    Missing,
+    /// Conan 1 only: build the packages whose binary is older than their recipe
+    Outdated,
+    /// Build the consumers of the packages being built
+    Cascade,
+    /// Build the packages matching a name or pattern, e.g. `zlib` or `boost*`
+    Package(String),
+    /// Conan 2 only: build the packages matching a pattern if their binary is missing
+    MissingPackage(String),
+    /// Conan 2 only: don't build the packages matching a pattern
+    Exclude(String),
+}
+
+impl BuildRule {
+    /// Arguments for the given Conan version, or `None` if the rule is not supported by it.
+    fn args(&self, conan_version: ConanVersion) -> Option<Vec<String>> {
+        let args = match conan_version {
+            ConanVersion::V1 => match self {
+                BuildRule::Never => vec!["-b".to_string(), "never".to_string()],
+                BuildRule::Always => vec!["-b".to_string()],
+                BuildRule::Missing => vec!["-b".to_string(), "missing".to_string()],
+                BuildRule::Outdated => vec!["-b".to_string(), "outdated".to_string()],
+                BuildRule::Cascade => vec!["-b".to_string(), "cascade".to_string()],
+                BuildRule::Package(pattern) => vec!["-b".to_string(), pattern.clone()],
+                BuildRule::MissingPackage(_) | BuildRule::Exclude(_) => return None,
+            },
+            ConanVersion::V2 => vec![match self {
+                BuildRule::Never => "--build=never".to_string(),
+                BuildRule::Always => "--build=*".to_string(),
+                BuildRule::Missing => "--build=missing".to_string(),
+                BuildRule::Outdated => return None,
+                BuildRule::Cascade => "--build=cascade".to_string(),
+                BuildRule::Package(pattern) => format!("--build={}", package_pattern(pattern, conan_version)),
+                BuildRule::MissingPackage(pattern) => {
+                    format!("--build=missing:{}", package_pattern(pattern, conan_version))
+                }
+                BuildRule::Exclude(pattern) => format!("--build=!{}", package_pattern(pattern, conan_version)),
+            }],
+        };
+        Some(args)
+    }
+}
+
+impl FromStr for BuildRule {
+    type Err = BuildPolicyError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        let rule = match s {
+            "never" => BuildRule::Never,
+            "always" | "*" => BuildRule::Always,
+            "missing" => BuildRule::Missing,
+            "outdated" => BuildRule::Outdated,
+            "cascade" => BuildRule::Cascade,
+            s => {
+                let pattern = s
+                    .strip_prefix("missing:")
+                    .or_else(|| s.strip_prefix('!'))
+                    .or_else(|| s.strip_prefix('~'))
+                    .unwrap_or(s);
+                if pattern.is_empty() || pattern.contains(char::is_whitespace) {
+                    return Err(BuildPolicyError::InvalidRule(s.to_string()));
+                }
+
+                if s.starts_with("missing:") {
+                    BuildRule::MissingPackage(pattern.to_string())
+                } else if s.starts_with('!') || s.starts_with('~') {
+                    BuildRule::Exclude(pattern.to_string())
+                } else {
+                    BuildRule::Package(pattern.to_string())
+                }
+            }
+        };
+        Ok(rule)
+    }
+}
+
+impl fmt::Display for BuildRule {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            BuildRule::Never => f.write_str("never"),
+            BuildRule::Always => f.write_str("always"),
+            BuildRule::Missing => f.write_str("missing"),
+            BuildRule::Outdated => f.write_str("outdated"),
+            BuildRule::Cascade => f.write_str("cascade"),
+            BuildRule::Package(pattern) => f.write_str(pattern),
+            BuildRule::MissingPackage(pattern) => write!(f, "missing:{}", pattern),
+            BuildRule::Exclude(pattern) => write!(f, "!{}", pattern),
+        }
+    }
+}
+
+/// Conan build policy: the list of rules deciding which packages are built from sources
+///
+/// Parsed from comma or space-separated rules, e.g. `missing,cascade,!openssl`.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct BuildPolicy {
+    rules: Vec<BuildRule>,
+}
+
+impl BuildPolicy {
+    pub fn new() -> Self {
+        BuildPolicy::default()
+    }
+
+    pub fn with_rule(mut self, rule: BuildRule) -> Self {
+        self.rules.push(rule);
+        self
+    }
+
+    /// Build the packages matching a name or pattern.
+    pub fn with_package(self, pattern: &str) -> Self {
+        self.with_rule(BuildRule::Package(pattern.to_string()))
+    }
+
+    /// Don't build the packages matching a pattern (Conan 2).
+    pub fn without_package(self, pattern: &str) -> Self {
+        self.with_rule(BuildRule::Exclude(pattern.to_string()))
+    }
+
+    pub fn rules(&self) -> &[BuildRule] {
+        &self.rules
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.rules.is_empty()
+    }
+
+    /// Parse the policy from an environment variable, if it is set and not empty.
+    pub fn from_env(name: &str) -> Result<Option<Self>, BuildPolicyError> {
+        match env::var(name) {
+            Ok(value) if value.trim().is_empty() => Ok(None),
+            Ok(value) => value.parse().map(Some),
+            Err(env::VarError::NotPresent) => Ok(None),
+            Err(env::VarError::NotUnicode(_)) => Err(BuildPolicyError::InvalidEnv(name.to_string())),
+        }
+    }
+
+    /// Parse the policy from the `CONAN_BUILD_POLICY` environment variable.
+    pub fn from_default_env() -> Result<Option<Self>, BuildPolicyError> {
+        BuildPolicy::from_env(BUILD_POLICY_ENV)
+    }
+
+    /// Arguments for the given Conan version, or `None` if a rule is not supported by it.
+    pub(crate) fn args(&self, conan_version: ConanVersion) -> Option<Vec<String>> {
+        let mut args = Vec::new();
+        for rule in &self.rules {
+            args.extend(rule.args(conan_version)?);
+        }
+        Some(args)
+    }
+}
+
+impl From<BuildRule> for BuildPolicy {
+    fn from(rule: BuildRule) -> Self {
+        BuildPolicy { rules: vec![rule] }
+    }
+}
+
+impl From<Vec<BuildRule>> for BuildPolicy {
+    fn from(rules: Vec<BuildRule>) -> Self {
+        BuildPolicy { rules }
+    }
+}
+
+impl FromStr for BuildPolicy {
+    type Err = BuildPolicyError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let rules = s
+            .split(|c: char| c == ',' || c.is_whitespace())
+            .filter(|x| !x.is_empty())
+            .map(str::parse)
+            .collect::<Result<Vec<BuildRule>, _>>()?;
+        Ok(BuildPolicy { rules })
+    }
+}
+
+impl fmt::Display for BuildPolicy {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let rules: Vec<String> = self.rules.iter().map(|x| x.to_string()).collect();
+        f.write_str(&rules.join(","))
+    }
+}
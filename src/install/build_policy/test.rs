@@ -0,0 +1,92 @@
+use super::{BuildPolicy, BuildPolicyError, BuildRule};
+use crate::util::ConanVersion;
+use std::env;
+
+#[test]
+fn test_build_policy_parse() -> Result<(), BuildPolicyError> {
+    let policy: BuildPolicy = "missing, cascade zlib missing:boost* !openssl ~libcurl".parse()?;
+    assert_eq!(
+        policy.rules(),
+        [
+            BuildRule::Missing,
+            BuildRule::Cascade,
+            BuildRule::Package("zlib".to_string()),
+            BuildRule::MissingPackage("boost*".to_string()),
+            BuildRule::Exclude("openssl".to_string()),
+            BuildRule::Exclude("libcurl".to_string()),
+        ]
+    );
+    assert_eq!(
+        policy.to_string(),
+        "missing,cascade,zlib,missing:boost*,!openssl,!libcurl"
+    );
+    assert_eq!(policy.to_string().parse::<BuildPolicy>()?, policy);
+
+    assert_eq!("*".parse::<BuildPolicy>()?, BuildRule::Always.into());
+    assert!("".parse::<BuildPolicy>()?.is_empty());
+    assert_eq!(
+        "missing:".parse::<BuildPolicy>(),
+        Err(BuildPolicyError::InvalidRule("missing:".to_string()))
+    );
+    assert_eq!(
+        "!".parse::<BuildPolicy>(),
+        Err(BuildPolicyError::InvalidRule("!".to_string()))
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_build_policy_args() -> Result<(), BuildPolicyError> {
+    let policy = BuildPolicy::from(BuildRule::Missing)
+        .with_rule(BuildRule::Cascade)
+        .with_package("zlib");
+    assert_eq!(
+        policy.args(ConanVersion::V1).unwrap(),
+        ["-b", "missing", "-b", "cascade", "-b", "zlib"]
+    );
+    assert_eq!(
+        policy.args(ConanVersion::V2).unwrap(),
+        ["--build=missing", "--build=cascade", "--build=zlib/*"]
+    );
+
+    let policy: BuildPolicy = "missing:boost/* !openssl".parse()?;
+    assert_eq!(policy.args(ConanVersion::V1), None);
+    assert_eq!(
+        policy.args(ConanVersion::V2).unwrap(),
+        ["--build=missing:boost/*", "--build=!openssl/*"]
+    );
+
+    let policy = BuildPolicy::from(BuildRule::Always).without_package("openssl*");
+    assert_eq!(
+        policy.args(ConanVersion::V2).unwrap(),
+        ["--build=*", "--build=!openssl*"]
+    );
+
+    assert_eq!(BuildPolicy::from(BuildRule::Outdated).args(ConanVersion::V2), None);
+    assert_eq!(
+        BuildPolicy::from(BuildRule::Always).args(ConanVersion::V1).unwrap(),
+        ["-b"]
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_build_policy_from_env() -> Result<(), BuildPolicyError> {
+    let name = "RS_TEST_BUILD_POLICY";
+    env::remove_var(name);
+    assert_eq!(BuildPolicy::from_env(name)?, None);
+
+    env::set_var(name, " ");
+    assert_eq!(BuildPolicy::from_env(name)?, None);
+
+    env::set_var(name, "never");
+    assert_eq!(BuildPolicy::from_env(name)?, Some(BuildRule::Never.into()));
+
+    env::set_var(name, "missing:");
+    assert!(BuildPolicy::from_env(name).is_err());
+    env::remove_var(name);
+
+    Ok(())
+}
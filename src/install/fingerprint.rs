@@ -0,0 +1,42 @@
+#[cfg(test)]
+mod test;
+
+const FNV_OFFSET_BASIS: u64 = 0xcbf2_9ce4_8422_2325;
+const FNV_PRIME: u64 = 0x0100_0000_01b3;
+
+/// FNV-1a hash of the inputs of an install, or of the configuration of a Conan home
+///
+/// Each input is hashed with its name and length, so that moving bytes from one input
+/// to the next changes the fingerprint.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Fingerprint(u64);
+
+impl Default for Fingerprint {
+    fn default() -> Self {
+        Fingerprint(FNV_OFFSET_BASIS)
+    }
+}
+
+impl Fingerprint {
+    pub fn new() -> Self {
+        Fingerprint::default()
+    }
+
+    pub fn add(&mut self, name: &str, value: &[u8]) -> &mut Self {
+        self.write(name.as_bytes());
+        self.write(&(value.len() as u64).to_le_bytes());
+        self.write(value);
+        self
+    }
+
+    fn write(&mut self, bytes: &[u8]) {
+        for byte in bytes {
+            self.0 ^= u64::from(*byte);
+            self.0 = self.0.wrapping_mul(FNV_PRIME);
+        }
+    }
+
+    pub fn to_hex(self) -> String {
+        format!("{:016x}", self.0)
+    }
+}
@@ -0,0 +1,29 @@
+use super::Fingerprint;
+
+#[test]
+fn test_fingerprint_fnv1a() {
+    // NOTE: Reference values of 64-bit FNV-1a, over the raw bytes
+    let mut empty = Fingerprint::new();
+    empty.write(b"");
+    assert_eq!(empty.to_hex(), "cbf29ce484222325");
+    let mut a = Fingerprint::new();
+    a.write(b"a");
+    assert_eq!(a.to_hex(), "af63dc4c8601ec8c");
+}
+
+#[test]
+fn test_fingerprint_inputs() {
+    let fingerprint = |inputs: &[(&str, &str)]| {
+        let mut fingerprint = Fingerprint::new();
+        for (name, value) in inputs {
+            fingerprint.add(name, value.as_bytes());
+        }
+        fingerprint.to_hex()
+    };
+
+    let base = fingerprint(&[("args", "install ."), ("profile", "[settings]")]);
+    assert_eq!(base, fingerprint(&[("args", "install ."), ("profile", "[settings]")]));
+    assert_ne!(base, fingerprint(&[("args", "install . "), ("profile", "[settings]")]));
+    assert_ne!(base, fingerprint(&[("args", "install"), ("profile", " .[settings]")]));
+    assert_ne!(base, fingerprint(&[("profile", "[settings]"), ("args", "install .")]));
+}
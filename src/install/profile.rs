@@ -1,15 +1,57 @@
 use super::ConanInstallError;
-use std::process::Command;
+use crate::context::ConanContext;
+use crate::util::ConanVersion;
+use std::env;
+use std::path::PathBuf;
 
 #[allow(dead_code)]
-pub fn get_profile_list() -> Result<Vec<String>, ConanInstallError> {
-    let output = Command::new("conan")
-        .arg("profile")
-        .arg("list")
-        .output()
-        .map_err(ConanInstallError::ConanInstallFailed)?;
+pub fn get_profile_list(context: &ConanContext) -> Result<Vec<String>, ConanInstallError> {
+    let mut invocation = context.invocation(&["profile".to_string(), "list".to_string()])?;
+    invocation.quiet = true;
+    let output = context.run(&invocation)?;
 
     let output_stdout = String::from_utf8(output.stdout).map_err(ConanInstallError::Utf8Error)?;
 
     Ok(output_stdout.lines().map(|x| x.to_string()).collect())
 }
+
+/// File of a profile given by path, or by name from the profiles of the Conan home, if it exists.
+pub fn profile_path(context: &ConanContext, profile: &str) -> Option<PathBuf> {
+    let path = match context.working_dir() {
+        Some(working_dir) => working_dir.join(profile),
+        None => PathBuf::from(profile),
+    };
+    if path.is_file() {
+        return Some(path);
+    }
+    let path = conan_dir(context)?.join("profiles").join(profile);
+    Some(path).filter(|path| path.is_file())
+}
+
+/// Folder Conan uses as its home, from the context, the environment, or the user home.
+fn conan_dir(context: &ConanContext) -> Option<PathBuf> {
+    let conan_version = context.conan_version();
+    if let Some(home) = context.home() {
+        return Some(home.conan_dir(conan_version));
+    }
+    let env_var = |name: &str| {
+        context
+            .env()
+            .iter()
+            .rev()
+            .find(|(env_name, _)| env_name == name)
+            .map(|(_, value)| PathBuf::from(value))
+            .or_else(|| env::var_os(name).map(PathBuf::from))
+    };
+    let user_home = || {
+        env::var_os("HOME")
+            .or_else(|| env::var_os("USERPROFILE"))
+            .map(PathBuf::from)
+    };
+    match conan_version {
+        ConanVersion::V1 => env_var("CONAN_USER_HOME")
+            .or_else(user_home)
+            .map(|home| home.join(".conan")),
+        ConanVersion::V2 => env_var("CONAN_HOME").or_else(|| user_home().map(|home| home.join(".conan2"))),
+    }
+}
@@ -2,10 +2,14 @@
 pub mod test;
 
 use super::ConanInstallError;
+use crate::context::ConanContext;
+use crate::home::ConanHome;
+use crate::util::ConanVersion;
 use lazy_static::lazy_static;
 use regex::Regex;
+use std::env;
 use std::fmt;
-use std::process::Command;
+use std::time::Duration;
 
 lazy_static! {
     static ref REGEX_CONAN_REMOTE: Regex = Regex::new(r"(\S+):\s+(\S+)\s+(.*)").unwrap();
@@ -29,25 +33,197 @@ impl fmt::Display for Remote {
     }
 }
 
+/// Callback returning the credentials for a given remote name
+pub type CredentialsProvider<'a> = Box<dyn Fn(&str) -> Option<RemoteCredentials> + 'a>;
+
+/// Credentials used to log into a Conan remote
+///
+/// The password is never part of the command line nor of the `Debug` output:
+/// it is handed over to Conan through the `CONAN_PASSWORD_<REMOTE>` environment variable.
+#[derive(Clone)]
+pub struct RemoteCredentials {
+    username: String,
+    password: String,
+}
+
+impl fmt::Debug for RemoteCredentials {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("RemoteCredentials")
+            .field("username", &self.username)
+            .field("password", &"***")
+            .finish()
+    }
+}
+
+impl RemoteCredentials {
+    pub fn new(username: &str, password: &str) -> Self {
+        RemoteCredentials {
+            username: username.to_string(),
+            password: password.to_string(),
+        }
+    }
+
+    /// Read the credentials from `CONAN_LOGIN_USERNAME_<REMOTE>` and `CONAN_PASSWORD_<REMOTE>`,
+    /// falling back to the remote-agnostic `CONAN_LOGIN_USERNAME` and `CONAN_PASSWORD`.
+    pub fn from_env(remote: &str) -> Option<Self> {
+        Self::from_vars(remote, |name| env::var(name).ok())
+    }
+
+    /// Credentials of `from_env`, with the variables read through `var`.
+    fn from_vars<F: Fn(&str) -> Option<String>>(remote: &str, var: F) -> Option<Self> {
+        let suffix = remote_env_suffix(remote);
+        let lookup = |prefix: &str| var(&format!("{}_{}", prefix, suffix)).or_else(|| var(prefix));
+
+        Some(RemoteCredentials {
+            username: lookup("CONAN_LOGIN_USERNAME")?,
+            password: lookup("CONAN_PASSWORD")?,
+        })
+    }
+
+    pub fn username(&self) -> &str {
+        &self.username
+    }
+}
+
+/// Environment variable suffix Conan derives from a remote name
+pub(crate) fn remote_env_suffix(remote: &str) -> String {
+    remote.replace('-', "_").to_uppercase()
+}
+
+/// "conan user" (Conan 1) or "conan remote login" (Conan 2) command runner
+pub struct RemoteLoginCommand<'a> {
+    remote: &'a str,
+    credentials: Option<RemoteCredentials>,
+    credentials_provider: Option<CredentialsProvider<'a>>,
+    context: ConanContext,
+}
+
+/// Command arguments builder for "conan user" / "conan remote login"
+pub struct RemoteLoginCommandBuilder<'a> {
+    remote: &'a str,
+    credentials: Option<RemoteCredentials>,
+    credentials_provider: Option<CredentialsProvider<'a>>,
+    context: ConanContext,
+}
+
+impl<'a> RemoteLoginCommandBuilder<'a> {
+    pub fn new(remote: &'a str) -> Self {
+        RemoteLoginCommandBuilder {
+            remote,
+            credentials: None,
+            credentials_provider: None,
+            context: ConanContext::default(),
+        }
+    }
+
+    pub fn with_credentials(mut self, credentials: RemoteCredentials) -> Self {
+        self.credentials = Some(credentials);
+        self
+    }
+
+    /// Query the credentials from a callback when the command runs.
+    pub fn with_credentials_provider<F>(mut self, provider: F) -> Self
+    where
+        F: Fn(&str) -> Option<RemoteCredentials> + 'a,
+    {
+        self.credentials_provider = Some(Box::new(provider));
+        self
+    }
+
+    /// Log in through a shared context, keeping the timeout, home and version set on this builder.
+    pub fn with_context(mut self, context: ConanContext) -> Self {
+        self.context = context.merged_with(self.context);
+        self
+    }
+
+    /// Kill Conan and fail with a timeout error if it runs longer than `timeout`.
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.context = self.context.with_timeout(timeout);
+        self
+    }
+
+    /// Run Conan with an isolated home.
+    pub fn with_conan_home(mut self, conan_home: ConanHome) -> Self {
+        self.context = self.context.with_home(conan_home);
+        self
+    }
+
+    pub fn conan_version(mut self, conan_version: ConanVersion) -> Self {
+        self.context = self.context.with_conan_version(conan_version);
+        self
+    }
+
+    pub fn build(self) -> RemoteLoginCommand<'a> {
+        RemoteLoginCommand {
+            remote: self.remote,
+            credentials: self.credentials,
+            credentials_provider: self.credentials_provider,
+            context: self.context,
+        }
+    }
+}
+
+impl<'a> RemoteLoginCommand<'a> {
+    /// Resolve the credentials: explicit ones first, then the provider, then the environment.
+    pub fn credentials(&self) -> Result<RemoteCredentials, ConanInstallError> {
+        self.credentials
+            .clone()
+            .or_else(|| self.credentials_provider.as_ref().and_then(|f| f(self.remote)))
+            .or_else(|| RemoteCredentials::from_env(self.remote))
+            .ok_or_else(|| ConanInstallError::MissingCredentials(self.remote.to_string()))
+    }
+
+    /// Command line arguments, which never contain the password.
+    pub fn args(&self, credentials: &RemoteCredentials) -> Vec<String> {
+        let args: Vec<&str> = match self.context.conan_version() {
+            ConanVersion::V1 => vec!["user", credentials.username(), "-r", self.remote, "-p"],
+            ConanVersion::V2 => vec!["remote", "login", self.remote, credentials.username()],
+        };
+
+        args.iter().map(|x| x.to_string()).collect()
+    }
+
+    /// Environment variables through which Conan reads the credentials.
+    pub fn env(&self, credentials: &RemoteCredentials) -> Vec<(String, String)> {
+        let suffix = remote_env_suffix(self.remote);
+        vec![
+            (format!("CONAN_LOGIN_USERNAME_{}", suffix), credentials.username.clone()),
+            (format!("CONAN_PASSWORD_{}", suffix), credentials.password.clone()),
+            ("CONAN_NON_INTERACTIVE".to_string(), "1".to_string()),
+        ]
+    }
+
+    pub fn run(&self) -> Result<(), ConanInstallError> {
+        let credentials = self.credentials()?;
+        let mut invocation = self.context.invocation(&self.args(&credentials))?;
+        invocation.env.extend(self.env(&credentials));
+        let output = self.context.run(&invocation)?;
+
+        if output.status.success() {
+            Ok(())
+        } else {
+            Err(ConanInstallError::RemoteLoginFailed(self.remote.to_string()))
+        }
+    }
+}
+
 // NOTE: This function will be used later
 #[allow(dead_code)]
-pub fn get_remote_list() -> Result<Vec<Remote>, ConanInstallError> {
-    let output = Command::new("conan")
-        .arg("remote")
-        .arg("list")
-        .output()
-        .expect("failed to execute conan");
+pub fn get_remote_list(context: &ConanContext) -> Result<Vec<Remote>, ConanInstallError> {
+    let mut invocation = context.invocation(&["remote".to_string(), "list".to_string()])?;
+    invocation.quiet = true;
+    let output = context.run(&invocation)?;
 
     let output_stdout = String::from_utf8(output.stdout).map_err(ConanInstallError::Utf8Error)?;
 
     Ok(output_stdout
         .lines()
-        .map(|x| {
-            let captures = REGEX_CONAN_REMOTE.captures(x.trim()).unwrap();
-            Remote {
+        .filter_map(|x| {
+            let captures = REGEX_CONAN_REMOTE.captures(x.trim())?;
+            Some(Remote {
                 name: captures[1].to_string(),
                 url: captures[2].to_string(),
-            }
+            })
         })
         .collect())
 }
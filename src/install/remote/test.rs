@@ -1,9 +1,59 @@
-use super::get_remote_list;
+use super::{get_remote_list, RemoteCredentials, RemoteLoginCommandBuilder};
+use crate::context::ConanContext;
+use crate::util::ConanVersion;
 
 #[test]
 fn test_conan_remote_list() {
-    let conan_remote_list = get_remote_list();
+    let conan_remote_list = get_remote_list(&ConanContext::new());
     if let Ok(conan_remote_list) = conan_remote_list {
-        assert!(conan_remote_list.len() > 0);
+        assert!(!conan_remote_list.is_empty());
     }
 }
+
+#[test]
+fn test_remote_login_args_v1() {
+    let credentials = RemoteCredentials::new("ci-bot", "s3cr3t");
+    let command = RemoteLoginCommandBuilder::new("my-artifactory")
+        .with_credentials(credentials.clone())
+        .conan_version(ConanVersion::V1)
+        .build();
+
+    let args = command.args(&command.credentials().unwrap());
+    assert_eq!(args, ["user", "ci-bot", "-r", "my-artifactory", "-p"]);
+    assert!(!args.iter().any(|x| x.contains("s3cr3t")));
+
+    let env = command.env(&credentials);
+    assert!(env.contains(&("CONAN_LOGIN_USERNAME_MY_ARTIFACTORY".to_string(), "ci-bot".to_string())));
+    assert!(env.contains(&("CONAN_PASSWORD_MY_ARTIFACTORY".to_string(), "s3cr3t".to_string())));
+}
+
+#[test]
+fn test_remote_login_args_v2() {
+    let command = RemoteLoginCommandBuilder::new("artifactory")
+        .with_credentials_provider(|remote| {
+            assert_eq!(remote, "artifactory");
+            Some(RemoteCredentials::new("ci-bot", "s3cr3t"))
+        })
+        .conan_version(ConanVersion::V2)
+        .build();
+
+    let args = command.args(&command.credentials().unwrap());
+    assert_eq!(args, ["remote", "login", "artifactory", "ci-bot"]);
+}
+
+#[test]
+fn test_remote_credentials_from_env() {
+    // NOTE: Setting `CONAN*` variables would change the fingerprint of concurrent install tests
+    let vars = |name: &str| match name {
+        "CONAN_LOGIN_USERNAME_TEST_REMOTE_ENV" => Some("env-user".to_string()),
+        "CONAN_PASSWORD_TEST_REMOTE_ENV" => Some("env-password".to_string()),
+        _ => None,
+    };
+
+    let credentials = RemoteCredentials::from_vars("test-remote-env", vars).unwrap();
+    assert_eq!(credentials.username(), "env-user");
+
+    let debug = format!("{:?}", credentials);
+    assert!(debug.contains("env-user"));
+    assert!(!debug.contains("env-password"));
+}
@@ -1,7 +1,13 @@
 use super::{
     build_info::{build_settings::BuildType, BuildSettings},
-    BuildPolicy, InstallCommandBuilder,
+    BuildRule, ConanInstallError, InstallCommandBuilder,
 };
+use crate::conanfile::ConanfileTxt;
+use crate::features::FeatureMapping;
+use crate::reference::ConanReference;
+use crate::util::ConanVersion;
+use std::fs;
+use std::path::Path;
 
 #[test]
 fn test_install_builder() -> Result<(), Box<dyn std::error::Error>> {
@@ -9,7 +15,8 @@ fn test_install_builder() -> Result<(), Box<dyn std::error::Error>> {
     let command = InstallCommandBuilder::new()
         .with_profile("linux-x86_64")
         .build_settings(build_settings)
-        .build_policy(BuildPolicy::Missing)
+        .build_policy(BuildRule::Missing)
+        .conan_version(ConanVersion::V1)
         .build();
     assert_eq!(
         command.args()?,
@@ -36,7 +43,8 @@ fn test_install_builder_cross() -> Result<(), Box<dyn std::error::Error>> {
         .with_host_profile("windows-x86_64")
         .with_build_profile("linux-x86_64")
         .build_settings(build_settings)
-        .build_policy(BuildPolicy::Always)
+        .build_policy(BuildRule::Always)
+        .conan_version(ConanVersion::V1)
         .build();
     assert_eq!(
         command.args()?,
@@ -64,7 +72,8 @@ fn test_install_builder_with_options() -> Result<(), Box<dyn std::error::Error>>
         .with_profile("linux-x86_64")
         .build_settings(build_settings)
         .with_options(&["shared=True", "build_type=Release"])
-        .build_policy(BuildPolicy::Missing)
+        .build_policy(BuildRule::Missing)
+        .conan_version(ConanVersion::V1)
         .build();
     assert_eq!(
         command.args()?,
@@ -87,3 +96,548 @@ fn test_install_builder_with_options() -> Result<(), Box<dyn std::error::Error>>
 
     Ok(())
 }
+
+#[test]
+fn test_install_builder_with_requires() -> Result<(), Box<dyn std::error::Error>> {
+    let requires: Vec<ConanReference> = vec!["zlib/1.3.1".parse()?, "openssl/3.2.0".parse()?];
+    let tool_requires: Vec<ConanReference> = vec!["cmake/3.27.7".parse()?];
+
+    let command = InstallCommandBuilder::new()
+        .build_settings(BuildSettings::new().build_type(BuildType::Release))
+        .with_requires(&requires)
+        .with_tool_requires(&tool_requires)
+        .output_dir(Path::new("target/conan-requires-test"))
+        .conan_version(ConanVersion::V2)
+        .build();
+    assert_eq!(
+        command.args()?,
+        [
+            "install",
+            "--output-folder",
+            "target/conan-requires-test",
+            "-s",
+            "build_type=Release",
+            "--requires=zlib/1.3.1",
+            "--requires=openssl/3.2.0",
+            "--tool-requires=cmake/3.27.7"
+        ]
+    );
+
+    let command = InstallCommandBuilder::new()
+        .build_settings(BuildSettings::new().build_type(BuildType::Release))
+        .with_requires(&requires)
+        .with_tool_requires(&tool_requires)
+        .output_dir(Path::new("target/conan-requires-test"))
+        .conan_version(ConanVersion::V1)
+        .build();
+    let requires_file = command.requires_file().unwrap();
+    assert_eq!(
+        command.args()?,
+        [
+            "install",
+            "-g",
+            "json",
+            "-if",
+            "target/conan-requires-test",
+            "-s",
+            "build_type=Release",
+            requires_file.to_str().unwrap()
+        ]
+    );
+
+    command.write_requires_file()?;
+    assert_eq!(
+        fs::read_to_string(&requires_file)?,
+        "[requires]\nzlib/1.3.1\nopenssl/3.2.0\n\n[build_requires]\ncmake/3.27.7\n"
+    );
+
+    let command = InstallCommandBuilder::new()
+        .with_requires(&requires)
+        .recipe_path(Path::new("conanfile.txt"))
+        .build();
+    assert!(matches!(command.args(), Err(ConanInstallError::RequiresWithRecipe)));
+
+    Ok(())
+}
+
+#[test]
+fn test_install_builder_with_conanfile_txt() -> Result<(), Box<dyn std::error::Error>> {
+    let conanfile: ConanfileTxt = "[requires]\nzlib/1.2.13\n\n[generators]\nCMakeDeps\n".parse()?;
+    let requires: Vec<ConanReference> = vec!["zlib/1.3.1".parse()?];
+
+    let command = InstallCommandBuilder::new()
+        .with_conanfile_txt(conanfile)
+        .with_requires(&requires)
+        .output_dir(Path::new("target/conan-conanfile-test"))
+        .conan_version(ConanVersion::V2)
+        .build();
+    let requires_file = command.requires_file().unwrap();
+    assert_eq!(
+        command.args()?,
+        [
+            "install",
+            "--output-folder",
+            "target/conan-conanfile-test",
+            requires_file.to_str().unwrap()
+        ]
+    );
+
+    command.write_requires_file()?;
+    assert_eq!(
+        fs::read_to_string(&requires_file)?,
+        "[requires]\nzlib/1.3.1\n\n[generators]\nCMakeDeps\n"
+    );
+
+    let command = InstallCommandBuilder::new()
+        .with_conanfile_txt(ConanfileTxt::new())
+        .recipe_path(Path::new("conanfile.txt"))
+        .build();
+    assert!(matches!(command.args(), Err(ConanInstallError::RequiresWithRecipe)));
+
+    Ok(())
+}
+
+#[test]
+fn test_install_builder_with_typed_options() -> Result<(), Box<dyn std::error::Error>> {
+    std::env::set_var("CARGO_FEATURE_CONAN_RS_INSTALL_SSL", "1");
+
+    let command = InstallCommandBuilder::new()
+        .build_settings(BuildSettings::new().build_type(BuildType::Release))
+        .with_options(&["zlib:fPIC=True"])
+        .with_option("zlib:shared", true)?
+        .with_option("libcurl:max_connections", 16)?
+        .with_feature(
+            FeatureMapping::new("conan-rs-install-ssl")
+                .with_option("libcurl:with_ssl", "openssl")?
+                .with_requires("openssl/3.2.0".parse()?),
+        )
+        .with_feature(FeatureMapping::new("conan-rs-install-zstd").with_bool_option("libcurl:with_zstd")?)
+        .with_conan_options(&["*:fPIC=True".parse()?])
+        .conan_version(ConanVersion::V2)
+        .build();
+    assert_eq!(
+        command.args()?,
+        [
+            "install",
+            "-o",
+            "zlib:fPIC=True",
+            "-o",
+            "zlib/*:shared=True",
+            "-o",
+            "libcurl/*:max_connections=16",
+            "-o",
+            "*:fPIC=True",
+            "-o",
+            "libcurl/*:with_ssl=openssl",
+            "-o",
+            "libcurl/*:with_zstd=False",
+            "-s",
+            "build_type=Release",
+            "--requires=openssl/3.2.0"
+        ]
+    );
+    assert!(InstallCommandBuilder::new().with_option("zlib:", true).is_err());
+
+    Ok(())
+}
+
+#[test]
+fn test_install_builder_with_lockfile() -> Result<(), Box<dyn std::error::Error>> {
+    let command = InstallCommandBuilder::new()
+        .with_host_profile("linux-x86_64")
+        .build_settings(BuildSettings::new().build_type(BuildType::Release))
+        .with_option("zlib:shared", true)?
+        .with_lockfile(Path::new("conan.lock"))
+        .lockfile_out(Path::new("out.lock"))
+        .recipe_path(Path::new("."))
+        .conan_version(ConanVersion::V1)
+        .build();
+    assert_eq!(
+        command.args()?,
+        [
+            "install",
+            "-g",
+            "json",
+            "--lockfile=conan.lock",
+            "--lockfile-out=out.lock",
+            "."
+        ]
+    );
+
+    let command = InstallCommandBuilder::new()
+        .with_host_profile("linux-x86_64")
+        .with_option("zlib:shared", true)?
+        .with_lockfile(Path::new("conan.lock"))
+        .recipe_path(Path::new("."))
+        .conan_version(ConanVersion::V2)
+        .build();
+    assert_eq!(
+        command.args()?,
+        [
+            "install",
+            "--profile:host",
+            "linux-x86_64",
+            "-o",
+            "zlib/*:shared=True",
+            "--lockfile=conan.lock",
+            "."
+        ]
+    );
+    assert_eq!(command.lockfile(), Some(Path::new("conan.lock")));
+
+    Ok(())
+}
+
+#[test]
+fn test_install_builder_with_conf_and_env() -> Result<(), Box<dyn std::error::Error>> {
+    let output_dir = std::env::temp_dir().join("conan-rs-install-conf");
+    let command = InstallCommandBuilder::new()
+        .with_conf("tools.cmake.cmaketoolchain:generator", "Ninja")
+        .with_package_conf("zlib", "tools.build:cflags", &["-O2", "-g"][..])
+        .with_env("CC", "clang")
+        .with_package_env("zlib", "CFLAGS", "-O2")
+        .output_dir(&output_dir)
+        .recipe_path(Path::new("."))
+        .conan_version(ConanVersion::V1)
+        .build();
+    let output_dir_str = output_dir.to_str().unwrap();
+    assert_eq!(
+        command.args()?,
+        [
+            "install",
+            "-g",
+            "json",
+            "-c",
+            "tools.cmake.cmaketoolchain:generator=Ninja",
+            "-c",
+            r#"zlib:tools.build:cflags=["-O2", "-g"]"#,
+            "-e",
+            "CC=clang",
+            "-e",
+            "zlib:CFLAGS=-O2",
+            "-if",
+            output_dir_str,
+            "."
+        ]
+    );
+
+    let command = InstallCommandBuilder::new()
+        .with_conf("tools.cmake.cmaketoolchain:generator", "Ninja")
+        .with_package_env("zlib", "CFLAGS", "-O2")
+        .output_dir(&output_dir)
+        .recipe_path(Path::new("."))
+        .conan_version(ConanVersion::V2)
+        .build();
+    let env_profile = command.env_profile().unwrap();
+    assert_eq!(
+        command.args()?,
+        [
+            "install",
+            "-c",
+            "tools.cmake.cmaketoolchain:generator=Ninja",
+            "--profile:host",
+            "default",
+            "--profile:host",
+            env_profile.to_str().unwrap(),
+            "--output-folder",
+            output_dir_str,
+            "."
+        ]
+    );
+    command.write_requires_file()?;
+    assert_eq!(fs::read_to_string(&env_profile)?, "[buildenv]\nzlib/*:CFLAGS=-O2\n");
+
+    let command = InstallCommandBuilder::new()
+        .with_conf("jobs", 4)
+        .conan_version(ConanVersion::V2)
+        .build();
+    assert!(matches!(command.args(), Err(ConanInstallError::InvalidConf(_))));
+
+    Ok(())
+}
+
+#[test]
+fn test_install_with_conan_home() {
+    use crate::context::ConanContext;
+    use crate::executor::RecordingExecutor;
+    use crate::home::ConanHome;
+
+    let dir = std::env::temp_dir().join(format!("conan-rs-install-home-{}", std::process::id()));
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(&dir).unwrap();
+
+    let executor = RecordingExecutor::new();
+    let home_dir = dir.join("home");
+    let command = InstallCommandBuilder::new()
+        .with_context(
+            ConanContext::new()
+                .with_program(Path::new("conan"))
+                .with_executor(executor.clone()),
+        )
+        .with_conan_home(ConanHome::new(&home_dir).with_config(&dir.join("config")))
+        .output_dir(&dir)
+        .recipe_path(Path::new("."))
+        .conan_version(ConanVersion::V1)
+        .build();
+    command.generate();
+
+    // NOTE: The home is seeded before the install, and both run with it
+    let invocations = executor.invocations();
+    let commands: Vec<&str> = invocations.iter().map(|x| x.args[0].as_str()).collect();
+    assert_eq!(commands, ["config", "install"]);
+    let home_env = ("CONAN_USER_HOME".to_string(), home_dir.to_str().unwrap().to_string());
+    assert!(invocations.iter().all(|x| x.env.contains(&home_env)));
+
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn test_install_generate_with_executor() {
+    use crate::context::ConanContext;
+    use crate::executor::{ScriptedExecutor, ScriptedResponse};
+
+    let dir = std::env::temp_dir().join(format!("conan-rs-install-executor-{}", std::process::id()));
+    let build_info = include_bytes!("../../test/conanbuildinfo1.json");
+    let executor = ScriptedExecutor::new().on(
+        &["install"],
+        ScriptedResponse::success().with_file(&dir.join("conanbuildinfo.json"), build_info),
+    );
+    let context = ConanContext::new()
+        .with_program(Path::new("conan"))
+        .with_conan_version(ConanVersion::V1)
+        .with_executor(executor.clone());
+    let command = InstallCommandBuilder::new()
+        .with_context(context)
+        .output_dir(&dir)
+        .recipe_path(Path::new("."))
+        .build();
+
+    let build_info = command.generate().expect("no build info");
+    assert!(build_info.get_dependency("openssl").is_some());
+    let invocations = executor.invocations();
+    assert_eq!(invocations.len(), 1);
+    assert_eq!(invocations[0].args, command.args().unwrap());
+
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn test_install_generate_v2() {
+    use crate::context::ConanContext;
+    use crate::executor::RecordingExecutor;
+
+    // NOTE: Conan 2 has no json generator, so nothing is installed
+    let executor = RecordingExecutor::new();
+    let command = InstallCommandBuilder::new()
+        .with_context(
+            ConanContext::new()
+                .with_program(Path::new("conan"))
+                .with_conan_version(ConanVersion::V2)
+                .with_executor(executor.clone()),
+        )
+        .output_dir(Path::new("out"))
+        .recipe_path(Path::new("."))
+        .build();
+    assert!(command.generate().is_none());
+    assert!(command.generate_if_changed().is_none());
+    assert!(executor.invocations().is_empty());
+}
+
+#[test]
+fn test_install_try_generate() {
+    use crate::context::{ConanContext, ConanContextError};
+    use crate::executor::{ScriptedExecutor, ScriptedResponse};
+    use std::time::Duration;
+
+    let executor = ScriptedExecutor::new()
+        .on(&["install"], ScriptedResponse::hanging().with_stderr("Downloading"))
+        .on(&["install"], ScriptedResponse::failure(1));
+    let command = InstallCommandBuilder::new()
+        .with_context(
+            ConanContext::new()
+                .with_program(Path::new("conan"))
+                .with_conan_version(ConanVersion::V1)
+                .with_executor(executor.clone()),
+        )
+        .with_timeout(Duration::from_secs(60))
+        .output_dir(Path::new("out"))
+        .recipe_path(Path::new("."))
+        .build();
+
+    match command.try_generate() {
+        Err(ConanInstallError::Context(ConanContextError::Timeout(timeout))) => {
+            assert_eq!(timeout.stderr, b"Downloading")
+        }
+        result => panic!("unexpected result {:?}", result.map(|_| ())),
+    }
+    assert!(matches!(
+        command.try_generate(),
+        Err(ConanInstallError::CommandFailed(_))
+    ));
+    assert_eq!(executor.invocations().len(), 2);
+}
+
+#[cfg(unix)]
+#[test]
+fn test_install_generate_with_fake_conan() {
+    use crate::test_support::{FakeConan, FakeResponse};
+
+    let dir = std::env::temp_dir().join(format!("conan-rs-install-fake-{}", std::process::id()));
+    let _ = fs::remove_dir_all(&dir);
+    let fake = FakeConan::new(&dir)
+        .on(
+            &["install"],
+            FakeResponse::success()
+                .with_output_file("conanbuildinfo.json", include_bytes!("../../test/conanbuildinfo2.json")),
+        )
+        .build()
+        .unwrap();
+    let command = InstallCommandBuilder::new()
+        .with_context(fake.context())
+        .output_dir(&dir.join("out"))
+        .recipe_path(Path::new("."))
+        .build();
+
+    let build_info = command.generate().expect("no build info");
+    assert!(!build_info.dependencies().is_empty());
+    let invocations = fake.invocations().unwrap();
+    assert_eq!(invocations.last().unwrap(), &command.args().unwrap());
+
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[cfg(all(unix, feature = "tokio"))]
+#[tokio::test]
+async fn test_install_generate_async() {
+    use crate::test_support::{FakeConan, FakeResponse};
+
+    let dir = std::env::temp_dir().join(format!("conan-rs-install-async-{}", std::process::id()));
+    let _ = fs::remove_dir_all(&dir);
+    let fake = FakeConan::new(&dir)
+        .on(
+            &["install"],
+            FakeResponse::success()
+                .with_output_file("conanbuildinfo.json", include_bytes!("../../test/conanbuildinfo2.json")),
+        )
+        .build()
+        .unwrap();
+    let command = InstallCommandBuilder::new()
+        .with_context(fake.context().with_conan_version(ConanVersion::V1))
+        .output_dir(&dir.join("out"))
+        .recipe_path(Path::new("."))
+        .build();
+
+    // NOTE: The future owns what it needs, so it can be spawned
+    let build_info = tokio::spawn(command.generate_async()).await.unwrap();
+    assert!(!build_info.expect("no build info").dependencies().is_empty());
+    assert_eq!(fake.invocations().unwrap(), [command.args().unwrap()]);
+
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn test_install_generate_if_changed() {
+    use crate::context::ConanContext;
+    use crate::executor::{ScriptedExecutor, ScriptedResponse};
+
+    let dir = std::env::temp_dir().join(format!("conan-rs-install-incremental-{}", std::process::id()));
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(&dir).unwrap();
+    let profile = dir.join("linux");
+    fs::write(&profile, "[settings]\nbuild_type=Release\n").unwrap();
+    fs::write(dir.join("conanfile.txt"), "[requires]\nzlib/1.2.13\n").unwrap();
+
+    let build_info = include_bytes!("../../test/conanbuildinfo1.json");
+    let executor = ScriptedExecutor::new()
+        .on(
+            &["--version"],
+            ScriptedResponse::success().with_stdout("Conan version 1.66.0\n"),
+        )
+        .on(
+            &["install"],
+            ScriptedResponse::success().with_file(&dir.join("out/conanbuildinfo.json"), build_info),
+        );
+    let installs = || {
+        executor
+            .invocations()
+            .iter()
+            .filter(|invocation| invocation.args[0] == "install")
+            .count()
+    };
+    let context = ConanContext::new()
+        .with_program(Path::new("conan"))
+        .with_conan_version(ConanVersion::V1)
+        .with_executor(executor.clone());
+    let command = InstallCommandBuilder::new()
+        .with_context(context.clone())
+        .with_profile(profile.to_str().unwrap())
+        .output_dir(&dir.join("out"))
+        .recipe_path(&dir)
+        .build();
+    assert_eq!(command.input_files(), [dir.join("conanfile.txt"), profile.clone()]);
+
+    assert!(command.generate_if_changed().is_some());
+    assert_eq!(installs(), 1);
+    assert!(command.generate_if_changed().is_some());
+    assert_eq!(installs(), 1);
+
+    // NOTE: Changes to the profile, the recipe or the environment trigger a reinstall
+    fs::write(&profile, "[settings]\nbuild_type=Debug\n").unwrap();
+    assert!(command.generate_if_changed().is_some());
+    assert_eq!(installs(), 2);
+    fs::write(dir.join("conanfile.txt"), "[requires]\nzlib/1.3.1\n").unwrap();
+    assert!(command.generate_if_changed().is_some());
+    assert_eq!(installs(), 3);
+    let command = InstallCommandBuilder::new()
+        .with_context(context.with_env("CONAN_CPU_COUNT", "2"))
+        .with_profile(profile.to_str().unwrap())
+        .output_dir(&dir.join("out"))
+        .recipe_path(&dir)
+        .build();
+    assert!(command.generate_if_changed().is_some());
+    assert_eq!(installs(), 4);
+    assert!(command.generate_if_changed().is_some());
+    assert_eq!(installs(), 4);
+
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn test_install_inputs() {
+    let dir = std::env::temp_dir().join(format!("conan-rs-install-inputs-{}", std::process::id()));
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(&dir).unwrap();
+    let recipe = dir.join("conanfile.py");
+    let lockfile = dir.join("conan.lock");
+    fs::write(&recipe, "from conan import ConanFile\n").unwrap();
+
+    let command = InstallCommandBuilder::new()
+        .conan_version(ConanVersion::V2)
+        .with_remote("my-remote")
+        .authenticate_remote()
+        .with_lockfile(&lockfile)
+        .output_dir(&dir.join("out"))
+        .recipe_path(&recipe)
+        .build();
+    assert_eq!(command.input_files(), [recipe, lockfile]);
+
+    let input_env = command.input_env();
+    for name in [
+        "CONAN",
+        "CONAN_HOME",
+        "CONAN_USER_HOME",
+        "HOME",
+        "USERPROFILE",
+        "PROFILE",
+        "CONAN_LOGIN_USERNAME_MY_REMOTE",
+        "CONAN_PASSWORD_MY_REMOTE",
+        "CONAN_PASSWORD",
+    ] {
+        assert!(input_env.iter().any(|input| input == name), "{} is not an input", name);
+    }
+    // NOTE: The output directory is set, so OUT_DIR is not read
+    assert!(!input_env.iter().any(|input| input == "OUT_DIR"));
+    command.cargo_emit_inputs();
+
+    fs::remove_dir_all(&dir).unwrap();
+}
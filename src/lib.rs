@@ -1,12 +1,61 @@
 mod build;
+mod conanfile;
+mod conf;
+mod context;
+mod create;
+mod executor;
+mod export;
+mod features;
+mod home;
 mod install;
+mod lock;
+mod metadata;
+mod options;
+mod output;
 mod package;
+mod reference;
+mod retry;
+mod search;
+mod upload;
 mod util;
+mod version;
+
+#[cfg(any(test, feature = "test-support"))]
+pub mod test_support;
 
 // API
-pub use build::{BuildCommand, BuildCommandBuilder};
+pub use build::{BuildCommand, BuildCommandBuilder, ConanBuildError};
+pub use conanfile::{ConanfileError, ConanfileTxt};
+pub use conf::{ConanConf, ConanEnv, ConfError, ConfValue};
+pub use context::{ConanContext, ConanContextError, Verbosity};
+pub use create::{ConanCreateError, CreateCommand, CreateCommandBuilder, CreatedPackage};
+pub use executor::{
+    CommandOutput, Executor, Invocation, LineHandler, OutputStream, RecordingExecutor, ScriptedExecutor,
+    ScriptedResponse, SystemExecutor, TimeoutError,
+};
+pub use export::{
+    ConanExportError, ExportCommand, ExportCommandBuilder, ExportPkgCommand, ExportPkgCommandBuilder, ExportedPackage,
+};
+pub use features::{feature_enabled, FeatureMapping};
+pub use home::{ConanHome, ConanHomeError};
 pub use install::{
     build_info::{BuildDependency, BuildInfo, BuildSettings},
-    BuildPolicy, InstallCommand, InstallCommandBuilder,
+    remote::{RemoteCredentials, RemoteLoginCommand, RemoteLoginCommandBuilder},
+    BuildPolicy, BuildPolicyError, BuildRule, ConanInstallError, InstallCommand, InstallCommandBuilder,
+};
+pub use lock::{
+    ConanLockError, LockAddCommand, LockAddCommandBuilder, LockCreateCommand, LockCreateCommandBuilder,
+    LockMergeCommand, LockMergeCommandBuilder, LockUpdateCommand, LockUpdateCommandBuilder, LockedReference, Lockfile,
+};
+pub use metadata::{from_cargo_metadata, ConanMetadata, MetadataError};
+pub use options::{ConanOption, OptionError, OptionValue};
+pub use output::{cargo_progress, ConanEvent, OutputSummary};
+pub use package::{ConanPackage, ConanPackageError, PackageCommand, PackageCommandBuilder};
+pub use reference::{ConanReference, PackageReference, ReferenceError};
+pub use retry::{FailureKind, RetryPolicy};
+pub use search::{ConanSearchError, PackageInfo, RecipeReference, SearchCommand, SearchCommandBuilder, SearchResults};
+pub use upload::{
+    ConanUploadError, UploadCommand, UploadCommandBuilder, UploadReport, UploadedPackage, UploadedRecipe,
 };
-pub use package::{ConanPackage, PackageCommand, PackageCommandBuilder};
+pub use util::ConanVersion;
+pub use version::{Version, VersionError, VersionRange};
@@ -0,0 +1,219 @@
+use super::*;
+use crate::install::build_info::build_settings::BuildType;
+
+#[test]
+fn test_lockfile_v1() -> Result<(), Box<dyn std::error::Error>> {
+    let lockfile: Lockfile = include_str!("../../test/conan_lock_v1.json").parse()?;
+    assert_eq!(lockfile.version, "0.4");
+    assert_eq!(lockfile.requires.len(), 2);
+    assert_eq!(lockfile.build_requires.len(), 1);
+
+    let zlib = lockfile.get("zlib").unwrap();
+    assert_eq!(zlib.reference.version, "1.2.13");
+    assert_eq!(zlib.reference.user.as_deref(), Some("devolutions"));
+    assert!(zlib.package_id.is_some());
+    assert!(zlib.timestamp.is_none());
+
+    assert_eq!(lockfile.get("cmake").unwrap().reference.version, "3.25.3");
+    assert!(lockfile.get("boost").is_none());
+
+    Ok(())
+}
+
+#[test]
+fn test_lockfile_v2() -> Result<(), Box<dyn std::error::Error>> {
+    let lockfile: Lockfile = include_str!("../../test/conan_lock_v2.json").parse()?;
+    assert_eq!(lockfile.version, "0.5");
+    assert_eq!(lockfile.references().count(), 3);
+
+    let zlib = lockfile.get("zlib").unwrap();
+    assert_eq!(zlib.reference.version, "1.3.1");
+    assert_eq!(
+        zlib.reference.revision.as_deref(),
+        Some("f52e03ae3d251dec704634230cd806a2")
+    );
+    assert_eq!(zlib.timestamp.as_deref(), Some("1708593606.497"));
+    assert_eq!(lockfile.build_requires[0].reference.name, "cmake");
+
+    assert!(r#"{"requires": [42]}"#.parse::<Lockfile>().is_err());
+    assert!(r#"{"graph_lock": {}}"#.parse::<Lockfile>().is_err());
+
+    Ok(())
+}
+
+#[test]
+fn test_lock_create_args() -> Result<(), Box<dyn std::error::Error>> {
+    let command = LockCreateCommandBuilder::new()
+        .recipe_path(Path::new("path/to/recipe"))
+        .with_host_profile("linux-x86_64")
+        .build_settings(BuildSettings::new().build_type(BuildType::Release))
+        .with_conan_options(&["zlib:shared=True".parse()?])
+        .conan_version(ConanVersion::V1)
+        .build();
+    assert_eq!(
+        command.args()?,
+        [
+            "lock",
+            "create",
+            "path/to/recipe",
+            "--lockfile-out=conan.lock",
+            "--profile:host",
+            "linux-x86_64",
+            "-s",
+            "build_type=Release",
+            "-o",
+            "zlib:shared=True"
+        ]
+    );
+
+    let command = LockCreateCommandBuilder::new()
+        .with_requires(&["zlib/1.3.1".parse()?])
+        .with_lockfile(Path::new("base.lock"))
+        .lockfile_out(Path::new("out/conan.lock"))
+        .with_remote("conancenter")
+        .conan_version(ConanVersion::V2)
+        .build();
+    assert_eq!(
+        command.args()?,
+        [
+            "lock",
+            "create",
+            "--requires=zlib/1.3.1",
+            "--lockfile=base.lock",
+            "--lockfile-out=out/conan.lock",
+            "-r",
+            "conancenter"
+        ]
+    );
+    assert_eq!(command.lockfile_out(), Path::new("out/conan.lock"));
+
+    let command = LockCreateCommandBuilder::new()
+        .with_requires(&["zlib/1.3.1".parse()?])
+        .conan_version(ConanVersion::V1)
+        .build();
+    assert!(matches!(
+        command.args(),
+        Err(ConanLockError::UnsupportedArgument("--requires"))
+    ));
+
+    Ok(())
+}
+
+#[test]
+fn test_lock_update_args() -> Result<(), Box<dyn std::error::Error>> {
+    let command = LockUpdateCommandBuilder::new(Path::new("conan.lock"))
+        .with_update_from(Path::new("new.lock"))
+        .conan_version(ConanVersion::V1)
+        .build();
+    assert_eq!(command.args()?, ["lock", "update", "conan.lock", "new.lock"]);
+    assert_eq!(command.lockfile_out(), Path::new("conan.lock"));
+
+    let command = LockUpdateCommandBuilder::new(Path::new("conan.lock"))
+        .conan_version(ConanVersion::V1)
+        .build();
+    assert!(matches!(command.args(), Err(ConanLockError::MissingArgument(_))));
+
+    let command = LockUpdateCommandBuilder::new(Path::new("conan.lock"))
+        .with_requires(&["zlib/1.3.1".parse()?])
+        .with_build_requires(&["cmake/3.28.1".parse()?])
+        .conan_version(ConanVersion::V2)
+        .build();
+    assert_eq!(
+        command.args()?,
+        [
+            "lock",
+            "update",
+            "--lockfile=conan.lock",
+            "--requires=zlib/1.3.1",
+            "--build-requires=cmake/3.28.1",
+            "--lockfile-out=conan.lock"
+        ]
+    );
+
+    let command = LockUpdateCommandBuilder::new(Path::new("conan.lock"))
+        .with_update_from(Path::new("new.lock"))
+        .conan_version(ConanVersion::V2)
+        .build();
+    assert!(matches!(command.args(), Err(ConanLockError::UnsupportedArgument(_))));
+
+    Ok(())
+}
+
+#[test]
+fn test_lock_merge_and_add_args() -> Result<(), Box<dyn std::error::Error>> {
+    let command = LockMergeCommandBuilder::new(&[PathBuf::from("linux.lock"), PathBuf::from("windows.lock")])
+        .conan_version(ConanVersion::V2)
+        .build();
+    assert_eq!(
+        command.args()?,
+        [
+            "lock",
+            "merge",
+            "--lockfile=linux.lock",
+            "--lockfile=windows.lock",
+            "--lockfile-out=conan.lock"
+        ]
+    );
+
+    let command = LockMergeCommandBuilder::new(&[])
+        .conan_version(ConanVersion::V2)
+        .build();
+    assert!(matches!(command.args(), Err(ConanLockError::MissingArgument(_))));
+
+    let command = LockAddCommandBuilder::new()
+        .with_lockfile(Path::new("conan.lock"))
+        .with_requires(&["zlib/1.3.1".parse()?])
+        .with_python_requires(&["pyreq/1.0".parse()?])
+        .conan_version(ConanVersion::V2)
+        .build();
+    assert_eq!(
+        command.args()?,
+        [
+            "lock",
+            "add",
+            "--lockfile=conan.lock",
+            "--requires=zlib/1.3.1",
+            "--python-requires=pyreq/1.0",
+            "--lockfile-out=conan.lock"
+        ]
+    );
+
+    let command = LockAddCommandBuilder::new().conan_version(ConanVersion::V1).build();
+    assert!(matches!(
+        command.args(),
+        Err(ConanLockError::UnsupportedArgument("conan lock add"))
+    ));
+
+    Ok(())
+}
+
+#[test]
+fn test_lock_create_in_working_dir() -> Result<(), Box<dyn std::error::Error>> {
+    use crate::context::ConanContext;
+    use crate::executor::{ScriptedExecutor, ScriptedResponse};
+
+    let dir = std::env::temp_dir().join(format!("conan-rs-lock-working-dir-{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(&dir)?;
+
+    // NOTE: The default conan.lock is written in the working directory of Conan, not the current one
+    let executor = ScriptedExecutor::new().on(
+        &["lock", "create"],
+        ScriptedResponse::success().with_file(Path::new("conan.lock"), include_bytes!("../../test/conan_lock_v2.json")),
+    );
+    let context = ConanContext::new()
+        .with_program(Path::new("conan"))
+        .with_conan_version(ConanVersion::V2)
+        .with_working_dir(&dir)
+        .with_executor(executor);
+    let lockfile = LockCreateCommandBuilder::new()
+        .with_context(context)
+        .with_requires(&["zlib/1.3.1".parse()?])
+        .build()
+        .run()?;
+    assert_eq!(lockfile, Lockfile::from_file(&dir.join("conan.lock"))?);
+    assert!(!Path::new("conan.lock").exists());
+
+    std::fs::remove_dir_all(&dir)?;
+    Ok(())
+}
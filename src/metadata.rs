@@ -0,0 +1,322 @@
+#[cfg(test)]
+mod tests;
+
+use std::collections::BTreeMap;
+use std::env;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use serde::{de, Deserialize, Deserializer};
+use thiserror::Error;
+
+use crate::features::feature_enabled;
+use crate::install::build_info::BuildInfo;
+use crate::install::build_policy::BUILD_POLICY_ENV;
+use crate::install::{BuildPolicy, BuildPolicyError, BuildRule, ConanInstallError, InstallCommandBuilder};
+use crate::lock::{ConanLockError, Lockfile};
+use crate::options::{ConanOption, OptionError};
+use crate::reference::{ConanReference, ReferenceError};
+
+#[derive(Debug, Error)]
+pub enum MetadataError {
+    #[error("CARGO_MANIFEST_DIR is not set")]
+    ManifestDirNotFound,
+    #[error("No [package.metadata.conan] section in {0}")]
+    MissingMetadata(PathBuf),
+    #[error("I/O error: {0}")]
+    Io(#[from] io::Error),
+    #[error("Invalid Cargo manifest: {0}")]
+    Toml(#[from] toml::de::Error),
+    #[error(transparent)]
+    InvalidReference(#[from] ReferenceError),
+    #[error(transparent)]
+    InvalidOption(#[from] OptionError),
+    #[error(transparent)]
+    InvalidBuildPolicy(#[from] BuildPolicyError),
+    #[error(transparent)]
+    Install(#[from] ConanInstallError),
+    #[error(transparent)]
+    Lock(#[from] ConanLockError),
+}
+
+/// Conan dependencies declared in the `[package.metadata.conan]` section of `Cargo.toml`
+///
+/// ```toml
+/// [package.metadata.conan]
+/// requires = ["zlib/1.3.1", "openssl/3.2.0"]
+/// tool-requires = ["cmake/3.27.7"]
+/// options = { "zlib:shared" = true }
+/// build-policy = "missing"
+/// remote = "conancenter"
+/// profile = "linux-x86_64"
+///
+/// [package.metadata.conan.target.windows]
+/// profile = "windows-x86_64"
+///
+/// [package.metadata.conan.features.ssl]
+/// requires = ["openssl/3.2.0"]
+/// options = { "libcurl:with_ssl" = "openssl" }
+/// ```
+///
+/// Without requirements, the `recipe` (by default the crate directory) is installed.
+/// The `target` tables are keyed by target OS (`CARGO_CFG_TARGET_OS`) or target triple (`TARGET`),
+/// and override the settings above them. The `features` tables are keyed by Cargo feature,
+/// and apply on top of the target overrides when the feature is enabled.
+///
+/// A `conan.lock` next to `Cargo.toml` is used unless another `lockfile` is declared.
+#[derive(Clone, Debug, Default, Deserialize, PartialEq)]
+#[serde(default, rename_all = "kebab-case")]
+pub struct ConanMetadata {
+    pub recipe: Option<PathBuf>,
+    pub requires: Vec<String>,
+    #[serde(alias = "tool_requires")]
+    pub tool_requires: Vec<String>,
+    /// Options as `pkg:name=value`, declared as a table or a list of strings
+    #[serde(deserialize_with = "deserialize_options")]
+    pub options: Vec<String>,
+    #[serde(alias = "build_policy")]
+    pub build_policy: Option<String>,
+    pub remote: Option<String>,
+    pub profile: Option<String>,
+    #[serde(alias = "build_profile")]
+    pub build_profile: Option<String>,
+    pub lockfile: Option<PathBuf>,
+    pub target: BTreeMap<String, ConanMetadata>,
+    pub features: BTreeMap<String, ConanMetadata>,
+    #[serde(skip)]
+    manifest_dir: Option<PathBuf>,
+}
+
+impl ConanMetadata {
+    /// Read the metadata of the crate being built, from `CARGO_MANIFEST_DIR/Cargo.toml`.
+    pub fn from_env() -> Result<Self, MetadataError> {
+        ConanMetadata::from_manifest(&manifest_path()?)
+    }
+
+    pub fn from_manifest(path: &Path) -> Result<Self, MetadataError> {
+        let mut metadata = ConanMetadata::from_toml(&fs::read_to_string(path)?).map_err(|error| match error {
+            MetadataError::MissingMetadata(_) => MetadataError::MissingMetadata(path.to_path_buf()),
+            error => error,
+        })?;
+        metadata.manifest_dir = path.parent().map(Path::to_path_buf);
+        Ok(metadata)
+    }
+
+    /// Parse the metadata from the content of a Cargo manifest.
+    pub fn from_toml(manifest: &str) -> Result<Self, MetadataError> {
+        let manifest: toml::Value = toml::from_str(manifest)?;
+        let metadata = manifest
+            .get("package")
+            .and_then(|x| x.get("metadata"))
+            .and_then(|x| x.get("conan"))
+            .ok_or_else(|| MetadataError::MissingMetadata(PathBuf::from("Cargo.toml")))?;
+        Ok(metadata.clone().try_into()?)
+    }
+
+    /// Metadata with the overrides of the given target OS, then of the given target triple, applied.
+    pub fn for_target(&self, target_os: &str, target: &str) -> ConanMetadata {
+        let mut metadata = ConanMetadata {
+            target: BTreeMap::new(),
+            ..self.clone()
+        };
+        for key in [target_os, target].iter() {
+            if let Some(overrides) = self.target.get(*key) {
+                metadata.merge(overrides);
+            }
+        }
+        metadata
+    }
+
+    /// Metadata with the overrides of the enabled Cargo features applied.
+    pub fn for_features<F>(&self, is_enabled: F) -> ConanMetadata
+    where
+        F: Fn(&str) -> bool,
+    {
+        let mut metadata = ConanMetadata {
+            features: BTreeMap::new(),
+            ..self.clone()
+        };
+        for (feature, overrides) in &self.features {
+            if is_enabled(feature) {
+                metadata.merge(overrides);
+            }
+        }
+        metadata
+    }
+
+    fn merge(&mut self, overrides: &ConanMetadata) {
+        if overrides.recipe.is_some() {
+            self.recipe = overrides.recipe.clone();
+        }
+        upsert(&mut self.requires, &overrides.requires, '/');
+        upsert(&mut self.tool_requires, &overrides.tool_requires, '/');
+        upsert(&mut self.options, &overrides.options, '=');
+        if overrides.build_policy.is_some() {
+            self.build_policy = overrides.build_policy.clone();
+        }
+        if overrides.remote.is_some() {
+            self.remote = overrides.remote.clone();
+        }
+        if overrides.profile.is_some() {
+            self.profile = overrides.profile.clone();
+        }
+        if overrides.build_profile.is_some() {
+            self.build_profile = overrides.build_profile.clone();
+        }
+        if overrides.lockfile.is_some() {
+            self.lockfile = overrides.lockfile.clone();
+        }
+    }
+
+    /// Build policy, e.g. `"missing, !openssl"`, defaulting to `missing`.
+    pub fn build_policy(&self) -> Result<BuildPolicy, MetadataError> {
+        match self.build_policy.as_deref() {
+            Some(build_policy) => Ok(build_policy.parse()?),
+            None => Ok(BuildRule::Missing.into()),
+        }
+    }
+
+    /// "conan install" builder for this metadata, which callers may customize further.
+    pub fn install_command(&self) -> Result<InstallCommandBuilder<'_>, MetadataError> {
+        let requires = parse_references(&self.requires)?;
+        let tool_requires = parse_references(&self.tool_requires)?;
+        let options = self
+            .options
+            .iter()
+            .map(|x| x.parse())
+            .collect::<Result<Vec<ConanOption>, _>>()?;
+
+        let mut command = InstallCommandBuilder::new()
+            .with_requires(&requires)
+            .with_tool_requires(&tool_requires)
+            .with_conan_options(&options)
+            .build_policy(self.build_policy()?);
+
+        if let Some(remote) = &self.remote {
+            command = command.with_remote(remote);
+        }
+        if let Some(profile) = &self.profile {
+            command = command.with_host_profile(profile);
+        }
+        if let Some(profile) = &self.build_profile {
+            command = command.with_build_profile(profile);
+        }
+
+        let recipe = match (&self.recipe, &self.manifest_dir) {
+            (Some(recipe), Some(manifest_dir)) => Some(manifest_dir.join(recipe)),
+            (Some(recipe), None) => Some(recipe.clone()),
+            (None, manifest_dir) if requires.is_empty() && tool_requires.is_empty() => manifest_dir.clone(),
+            (None, _) => None,
+        };
+        if let Some(recipe) = recipe {
+            command = command.recipe_path(&recipe);
+        }
+        if let Some(lockfile) = self.lockfile() {
+            command = command.with_lockfile(&lockfile);
+        }
+
+        Ok(command)
+    }
+
+    /// Declared lockfile, or the `conan.lock` checked in next to `Cargo.toml` if there is one.
+    pub fn lockfile(&self) -> Option<PathBuf> {
+        match (&self.lockfile, &self.manifest_dir) {
+            (Some(lockfile), Some(manifest_dir)) => Some(manifest_dir.join(lockfile)),
+            (Some(lockfile), None) => Some(lockfile.clone()),
+            (None, Some(manifest_dir)) => Some(manifest_dir.join("conan.lock")).filter(|x| x.is_file()),
+            (None, None) => None,
+        }
+    }
+}
+
+/// Add or replace the values sharing the key found before `separator`.
+fn upsert(values: &mut Vec<String>, overrides: &[String], separator: char) {
+    let key = |value: &str| value.split(separator).next().unwrap_or_default().trim().to_string();
+    for value in overrides {
+        match values.iter_mut().find(|x| key(x) == key(value)) {
+            Some(existing) => *existing = value.clone(),
+            None => values.push(value.clone()),
+        }
+    }
+}
+
+fn parse_references(references: &[String]) -> Result<Vec<ConanReference>, ReferenceError> {
+    references.iter().map(|x| x.parse()).collect()
+}
+
+fn deserialize_options<'de, D>(deserializer: D) -> Result<Vec<String>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum Options {
+        List(Vec<String>),
+        Table(BTreeMap<String, toml::Value>),
+    }
+
+    match Options::deserialize(deserializer)? {
+        Options::List(options) => Ok(options),
+        Options::Table(options) => options
+            .into_iter()
+            .map(|(name, value)| {
+                let value = match value {
+                    toml::Value::Boolean(true) => "True".to_string(),
+                    toml::Value::Boolean(false) => "False".to_string(),
+                    toml::Value::String(value) => value,
+                    toml::Value::Integer(value) => value.to_string(),
+                    toml::Value::Float(value) => value.to_string(),
+                    value => {
+                        return Err(de::Error::custom(format!(
+                            "invalid value for option {}: {}",
+                            name, value
+                        )))
+                    }
+                };
+                Ok(format!("{}={}", name, value))
+            })
+            .collect(),
+    }
+}
+
+fn manifest_path() -> Result<PathBuf, MetadataError> {
+    let manifest_dir = env::var_os("CARGO_MANIFEST_DIR").ok_or(MetadataError::ManifestDirNotFound)?;
+    Ok(PathBuf::from(manifest_dir).join("Cargo.toml"))
+}
+
+/// Install the Conan dependencies declared in `[package.metadata.conan]` and link them.
+///
+/// The `CONAN_BUILD_POLICY` environment variable, when set, overrides the declared build policy.
+///
+/// This is the whole build script of a typical `-sys` crate:
+///
+/// ```no_run
+/// // build.rs
+/// conan::from_cargo_metadata().expect("conan install failed");
+/// ```
+pub fn from_cargo_metadata() -> Result<BuildInfo, MetadataError> {
+    let manifest_path = manifest_path()?;
+    println!("cargo:rerun-if-changed={}", manifest_path.display());
+
+    let target_os = env::var("CARGO_CFG_TARGET_OS").unwrap_or_default();
+    let target = env::var("TARGET").unwrap_or_default();
+    let metadata = ConanMetadata::from_manifest(&manifest_path)?
+        .for_target(&target_os, &target)
+        .for_features(feature_enabled);
+
+    // NOTE: CI can override the declared build policy, e.g. to rebuild everything
+    println!("cargo:rerun-if-env-changed={}", BUILD_POLICY_ENV);
+    let mut command = metadata.install_command()?;
+    if let Some(build_policy) = BuildPolicy::from_default_env()? {
+        command = command.build_policy(build_policy);
+    }
+    let command = command.build();
+    let build_info = command.try_generate()?;
+    build_info.check_options(command.options())?;
+    if let Some(lockfile) = command.lockfile() {
+        build_info.check_lockfile(&Lockfile::from_file(lockfile)?)?;
+    }
+    build_info.cargo_emit();
+    Ok(build_info)
+}
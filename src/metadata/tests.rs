@@ -0,0 +1,190 @@
+use super::*;
+use crate::util::ConanVersion;
+
+const MANIFEST: &str = r#"
+[package]
+name = "zlib-sys"
+version = "0.1.0"
+
+[package.metadata.conan]
+requires = ["zlib/1.3.1", "openssl/3.2.0"]
+tool-requires = ["cmake/3.27.7"]
+options = { "zlib:shared" = true, "openssl:openssldir" = "/etc/ssl" }
+remote = "conancenter"
+profile = "linux-x86_64"
+
+[package.metadata.conan.target.windows]
+requires = ["openssl/3.1.4"]
+options = { "zlib:shared" = false }
+profile = "windows-x86_64"
+
+[package.metadata.conan.target.x86_64-pc-windows-msvc]
+build-policy = "never"
+
+[package.metadata.conan.features.ssl]
+requires = ["libcurl/8.5.0"]
+options = { "libcurl:with_ssl" = "openssl", "libcurl:shared" = true }
+"#;
+
+#[test]
+fn test_metadata_from_toml() -> Result<(), Box<dyn std::error::Error>> {
+    let metadata = ConanMetadata::from_toml(MANIFEST)?;
+    assert_eq!(metadata.requires, ["zlib/1.3.1", "openssl/3.2.0"]);
+    assert_eq!(metadata.tool_requires, ["cmake/3.27.7"]);
+    assert_eq!(metadata.options, ["openssl:openssldir=/etc/ssl", "zlib:shared=True"]);
+    assert_eq!(metadata.remote.as_deref(), Some("conancenter"));
+    assert_eq!(metadata.target.len(), 2);
+    assert_eq!(metadata.features.len(), 1);
+
+    let metadata = ConanMetadata::from_toml("[package.metadata.conan]\noptions = [\"shared=True\"]\n")?;
+    assert_eq!(metadata.options, ["shared=True"]);
+
+    assert!(matches!(
+        ConanMetadata::from_toml("[package]\nname = \"foo\"\n"),
+        Err(MetadataError::MissingMetadata(_))
+    ));
+    assert!(matches!(
+        ConanMetadata::from_toml("[package.metadata.conan]\noptions = { shared = [1] }\n"),
+        Err(MetadataError::Toml(_))
+    ));
+
+    Ok(())
+}
+
+#[test]
+fn test_metadata_for_target() -> Result<(), Box<dyn std::error::Error>> {
+    let metadata = ConanMetadata::from_toml(MANIFEST)?;
+
+    let linux = metadata.for_target("linux", "x86_64-unknown-linux-gnu");
+    assert_eq!(linux.profile.as_deref(), Some("linux-x86_64"));
+    assert!(linux.target.is_empty());
+
+    let windows = metadata.for_target("windows", "x86_64-pc-windows-msvc");
+    assert_eq!(windows.requires, ["zlib/1.3.1", "openssl/3.1.4"]);
+    assert_eq!(windows.options, ["openssl:openssldir=/etc/ssl", "zlib:shared=False"]);
+    assert_eq!(windows.profile.as_deref(), Some("windows-x86_64"));
+    assert_eq!(windows.build_policy()?, BuildRule::Never.into());
+
+    Ok(())
+}
+
+#[test]
+fn test_metadata_for_features() -> Result<(), Box<dyn std::error::Error>> {
+    let metadata = ConanMetadata::from_toml(MANIFEST)?;
+    assert_eq!(metadata.features.len(), 1);
+
+    let without_ssl = metadata.for_features(|_| false);
+    assert_eq!(without_ssl.requires, ["zlib/1.3.1", "openssl/3.2.0"]);
+    assert!(without_ssl.features.is_empty());
+
+    let with_ssl = metadata.for_features(|feature| feature == "ssl");
+    assert_eq!(with_ssl.requires, ["zlib/1.3.1", "openssl/3.2.0", "libcurl/8.5.0"]);
+    assert_eq!(
+        with_ssl.options,
+        [
+            "openssl:openssldir=/etc/ssl",
+            "zlib:shared=True",
+            "libcurl:shared=True",
+            "libcurl:with_ssl=openssl"
+        ]
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_metadata_install_command() -> Result<(), Box<dyn std::error::Error>> {
+    let metadata = ConanMetadata::from_toml(MANIFEST)?.for_target("linux", "x86_64-unknown-linux-gnu");
+    let command = metadata
+        .install_command()?
+        .output_dir(Path::new("target/conan-metadata-test"))
+        .conan_version(ConanVersion::V2)
+        .build();
+    let args = command.args()?;
+    assert_eq!(
+        args[..12],
+        [
+            "install",
+            "--profile:host",
+            "linux-x86_64",
+            "-r",
+            "conancenter",
+            "--build=missing",
+            "-o",
+            "openssl/*:openssldir=/etc/ssl",
+            "-o",
+            "zlib/*:shared=True",
+            "--output-folder",
+            "target/conan-metadata-test",
+        ]
+    );
+    assert_eq!(
+        args[args.len() - 3..],
+        [
+            "--requires=zlib/1.3.1",
+            "--requires=openssl/3.2.0",
+            "--tool-requires=cmake/3.27.7"
+        ]
+    );
+
+    let metadata = ConanMetadata::from_toml("[package.metadata.conan]\nbuild-policy = \"missing:\"\n")?;
+    assert!(matches!(
+        metadata.install_command(),
+        Err(MetadataError::InvalidBuildPolicy(_))
+    ));
+
+    let metadata = ConanMetadata::from_toml("[package.metadata.conan]\noptions = [\"zlib:shared\"]\n")?;
+    assert!(matches!(
+        metadata.install_command(),
+        Err(MetadataError::InvalidOption(_))
+    ));
+
+    let metadata = ConanMetadata::from_toml("[package.metadata.conan]\nrequires = [\"zlib\"]\n")?;
+    assert!(matches!(
+        metadata.install_command(),
+        Err(MetadataError::InvalidReference(_))
+    ));
+
+    Ok(())
+}
+
+#[test]
+fn test_metadata_recipe_path() -> Result<(), Box<dyn std::error::Error>> {
+    let manifest = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("Cargo.toml");
+    let mut metadata = ConanMetadata::from_toml("[package.metadata.conan]\nrecipe = \"conanfile.txt\"\n")?;
+    metadata.manifest_dir = manifest.parent().map(Path::to_path_buf);
+
+    let recipe = Path::new(env!("CARGO_MANIFEST_DIR")).join("conanfile.txt");
+    {
+        let command = metadata.install_command()?.conan_version(ConanVersion::V2).build();
+        assert_eq!(command.args()?.last().map(String::as_str), recipe.to_str());
+    }
+
+    metadata.recipe = None;
+    let command = metadata.install_command()?.conan_version(ConanVersion::V2).build();
+    assert_eq!(
+        command.args()?.last().map(String::as_str),
+        Some(env!("CARGO_MANIFEST_DIR"))
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_metadata_lockfile() -> Result<(), Box<dyn std::error::Error>> {
+    let mut metadata = ConanMetadata::from_toml("[package.metadata.conan]\nlockfile = \"locks/conan.lock\"\n")?;
+    assert_eq!(metadata.lockfile(), Some(PathBuf::from("locks/conan.lock")));
+
+    metadata.manifest_dir = Some(PathBuf::from(env!("CARGO_MANIFEST_DIR")));
+    let lockfile = Path::new(env!("CARGO_MANIFEST_DIR")).join("locks/conan.lock");
+    {
+        let command = metadata.install_command()?.conan_version(ConanVersion::V2).build();
+        assert_eq!(command.lockfile(), Some(lockfile.as_path()));
+    }
+
+    // NOTE: This repository has no checked-in conan.lock
+    metadata.lockfile = None;
+    assert_eq!(metadata.lockfile(), None);
+
+    Ok(())
+}
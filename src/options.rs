@@ -0,0 +1,224 @@
+#[cfg(test)]
+mod tests;
+
+use std::convert::Infallible;
+use std::fmt;
+use std::str::FromStr;
+
+use lazy_static::lazy_static;
+use regex::Regex;
+use thiserror::Error;
+
+use crate::util::{glob_match, package_pattern, ConanVersion};
+
+lazy_static! {
+    static ref REGEX_OPTION_NAME: Regex = Regex::new(r"^[a-zA-Z_][a-zA-Z0-9_.]*$").unwrap();
+    static ref REGEX_PACKAGE_PATTERN: Regex = Regex::new(r"^[a-zA-Z0-9_+.*?/@\[\]<>=~^-]+$").unwrap();
+}
+
+#[derive(Debug, Error, Clone, PartialEq)]
+pub enum OptionError {
+    #[error("Missing value in option \"{0}\"")]
+    MissingValue(String),
+    #[error("Invalid option name \"{0}\"")]
+    InvalidName(String),
+    #[error("Invalid package pattern \"{0}\"")]
+    InvalidPackage(String),
+    #[error("Package {0} not found in the build info")]
+    UnknownPackage(String),
+    #[error("Package {package} has no option {name}")]
+    UnknownOption { package: String, name: String },
+    #[error("Option {package}:{name} is {actual}, expected {expected}")]
+    Mismatch {
+        package: String,
+        name: String,
+        expected: String,
+        actual: String,
+    },
+}
+
+/// Value of a Conan option
+///
+/// Booleans are written the Python way (`True`/`False`), as Conan expects them.
+/// Only these literals are parsed as booleans, so `true` stays a string.
+#[derive(Clone, Debug, PartialEq)]
+pub enum OptionValue {
+    Bool(bool),
+    Integer(i64),
+    Float(f64),
+    String(String),
+}
+
+impl OptionValue {
+    fn parse(value: &str) -> Self {
+        // NOTE: Numbers are only typed when they format back to the same text, e.g. "1.10" stays a string
+        let value = value.trim();
+        match value {
+            "True" => OptionValue::Bool(true),
+            "False" => OptionValue::Bool(false),
+            value => match (value.parse::<i64>(), value.parse::<f64>()) {
+                (Ok(number), _) if number.to_string() == value => OptionValue::Integer(number),
+                (_, Ok(number)) if number.is_finite() && number.to_string() == value => OptionValue::Float(number),
+                _ => OptionValue::String(value.to_string()),
+            },
+        }
+    }
+}
+
+impl FromStr for OptionValue {
+    type Err = Infallible;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(OptionValue::parse(s))
+    }
+}
+
+impl fmt::Display for OptionValue {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            OptionValue::Bool(true) => f.write_str("True"),
+            OptionValue::Bool(false) => f.write_str("False"),
+            OptionValue::Integer(value) => write!(f, "{}", value),
+            OptionValue::Float(value) => write!(f, "{}", value),
+            OptionValue::String(value) => f.write_str(value),
+        }
+    }
+}
+
+impl From<bool> for OptionValue {
+    fn from(value: bool) -> Self {
+        OptionValue::Bool(value)
+    }
+}
+
+impl From<i32> for OptionValue {
+    fn from(value: i32) -> Self {
+        OptionValue::Integer(value.into())
+    }
+}
+
+impl From<i64> for OptionValue {
+    fn from(value: i64) -> Self {
+        OptionValue::Integer(value)
+    }
+}
+
+impl From<f64> for OptionValue {
+    fn from(value: f64) -> Self {
+        OptionValue::Float(value)
+    }
+}
+
+impl From<&str> for OptionValue {
+    fn from(value: &str) -> Self {
+        OptionValue::String(value.to_string())
+    }
+}
+
+impl From<String> for OptionValue {
+    fn from(value: String) -> Self {
+        OptionValue::String(value)
+    }
+}
+
+/// Package option: `[package:]name=value`
+///
+/// The package is a name or a pattern, e.g. `zlib`, `zlib/*` or `*`.
+/// Options without a package apply to the consumer recipe.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ConanOption {
+    pub package: Option<String>,
+    pub name: String,
+    pub value: OptionValue,
+}
+
+impl ConanOption {
+    pub fn new<V: Into<OptionValue>>(name: &str, value: V) -> Result<Self, OptionError> {
+        let option = ConanOption {
+            package: None,
+            name: name.to_string(),
+            value: value.into(),
+        };
+        option.validate()?;
+        Ok(option)
+    }
+
+    pub fn for_package(mut self, package: &str) -> Result<Self, OptionError> {
+        self.package = Some(package.to_string());
+        self.validate()?;
+        Ok(self)
+    }
+
+    /// Split an option name qualified by its package, e.g. `zlib:shared`, and validate both parts.
+    pub(crate) fn from_qualified_name(name: &str, value: OptionValue) -> Result<Self, OptionError> {
+        let (package, name) = match name.rfind(':') {
+            Some(index) => (Some(name[..index].to_string()), &name[index + 1..]),
+            None => (None, name),
+        };
+        let option = ConanOption {
+            package,
+            name: name.to_string(),
+            value,
+        };
+        option.validate()?;
+        Ok(option)
+    }
+
+    /// Format the option for the command line of the given Conan version.
+    ///
+    /// Conan 2 matches the package against references, so `zlib:shared` becomes `zlib/*:shared`,
+    /// while Conan 1 only matches package names.
+    pub fn to_cli_string(&self, conan_version: ConanVersion) -> String {
+        let package = self
+            .package
+            .as_deref()
+            .map(|package| package_pattern(package, conan_version));
+        match package {
+            Some(package) => format!("{}:{}={}", package, self.name, self.value),
+            None => format!("{}={}", self.name, self.value),
+        }
+    }
+
+    /// Whether the package pattern matches a package of the given name and version.
+    pub fn matches_package(&self, name: &str, version: Option<&str>) -> bool {
+        match &self.package {
+            None => false,
+            Some(package) if package.contains('/') => match version {
+                Some(version) => glob_match(package, &format!("{}/{}", name, version)),
+                None => glob_match(package.split('/').next().unwrap_or_default(), name),
+            },
+            Some(package) => glob_match(package, name),
+        }
+    }
+
+    fn validate(&self) -> Result<(), OptionError> {
+        if !REGEX_OPTION_NAME.is_match(&self.name) {
+            return Err(OptionError::InvalidName(self.name.clone()));
+        }
+        if let Some(package) = &self.package {
+            if !REGEX_PACKAGE_PATTERN.is_match(package) {
+                return Err(OptionError::InvalidPackage(package.clone()));
+            }
+        }
+        Ok(())
+    }
+}
+
+impl FromStr for ConanOption {
+    type Err = OptionError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        let index = s.find('=').ok_or_else(|| OptionError::MissingValue(s.to_string()))?;
+        ConanOption::from_qualified_name(s[..index].trim(), OptionValue::parse(&s[index + 1..]))
+    }
+}
+
+impl fmt::Display for ConanOption {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if let Some(package) = &self.package {
+            write!(f, "{}:", package)?;
+        }
+        write!(f, "{}={}", self.name, self.value)
+    }
+}
@@ -0,0 +1,96 @@
+use super::*;
+use crate::util::ConanVersion;
+
+#[test]
+fn test_option_value_from_str() {
+    assert_eq!("True".parse(), Ok(OptionValue::Bool(true)));
+    assert_eq!("False".parse(), Ok(OptionValue::Bool(false)));
+    assert_eq!("true".parse(), Ok(OptionValue::String("true".to_string())));
+    assert_eq!("1024".parse(), Ok(OptionValue::Integer(1024)));
+    assert_eq!("-3".parse(), Ok(OptionValue::Integer(-3)));
+    assert_eq!("1.5".parse(), Ok(OptionValue::Float(1.5)));
+    assert_eq!("openssl".parse(), Ok(OptionValue::String("openssl".to_string())));
+    assert_eq!("inf".parse(), Ok(OptionValue::String("inf".to_string())));
+    assert_eq!("1.10".parse(), Ok(OptionValue::String("1.10".to_string())));
+    assert_eq!("007".parse(), Ok(OptionValue::String("007".to_string())));
+}
+
+#[test]
+fn test_option_value_display() {
+    assert_eq!(OptionValue::from(true).to_string(), "True");
+    assert_eq!(OptionValue::from(false).to_string(), "False");
+    assert_eq!(OptionValue::from(64).to_string(), "64");
+    assert_eq!(OptionValue::from(0.5).to_string(), "0.5");
+    assert_eq!(OptionValue::from("openssl").to_string(), "openssl");
+}
+
+#[test]
+fn test_conan_option_from_str() -> Result<(), OptionError> {
+    let option: ConanOption = "zlib:shared=True".parse()?;
+    assert_eq!(option.package.as_deref(), Some("zlib"));
+    assert_eq!(option.name, "shared");
+    assert_eq!(option.value, OptionValue::Bool(true));
+
+    let option: ConanOption = "*:shared=False".parse()?;
+    assert_eq!(option.package.as_deref(), Some("*"));
+
+    let option: ConanOption = "openssl/*:openssldir=/etc/ssl".parse()?;
+    assert_eq!(option.package.as_deref(), Some("openssl/*"));
+    assert_eq!(option.value, OptionValue::from("/etc/ssl"));
+
+    let option: ConanOption = "fPIC=True".parse()?;
+    assert_eq!(option.package, None);
+
+    assert_eq!(
+        "zlib:shared".parse::<ConanOption>(),
+        Err(OptionError::MissingValue("zlib:shared".to_string()))
+    );
+    assert_eq!(
+        "zlib:sha red=True".parse::<ConanOption>(),
+        Err(OptionError::InvalidName("sha red".to_string()))
+    );
+    assert_eq!(
+        "zl ib:shared=True".parse::<ConanOption>(),
+        Err(OptionError::InvalidPackage("zl ib".to_string()))
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_conan_option_display() -> Result<(), OptionError> {
+    let option = ConanOption::new("shared", true)?.for_package("zlib")?;
+    assert_eq!(option.to_string(), "zlib:shared=True");
+    assert_eq!(option.to_cli_string(ConanVersion::V1), "zlib:shared=True");
+    assert_eq!(option.to_cli_string(ConanVersion::V2), "zlib/*:shared=True");
+
+    let option: ConanOption = "zlib/*:shared=True".parse()?;
+    assert_eq!(option.to_cli_string(ConanVersion::V1), "zlib:shared=True");
+    assert_eq!(option.to_cli_string(ConanVersion::V2), "zlib/*:shared=True");
+
+    let option: ConanOption = "*:shared=True".parse()?;
+    assert_eq!(option.to_cli_string(ConanVersion::V1), "*:shared=True");
+    assert_eq!(option.to_cli_string(ConanVersion::V2), "*:shared=True");
+
+    let option = ConanOption::new("with_ssl", "openssl")?;
+    assert_eq!(option.to_cli_string(ConanVersion::V2), "with_ssl=openssl");
+
+    Ok(())
+}
+
+#[test]
+fn test_conan_option_matches_package() -> Result<(), OptionError> {
+    let option: ConanOption = "zlib:shared=True".parse()?;
+    assert!(option.matches_package("zlib", Some("1.3.1")));
+    assert!(!option.matches_package("zlib-ng", Some("2.1.5")));
+
+    let option: ConanOption = "zlib/1.3*:shared=True".parse()?;
+    assert!(option.matches_package("zlib", Some("1.3.1")));
+    assert!(!option.matches_package("zlib", Some("1.2.13")));
+    assert!(option.matches_package("zlib", None));
+
+    let option: ConanOption = "*:shared=True".parse()?;
+    assert!(option.matches_package("openssl", None));
+
+    Ok(())
+}
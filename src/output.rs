@@ -0,0 +1,200 @@
+#[cfg(test)]
+mod tests;
+
+use std::fmt;
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, Write};
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+
+use lazy_static::lazy_static;
+use regex::Regex;
+
+use crate::executor::{lines, CommandOutput, Invocation, OutputStream};
+
+lazy_static! {
+    static ref REGEX_DOWNLOAD_STARTED: Regex = Regex::new(r"^(\S+/\S+): Retrieving package \w+ from remote").unwrap();
+    static ref REGEX_DOWNLOAD_FINISHED: Regex = Regex::new(r"^(\S+/\S+): Downloaded package revision").unwrap();
+    static ref REGEX_BUILDING: Regex =
+        Regex::new(r"^(\S+/\S+): (Building from source|Building your package in)").unwrap();
+    static ref REGEX_WARNING: Regex = Regex::new(r"^(\S+: )?WARN(ING)?: ").unwrap();
+    static ref REGEX_ERROR: Regex = Regex::new(r"^(\S+: )?ERROR: ").unwrap();
+}
+
+/// Event parsed from a line of Conan output, or the summary once Conan exited
+///
+/// The lines of Conan 1 and 2 are recognized, any other one is passed as `Line`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ConanEvent {
+    /// Download of the binary package of a reference from a remote
+    DownloadStarted(String),
+    DownloadFinished(String),
+    /// Build of the binary package of a reference
+    BuildingFromSource(String),
+    /// Whole line of a warning
+    Warning(String),
+    /// Whole line of an error
+    Error(String),
+    Line(OutputStream, String),
+    Finished(OutputSummary),
+}
+
+impl ConanEvent {
+    /// Parse a line of output, without its line ending.
+    pub fn parse(stream: OutputStream, line: &str) -> ConanEvent {
+        let reference = |regex: &Regex| regex.captures(line).map(|captures| captures[1].to_string());
+        if let Some(reference) = reference(&REGEX_DOWNLOAD_STARTED) {
+            ConanEvent::DownloadStarted(reference)
+        } else if let Some(reference) = reference(&REGEX_DOWNLOAD_FINISHED) {
+            ConanEvent::DownloadFinished(reference)
+        } else if let Some(reference) = reference(&REGEX_BUILDING) {
+            ConanEvent::BuildingFromSource(reference)
+        } else if REGEX_ERROR.is_match(line) {
+            ConanEvent::Error(line.to_string())
+        } else if REGEX_WARNING.is_match(line) {
+            ConanEvent::Warning(line.to_string())
+        } else {
+            ConanEvent::Line(stream, line.to_string())
+        }
+    }
+}
+
+/// Outcome of a Conan invocation, gathered from its output
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct OutputSummary {
+    pub success: bool,
+    /// References whose binary package was downloaded
+    pub downloaded: Vec<String>,
+    /// References whose binary package was built from source
+    pub built: Vec<String>,
+    pub warnings: Vec<String>,
+    pub errors: Vec<String>,
+}
+
+impl OutputSummary {
+    pub fn from_output(output: &CommandOutput) -> OutputSummary {
+        let mut summary = OutputSummary {
+            success: output.status.success(),
+            ..OutputSummary::default()
+        };
+        let stdout = lines(&output.stdout)
+            .into_iter()
+            .map(|line| (OutputStream::Stdout, line));
+        let stderr = lines(&output.stderr)
+            .into_iter()
+            .map(|line| (OutputStream::Stderr, line));
+        for (stream, line) in stdout.chain(stderr) {
+            match ConanEvent::parse(stream, &line) {
+                ConanEvent::DownloadFinished(reference) => summary.downloaded.push(reference),
+                ConanEvent::BuildingFromSource(reference) => summary.built.push(reference),
+                ConanEvent::Warning(line) => summary.warnings.push(line),
+                ConanEvent::Error(line) => summary.errors.push(line),
+                _ => {}
+            }
+        }
+        summary
+    }
+}
+
+impl fmt::Display for OutputSummary {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{} package(s) downloaded, {} built from source, {} warning(s), {} error(s)",
+            self.downloaded.len(),
+            self.built.len(),
+            self.warnings.len(),
+            self.errors.len()
+        )
+    }
+}
+
+/// Output handler reporting progress with `cargo:warning`, for build scripts
+///
+/// Downloads, builds from source, errors, and the summary of the commands
+/// that downloaded or built packages, or failed, are reported.
+///
+/// ```no_run
+/// use conan::{cargo_progress, ConanContext, InstallCommandBuilder};
+///
+/// let context = ConanContext::new().with_output_handler(cargo_progress);
+/// let command = InstallCommandBuilder::new().with_context(context).build();
+/// ```
+pub fn cargo_progress(event: &ConanEvent) {
+    match event {
+        ConanEvent::DownloadStarted(reference) => println!("cargo:warning=conan: downloading {}", reference),
+        ConanEvent::BuildingFromSource(reference) => {
+            println!("cargo:warning=conan: building {} from source", reference)
+        }
+        ConanEvent::Error(line) => println!("cargo:warning=conan: {}", line),
+        ConanEvent::Finished(summary)
+            if !summary.success || !summary.downloaded.is_empty() || !summary.built.is_empty() =>
+        {
+            println!("cargo:warning=conan: {}", summary)
+        }
+        _ => {}
+    }
+}
+
+/// Handler of the events of the commands, shared by the clones of a context
+#[derive(Clone)]
+pub(crate) struct OutputHandler(pub(crate) Arc<dyn Fn(&ConanEvent) + Send + Sync>);
+
+impl fmt::Debug for OutputHandler {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("OutputHandler")
+    }
+}
+
+/// Destination of the output of an invocation: the event handler and the log file
+#[derive(Debug)]
+pub(crate) struct OutputSink {
+    handler: Option<OutputHandler>,
+    log: Option<Mutex<File>>,
+}
+
+impl OutputSink {
+    /// Sink of an invocation, appending to the log file after a header with the command line.
+    pub(crate) fn new(
+        handler: Option<OutputHandler>,
+        log_file: Option<&Path>,
+        invocation: &Invocation,
+    ) -> io::Result<OutputSink> {
+        let log = match log_file {
+            Some(path) => {
+                if let Some(parent) = path.parent() {
+                    fs::create_dir_all(parent)?;
+                }
+                let mut file = OpenOptions::new().create(true).append(true).open(path)?;
+                writeln!(file, "$ {}", invocation)?;
+                Some(Mutex::new(file))
+            }
+            None => None,
+        };
+        Ok(OutputSink { handler, log })
+    }
+
+    pub(crate) fn line(&self, stream: OutputStream, line: &str) {
+        if let Some(log) = &self.log {
+            if let Ok(mut file) = log.lock() {
+                // NOTE: A full disk must not fail the command
+                let _ = writeln!(file, "{}", line);
+            }
+        }
+        if let Some(handler) = &self.handler {
+            (handler.0)(&ConanEvent::parse(stream, line));
+        }
+    }
+
+    /// Report the exit of the invocation.
+    pub(crate) fn finish(&self, output: &CommandOutput) {
+        if let Some(log) = &self.log {
+            if let Ok(mut file) = log.lock() {
+                let _ = writeln!(file, "# {}", output.status);
+            }
+        }
+        if let Some(handler) = &self.handler {
+            (handler.0)(&ConanEvent::Finished(OutputSummary::from_output(output)));
+        }
+    }
+}
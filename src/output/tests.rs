@@ -0,0 +1,71 @@
+use super::*;
+use crate::executor::exit_status;
+
+#[test]
+fn test_parse_conan1() {
+    let parse = |line| ConanEvent::parse(OutputStream::Stdout, line);
+    assert_eq!(
+        parse("zlib/1.2.11: Retrieving package 3fb49604f9c2f729b85ba3115852006824e72cab from remote 'conancenter' "),
+        ConanEvent::DownloadStarted("zlib/1.2.11".to_string())
+    );
+    assert_eq!(
+        parse("zlib/1.2.11: Downloaded package revision 0"),
+        ConanEvent::DownloadFinished("zlib/1.2.11".to_string())
+    );
+    assert_eq!(
+        parse("openssl/1.1.1q@user/stable: Building your package in /root/.conan/data/openssl/build/abc"),
+        ConanEvent::BuildingFromSource("openssl/1.1.1q@user/stable".to_string())
+    );
+    assert_eq!(
+        parse("WARN: Remotes registry file missing, creating default one"),
+        ConanEvent::Warning("WARN: Remotes registry file missing, creating default one".to_string())
+    );
+    assert_eq!(
+        parse("Installing package: zlib/1.2.11"),
+        ConanEvent::Line(OutputStream::Stdout, "Installing package: zlib/1.2.11".to_string())
+    );
+}
+
+#[test]
+fn test_parse_conan2() {
+    let parse = |line| ConanEvent::parse(OutputStream::Stderr, line);
+    assert_eq!(
+        parse("zlib/1.3.1: Retrieving package 7bfde258ff4f62f75668d0896dbddedaa7480a0f from remote 'conancenter' "),
+        ConanEvent::DownloadStarted("zlib/1.3.1".to_string())
+    );
+    assert_eq!(
+        parse("zlib/1.3.1: Downloaded package revision b647c43bfefae3f830561ca202b6cfd935b56205"),
+        ConanEvent::DownloadFinished("zlib/1.3.1".to_string())
+    );
+    assert_eq!(
+        parse("fmt/10.2.1: Building from source"),
+        ConanEvent::BuildingFromSource("fmt/10.2.1".to_string())
+    );
+    assert_eq!(
+        parse("fmt/10.2.1: WARN: deprecated: Usage of deprecated Conan 1.X features"),
+        ConanEvent::Warning("fmt/10.2.1: WARN: deprecated: Usage of deprecated Conan 1.X features".to_string())
+    );
+    assert_eq!(
+        parse("ERROR: Package 'boost/1.0' not resolved"),
+        ConanEvent::Error("ERROR: Package 'boost/1.0' not resolved".to_string())
+    );
+}
+
+#[test]
+fn test_output_summary() {
+    let output = CommandOutput {
+        status: exit_status(1),
+        stdout: b"zlib/1.3.1: Downloaded package revision 0\r\nfmt/10.2.1: Building from source\n".to_vec(),
+        stderr: b"WARN: slow remote\nERROR: fmt/10.2.1: Error in build() method".to_vec(),
+    };
+    let summary = OutputSummary::from_output(&output);
+    assert!(!summary.success);
+    assert_eq!(summary.downloaded, ["zlib/1.3.1"]);
+    assert_eq!(summary.built, ["fmt/10.2.1"]);
+    assert_eq!(summary.warnings, ["WARN: slow remote"]);
+    assert_eq!(summary.errors, ["ERROR: fmt/10.2.1: Error in build() method"]);
+    assert_eq!(
+        summary.to_string(),
+        "1 package(s) downloaded, 1 built from source, 1 warning(s), 1 error(s)"
+    );
+}
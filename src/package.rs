@@ -1,11 +1,13 @@
 #[cfg(test)]
 mod tests;
 
-use super::util::find_program;
+use super::context::{ConanContext, ConanContextError};
+use super::home::ConanHome;
+use super::output::ConanEvent;
 use std::fs;
-use std::path::PathBuf;
-use std::process::Command;
+use std::path::{Path, PathBuf};
 use std::process::ExitStatus;
+use std::time::Duration;
 use thiserror::Error;
 
 #[derive(Debug, Error)]
@@ -30,6 +32,9 @@ pub enum ConanPackageError {
 
     #[error("Other error: {0}")]
     Other(String),
+
+    #[error(transparent)]
+    Context(#[from] ConanContextError),
 }
 
 /// Thin Wrapper around binary packages that contain libraries and headers
@@ -45,6 +50,7 @@ pub struct PackageCommand {
     package_path: Option<PathBuf>,
     source_path: Option<PathBuf>,
     recipe_path: Option<PathBuf>,
+    context: ConanContext,
 }
 
 impl Default for PackageCommandBuilder {
@@ -55,6 +61,7 @@ impl Default for PackageCommandBuilder {
             package_path: None,
             source_path: None,
             recipe_path: Some(PathBuf::from(".")),
+            context: ConanContext::default(),
         }
     }
 }
@@ -66,6 +73,7 @@ pub struct PackageCommandBuilder {
     package_path: Option<PathBuf>,
     source_path: Option<PathBuf>,
     recipe_path: Option<PathBuf>,
+    context: ConanContext,
 }
 
 impl PackageCommandBuilder {
@@ -98,6 +106,39 @@ impl PackageCommandBuilder {
         self
     }
 
+    /// Run `conan package` through a shared context, keeping the timeout, output handling and home set on this builder.
+    pub fn with_context(mut self, context: ConanContext) -> Self {
+        self.context = context.merged_with(self.context);
+        self
+    }
+
+    /// Kill Conan if it runs longer than `timeout`, failing `try_run` with `ConanContextError::Timeout`.
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.context = self.context.with_timeout(timeout);
+        self
+    }
+
+    /// Pass the events parsed from the Conan output to `handler`, e.g. `cargo_progress`.
+    pub fn with_output_handler<F>(mut self, handler: F) -> Self
+    where
+        F: Fn(&ConanEvent) + Send + Sync + 'static,
+    {
+        self.context = self.context.with_output_handler(handler);
+        self
+    }
+
+    /// Append the complete Conan output to this file instead of `OUT_DIR/conan.log`.
+    pub fn with_log_file(mut self, log_file: &Path) -> Self {
+        self.context = self.context.with_log_file(log_file);
+        self
+    }
+
+    /// Run Conan with an isolated home, e.g. `ConanHome::in_out_dir()?`.
+    pub fn with_conan_home(mut self, conan_home: ConanHome) -> Self {
+        self.context = self.context.with_home(conan_home);
+        self
+    }
+
     pub fn build(self) -> PackageCommand {
         PackageCommand {
             build_path: self.build_path,
@@ -105,6 +146,7 @@ impl PackageCommandBuilder {
             package_path: self.package_path,
             source_path: self.source_path,
             recipe_path: self.recipe_path,
+            context: self.context,
         }
     }
 }
@@ -146,11 +188,30 @@ impl PackageCommand {
         Ok(args.iter().map(|s| s.to_string()).collect())
     }
 
+    /// Run `conan package`, returning the failure to start or finish it, e.g. `ConanContextError::Timeout`.
+    pub fn try_run(&self) -> Result<ExitStatus, ConanPackageError> {
+        let args = self.args()?;
+        Ok(self.context.execute(&args)?.status)
+    }
+
     pub fn run(&self) -> Option<ExitStatus> {
-        let args = self.args().ok()?;
-        let conan_bin = find_program()?;
-        let mut command = Command::new(conan_bin);
-        Some(command.args(args).status().ok()?)
+        self.try_run().ok()
+    }
+
+    /// Async `try_run`, with `tokio::process`. Dropping the future kills Conan.
+    #[cfg(feature = "tokio")]
+    pub fn try_run_async(
+        &self,
+    ) -> impl std::future::Future<Output = Result<ExitStatus, ConanPackageError>> + Send + 'static {
+        let package = self.args().map(|args| self.context.execute_async(&args));
+        async move { Ok(package?.await?.status) }
+    }
+
+    /// Async `run`, with `tokio::process`. Dropping the future kills Conan.
+    #[cfg(feature = "tokio")]
+    pub fn run_async(&self) -> impl std::future::Future<Output = Option<ExitStatus>> + Send + 'static {
+        let package = self.try_run_async();
+        async move { package.await.ok() }
     }
 }
 
@@ -180,11 +241,7 @@ impl ConanPackage {
                     .and_then(|n| n.to_str())
                     .ok_or_else(|| ConanPackageError::InvalidFileName(lib_path.display().to_string()))?;
 
-                let lib_name = if lib_name.starts_with("lib") {
-                    &lib_name[3..]
-                } else {
-                    lib_name
-                };
+                let lib_name = lib_name.strip_prefix("lib").unwrap_or(lib_name);
 
                 if let Some(lib_suffix) = lib_path.extension().and_then(|s| s.to_str()) {
                     let lib_type = match lib_suffix {
@@ -43,3 +43,28 @@ fn test_successful_args_generation() {
         ]
     );
 }
+
+#[test]
+fn test_try_run_timeout() {
+    use super::ConanPackageError;
+    use crate::context::{ConanContext, ConanContextError};
+    use crate::executor::{ScriptedExecutor, ScriptedResponse};
+    use std::path::Path;
+    use std::time::Duration;
+
+    let executor = ScriptedExecutor::new().on(&["package"], ScriptedResponse::hanging());
+    let command = PackageCommandBuilder::new()
+        .with_context(
+            ConanContext::new()
+                .with_program(Path::new("conan"))
+                .with_executor(executor),
+        )
+        .with_timeout(Duration::from_secs(60))
+        .build();
+
+    assert!(matches!(
+        command.try_run(),
+        Err(ConanPackageError::Context(ConanContextError::Timeout(_)))
+    ));
+    assert_eq!(command.run(), None);
+}
@@ -0,0 +1,239 @@
+#[cfg(test)]
+mod tests;
+
+use std::fmt;
+use std::str::FromStr;
+
+use lazy_static::lazy_static;
+use regex::Regex;
+use thiserror::Error;
+
+use crate::util::ConanVersion;
+
+lazy_static! {
+    static ref REGEX_NAME: Regex = Regex::new(r"^[a-zA-Z0-9_][a-zA-Z0-9_+.-]{1,100}$").unwrap();
+    static ref REGEX_VERSION: Regex = Regex::new(r"^(?:[a-zA-Z0-9_][a-zA-Z0-9_+.-]{0,100}|\[[^\[\]@#:]+\])$").unwrap();
+    static ref REGEX_REVISION: Regex = Regex::new(r"^[a-zA-Z0-9]{1,64}$").unwrap();
+}
+
+#[derive(Debug, Error, Clone, PartialEq)]
+pub enum ReferenceError {
+    #[error("Missing version in reference \"{0}\"")]
+    MissingVersion(String),
+    #[error("Invalid package name \"{0}\"")]
+    InvalidName(String),
+    #[error("Invalid package version \"{0}\"")]
+    InvalidVersion(String),
+    #[error("Invalid user \"{0}\"")]
+    InvalidUser(String),
+    #[error("Invalid channel \"{0}\"")]
+    InvalidChannel(String),
+    #[error("Invalid revision \"{0}\"")]
+    InvalidRevision(String),
+    #[error("Missing package ID in reference \"{0}\"")]
+    MissingPackageId(String),
+    #[error("Invalid package ID \"{0}\"")]
+    InvalidPackageId(String),
+}
+
+/// Recipe reference: `name/version[@user[/channel]][#revision]`
+///
+/// Conan 1 references without user and channel are written with a trailing `@` (`zlib/1.2.13@`),
+/// which is accepted when parsing and added back by [`ConanReference::to_cli_string`].
+#[derive(Clone, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct ConanReference {
+    pub name: String,
+    pub version: String,
+    pub user: Option<String>,
+    pub channel: Option<String>,
+    pub revision: Option<String>,
+}
+
+impl ConanReference {
+    pub fn new(name: &str, version: &str) -> Result<Self, ReferenceError> {
+        let reference = ConanReference {
+            name: name.to_string(),
+            version: version.to_string(),
+            user: None,
+            channel: None,
+            revision: None,
+        };
+        reference.validate()?;
+        Ok(reference)
+    }
+
+    pub fn with_user_channel(mut self, user: &str, channel: &str) -> Result<Self, ReferenceError> {
+        self.user = Some(user.to_string());
+        self.channel = Some(channel.to_string());
+        self.validate()?;
+        Ok(self)
+    }
+
+    pub fn with_revision(mut self, revision: &str) -> Result<Self, ReferenceError> {
+        self.revision = Some(revision.to_string());
+        self.validate()?;
+        Ok(self)
+    }
+
+    /// Same reference without the recipe revision.
+    pub fn without_revision(&self) -> Self {
+        ConanReference {
+            revision: None,
+            ..self.clone()
+        }
+    }
+
+    /// Format the reference for the command line of the given Conan version.
+    ///
+    /// Conan 1 needs a trailing `@` to tell a reference without user and channel from a path.
+    pub fn to_cli_string(&self, conan_version: ConanVersion) -> String {
+        let mut reference = self.to_string();
+        if conan_version == ConanVersion::V1 && self.user.is_none() {
+            match reference.find('#') {
+                Some(index) => reference.insert(index, '@'),
+                None => reference.push('@'),
+            }
+        }
+        reference
+    }
+
+    fn validate(&self) -> Result<(), ReferenceError> {
+        if !REGEX_NAME.is_match(&self.name) {
+            return Err(ReferenceError::InvalidName(self.name.clone()));
+        }
+        if !REGEX_VERSION.is_match(&self.version) {
+            return Err(ReferenceError::InvalidVersion(self.version.clone()));
+        }
+        if let Some(user) = &self.user {
+            if !REGEX_NAME.is_match(user) {
+                return Err(ReferenceError::InvalidUser(user.clone()));
+            }
+        }
+        match (&self.user, &self.channel) {
+            (_, Some(channel)) if !REGEX_NAME.is_match(channel) => {
+                return Err(ReferenceError::InvalidChannel(channel.clone()));
+            }
+            (None, Some(channel)) => return Err(ReferenceError::InvalidChannel(channel.clone())),
+            _ => {}
+        }
+        if let Some(revision) = &self.revision {
+            if !REGEX_REVISION.is_match(revision) {
+                return Err(ReferenceError::InvalidRevision(revision.clone()));
+            }
+        }
+        Ok(())
+    }
+}
+
+impl FromStr for ConanReference {
+    type Err = ReferenceError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        let (s, revision) = match s.find('#') {
+            Some(index) => (&s[..index], Some(s[index + 1..].to_string())),
+            None => (s, None),
+        };
+        let (name_version, user_channel) = match s.find('@') {
+            Some(index) => (&s[..index], &s[index + 1..]),
+            None => (s, ""),
+        };
+
+        let mut name_version = name_version.splitn(2, '/');
+        let name = name_version.next().unwrap_or_default();
+        let version = name_version
+            .next()
+            .ok_or_else(|| ReferenceError::MissingVersion(s.to_string()))?;
+
+        // NOTE: Conan 2 accepts a user without a channel, Conan 1 "name/version@" means neither
+        let mut user_channel = user_channel.splitn(2, '/');
+        let user = user_channel.next().filter(|x| !x.is_empty()).map(str::to_string);
+        let channel = user_channel.next().map(str::to_string);
+
+        let reference = ConanReference {
+            name: name.to_string(),
+            version: version.to_string(),
+            user,
+            channel,
+            revision,
+        };
+        reference.validate()?;
+        Ok(reference)
+    }
+}
+
+impl fmt::Display for ConanReference {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}/{}", self.name, self.version)?;
+        if let Some(user) = &self.user {
+            write!(f, "@{}", user)?;
+            if let Some(channel) = &self.channel {
+                write!(f, "/{}", channel)?;
+            }
+        }
+        if let Some(revision) = &self.revision {
+            write!(f, "#{}", revision)?;
+        }
+        Ok(())
+    }
+}
+
+/// Binary package reference: `name/version[@user[/channel]][#revision]:package_id[#package_revision]`
+#[derive(Clone, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct PackageReference {
+    pub reference: ConanReference,
+    pub package_id: String,
+    pub revision: Option<String>,
+}
+
+impl PackageReference {
+    pub fn new(reference: ConanReference, package_id: &str) -> Result<Self, ReferenceError> {
+        if !REGEX_REVISION.is_match(package_id) {
+            return Err(ReferenceError::InvalidPackageId(package_id.to_string()));
+        }
+        Ok(PackageReference {
+            reference,
+            package_id: package_id.to_string(),
+            revision: None,
+        })
+    }
+}
+
+impl FromStr for PackageReference {
+    type Err = ReferenceError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        let index = s
+            .find(':')
+            .ok_or_else(|| ReferenceError::MissingPackageId(s.to_string()))?;
+        let reference = s[..index].parse::<ConanReference>()?;
+
+        let (package_id, revision) = match s[index + 1..].find('#') {
+            Some(rev_index) => {
+                let (package_id, revision) = s[index + 1..].split_at(rev_index);
+                (package_id, Some(revision[1..].to_string()))
+            }
+            None => (&s[index + 1..], None),
+        };
+
+        let mut package_reference = PackageReference::new(reference, package_id)?;
+        if let Some(revision) = revision {
+            if !REGEX_REVISION.is_match(&revision) {
+                return Err(ReferenceError::InvalidRevision(revision));
+            }
+            package_reference.revision = Some(revision);
+        }
+        Ok(package_reference)
+    }
+}
+
+impl fmt::Display for PackageReference {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}:{}", self.reference, self.package_id)?;
+        if let Some(revision) = &self.revision {
+            write!(f, "#{}", revision)?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,101 @@
+use super::*;
+
+#[test]
+fn test_parse_reference() {
+    let reference: ConanReference = "openssl/1.1.1l@devolutions/stable".parse().unwrap();
+    assert_eq!(reference.name, "openssl");
+    assert_eq!(reference.version, "1.1.1l");
+    assert_eq!(reference.user.as_deref(), Some("devolutions"));
+    assert_eq!(reference.channel.as_deref(), Some("stable"));
+    assert_eq!(reference.revision, None);
+    assert_eq!(reference.to_string(), "openssl/1.1.1l@devolutions/stable");
+
+    let reference: ConanReference = "zlib/1.2.13#97d5730b529b4224045fe7090592d4c1".parse().unwrap();
+    assert_eq!(reference.user, None);
+    assert_eq!(reference.revision.as_deref(), Some("97d5730b529b4224045fe7090592d4c1"));
+    assert_eq!(reference.to_string(), "zlib/1.2.13#97d5730b529b4224045fe7090592d4c1");
+
+    // Conan 1 trailing "@" and Conan 2 user without channel
+    let reference: ConanReference = "zlib/1.2.13@".parse().unwrap();
+    assert_eq!(reference, ConanReference::new("zlib", "1.2.13").unwrap());
+    let reference: ConanReference = "bridge/0.1.0@devolutions".parse().unwrap();
+    assert_eq!(reference.user.as_deref(), Some("devolutions"));
+    assert_eq!(reference.channel, None);
+
+    let reference: ConanReference = "boost/[>=1.70 <1.80]".parse().unwrap();
+    assert_eq!(reference.version, "[>=1.70 <1.80]");
+}
+
+#[test]
+fn test_parse_invalid_reference() {
+    assert_eq!(
+        "zlib".parse::<ConanReference>(),
+        Err(ReferenceError::MissingVersion("zlib".to_string()))
+    );
+    assert_eq!(
+        "z/1.0".parse::<ConanReference>(),
+        Err(ReferenceError::InvalidName("z".to_string()))
+    );
+    assert_eq!(
+        "zlib/1.0 beta".parse::<ConanReference>(),
+        Err(ReferenceError::InvalidVersion("1.0 beta".to_string()))
+    );
+    assert_eq!(
+        "zlib/1.0@user/chan nel".parse::<ConanReference>(),
+        Err(ReferenceError::InvalidChannel("chan nel".to_string()))
+    );
+    assert_eq!(
+        "zlib/1.0#not-a-revision".parse::<ConanReference>(),
+        Err(ReferenceError::InvalidRevision("not-a-revision".to_string()))
+    );
+    assert!(ConanReference::new(&"a".repeat(102), "1.0").is_err());
+}
+
+#[test]
+fn test_reference_cli_string() {
+    let reference = ConanReference::new("zlib", "1.2.13").unwrap();
+    assert_eq!(reference.to_cli_string(ConanVersion::V1), "zlib/1.2.13@");
+    assert_eq!(reference.to_cli_string(ConanVersion::V2), "zlib/1.2.13");
+
+    let reference = reference.with_revision("97d5730b529b4224045fe7090592d4c1").unwrap();
+    assert_eq!(
+        reference.to_cli_string(ConanVersion::V1),
+        "zlib/1.2.13@#97d5730b529b4224045fe7090592d4c1"
+    );
+
+    let reference = ConanReference::new("openssl", "3.2.0")
+        .unwrap()
+        .with_user_channel("devolutions", "stable")
+        .unwrap();
+    assert_eq!(
+        reference.to_cli_string(ConanVersion::V1),
+        "openssl/3.2.0@devolutions/stable"
+    );
+}
+
+#[test]
+fn test_parse_package_reference() {
+    let package: PackageReference =
+        "zlib/1.2.13#97d5730b529b4224045fe7090592d4c1:6af9cc7cb931c5ad942174fd7838eb655717c709#2ba9c0a6b1f1b4b1a8c7e4b0d2b8c4a1"
+            .parse()
+            .unwrap();
+    assert_eq!(package.reference.name, "zlib");
+    assert_eq!(package.package_id, "6af9cc7cb931c5ad942174fd7838eb655717c709");
+    assert_eq!(package.revision.as_deref(), Some("2ba9c0a6b1f1b4b1a8c7e4b0d2b8c4a1"));
+    assert_eq!(
+        package.to_string(),
+        "zlib/1.2.13#97d5730b529b4224045fe7090592d4c1:6af9cc7cb931c5ad942174fd7838eb655717c709#2ba9c0a6b1f1b4b1a8c7e4b0d2b8c4a1"
+    );
+
+    let package: PackageReference = "bridge/0.1.0@devolutions/stable:3fb49604f9c2f729b85ba3115852006824e72cab"
+        .parse()
+        .unwrap();
+    assert_eq!(package.reference.to_string(), "bridge/0.1.0@devolutions/stable");
+    assert_eq!(package.revision, None);
+
+    assert_eq!(
+        "zlib/1.2.13".parse::<PackageReference>(),
+        Err(ReferenceError::MissingPackageId("zlib/1.2.13".to_string()))
+    );
+    assert!("zlib/1.2.13:not a package".parse::<PackageReference>().is_err());
+}
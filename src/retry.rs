@@ -0,0 +1,161 @@
+#[cfg(test)]
+mod tests;
+
+use std::fmt;
+use std::sync::Arc;
+use std::time::Duration;
+
+use lazy_static::lazy_static;
+use regex::Regex;
+
+use crate::executor::CommandOutput;
+
+lazy_static! {
+    static ref REGEX_COMPILATION: Regex = Regex::new(
+        r"(?i)(error \d+ while executing|cmake error|compilation terminated|ninja: build stopped|make(\[\d+\])?: \*\*\*|\berror (C\d+|LNK\d+)|(?-i:: (fatal )?error: ))"
+    )
+    .unwrap();
+    static ref REGEX_NETWORK: Regex = Regex::new(
+        r"(?i)(connection (reset|refused|aborted)|connectionerror|connecttimeout|read timed out|max retries exceeded|temporary failure in name resolution|name or service not known|failed to establish a new connection|remote end closed connection|remotedisconnected|incompleteread|chunkedencodingerror|bad gateway|service unavailable|gateway time-?out|\b50[234] server error)"
+    )
+    .unwrap();
+    static ref REGEX_AUTHENTICATION: Regex =
+        Regex::new(r"(?i)(\b40[13]\b|unauthorized|forbidden|wrong user or password|authentication)").unwrap();
+    static ref REGEX_RECIPE: Regex = Regex::new(
+        r"(?i)(unable to find|not found in remote|missing prebuilt package|missing binar(y|ies)|invalid configuration|invalid setting|invalid option|conflict|error loading conanfile|error in \w+\(\) method|syntaxerror)"
+    )
+    .unwrap();
+}
+
+/// Class of a failed Conan command, guessed from its output
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum FailureKind {
+    /// Connection reset or refused, DNS failure, or 502, 503 and 504 from the remote
+    Network,
+    /// Killed once the timeout of the command expired
+    Timeout,
+    /// Rejected credentials or permissions on the remote
+    Authentication,
+    /// Missing package, invalid setting or option, or broken recipe
+    Recipe,
+    /// Failed build of a package
+    Compilation,
+    Other,
+}
+
+impl FailureKind {
+    /// Classify the output of a failed command.
+    pub fn classify(output: &CommandOutput) -> FailureKind {
+        let mut log = String::from_utf8_lossy(&output.stdout).into_owned();
+        log.push_str(&String::from_utf8_lossy(&output.stderr));
+        FailureKind::classify_log(&log)
+    }
+
+    /// Classify the log of a failed command.
+    ///
+    /// A compilation error wins over a network one, e.g. a package build that downloads.
+    pub fn classify_log(log: &str) -> FailureKind {
+        if REGEX_COMPILATION.is_match(log) {
+            FailureKind::Compilation
+        } else if REGEX_NETWORK.is_match(log) {
+            FailureKind::Network
+        } else if REGEX_AUTHENTICATION.is_match(log) {
+            FailureKind::Authentication
+        } else if REGEX_RECIPE.is_match(log) {
+            FailureKind::Recipe
+        } else {
+            FailureKind::Other
+        }
+    }
+}
+
+impl fmt::Display for FailureKind {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let kind = match self {
+            FailureKind::Network => "network",
+            FailureKind::Timeout => "timeout",
+            FailureKind::Authentication => "authentication",
+            FailureKind::Recipe => "recipe",
+            FailureKind::Compilation => "compilation",
+            FailureKind::Other => "unknown",
+        };
+        f.write_str(kind)
+    }
+}
+
+/// When to run a failed command again
+///
+/// By default only network failures are retried, after a delay of 2 seconds doubled
+/// for each retry, up to a minute. Each retry is reported with `cargo:warning`.
+///
+/// ```
+/// use conan::{FailureKind, InstallCommandBuilder, RetryPolicy};
+/// use std::time::Duration;
+///
+/// let retry = RetryPolicy::new(3)
+///     .with_backoff(Duration::from_secs(5), Duration::from_secs(30))
+///     .retry_if(|kind| kind == FailureKind::Network || kind == FailureKind::Timeout);
+/// let command = InstallCommandBuilder::new().with_retry(retry).build();
+/// ```
+#[derive(Clone)]
+pub struct RetryPolicy {
+    max_attempts: u32,
+    backoff: Duration,
+    max_backoff: Duration,
+    predicate: Arc<dyn Fn(FailureKind) -> bool + Send + Sync>,
+}
+
+impl RetryPolicy {
+    /// Run the command at most `max_attempts` times in total.
+    pub fn new(max_attempts: u32) -> Self {
+        RetryPolicy {
+            max_attempts,
+            backoff: Duration::from_secs(2),
+            max_backoff: Duration::from_secs(60),
+            predicate: Arc::new(|kind| kind == FailureKind::Network),
+        }
+    }
+
+    /// Wait `backoff` before the first retry, doubled for each retry up to `max_backoff`.
+    pub fn with_backoff(mut self, backoff: Duration, max_backoff: Duration) -> Self {
+        self.backoff = backoff;
+        self.max_backoff = max_backoff;
+        self
+    }
+
+    /// Retry the failures of the kinds accepted by `predicate`, instead of network ones only.
+    pub fn retry_if<F>(mut self, predicate: F) -> Self
+    where
+        F: Fn(FailureKind) -> bool + Send + Sync + 'static,
+    {
+        self.predicate = Arc::new(predicate);
+        self
+    }
+
+    pub fn max_attempts(&self) -> u32 {
+        self.max_attempts
+    }
+
+    /// Whether to run the command again after a failed `attempt`, counted from 1.
+    pub fn should_retry(&self, attempt: u32, kind: FailureKind) -> bool {
+        attempt < self.max_attempts && (self.predicate)(kind)
+    }
+
+    /// Delay after a failed `attempt`, counted from 1.
+    pub fn backoff(&self, attempt: u32) -> Duration {
+        let factor = 2u32.saturating_pow(attempt.saturating_sub(1));
+        self.backoff
+            .checked_mul(factor)
+            .map_or(self.max_backoff, |backoff| backoff.min(self.max_backoff))
+    }
+}
+
+impl fmt::Debug for RetryPolicy {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("RetryPolicy")
+            .field("max_attempts", &self.max_attempts)
+            .field("backoff", &self.backoff)
+            .field("max_backoff", &self.max_backoff)
+            .finish()
+    }
+}
@@ -0,0 +1,57 @@
+use super::*;
+
+#[test]
+fn test_failure_kind_classify_log() {
+    let network = [
+        "ERROR: HTTPSConnectionPool(host='center.conan.io', port=443): Max retries exceeded with url: /v1/ping",
+        "ERROR: ('Connection aborted.', ConnectionResetError(104, 'Connection reset by peer'))",
+        "ERROR: 502 Server Error: Bad Gateway for url: https://artifactory.example.com/api/conan/v1/ping",
+        "ERROR: Temporary failure in name resolution",
+        // NOTE: Conan prefixes errors with the reference, which must not pass for a compiler error
+        "zlib/1.2.13: ERROR: Exception while getting package: ConnectionError: Read timed out.",
+    ];
+    for log in network.iter() {
+        assert_eq!(FailureKind::classify_log(log), FailureKind::Network, "{}", log);
+    }
+
+    assert_eq!(
+        FailureKind::classify_log("ERROR: Unable to find 'zlib/1.2.99' in remotes"),
+        FailureKind::Recipe
+    );
+    assert_eq!(
+        FailureKind::classify_log("ERROR: Missing prebuilt package for 'openssl/3.2.0'"),
+        FailureKind::Recipe
+    );
+    assert_eq!(
+        FailureKind::classify_log("ERROR: 401: Unauthorized. [Remote: artifactory]"),
+        FailureKind::Authentication
+    );
+    assert_eq!(
+        FailureKind::classify_log(
+            "Downloading conan_sources.tgz\nsrc/zlib.c:12:1: error: expected ';'\nConanException: Error 2 while executing make"
+        ),
+        FailureKind::Compilation
+    );
+    assert_eq!(FailureKind::classify_log("ERROR: something else"), FailureKind::Other);
+}
+
+#[test]
+fn test_retry_policy() {
+    let retry = RetryPolicy::new(3);
+    assert!(retry.should_retry(1, FailureKind::Network));
+    assert!(retry.should_retry(2, FailureKind::Network));
+    assert!(!retry.should_retry(3, FailureKind::Network));
+    assert!(!retry.should_retry(1, FailureKind::Recipe));
+    assert!(!retry.should_retry(1, FailureKind::Timeout));
+
+    let retry = retry
+        .with_backoff(Duration::from_secs(1), Duration::from_secs(5))
+        .retry_if(|kind| kind == FailureKind::Timeout);
+    assert!(retry.should_retry(1, FailureKind::Timeout));
+    assert!(!retry.should_retry(1, FailureKind::Network));
+    assert_eq!(retry.backoff(1), Duration::from_secs(1));
+    assert_eq!(retry.backoff(2), Duration::from_secs(2));
+    assert_eq!(retry.backoff(3), Duration::from_secs(4));
+    assert_eq!(retry.backoff(4), Duration::from_secs(5));
+    assert_eq!(retry.backoff(100), Duration::from_secs(5));
+}
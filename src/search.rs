@@ -0,0 +1,379 @@
+#[cfg(test)]
+mod tests;
+
+use std::collections::BTreeMap;
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::ExitStatus;
+use std::time::Duration;
+
+use serde_json::Value;
+use thiserror::Error;
+
+use crate::context::{ConanContext, ConanContextError};
+use crate::home::ConanHome;
+use crate::install::build_info::BuildSettings;
+use crate::reference::{ConanReference, ReferenceError};
+use crate::util::ConanVersion;
+
+#[derive(Debug, Error)]
+pub enum ConanSearchError {
+    #[error("Conan binary not found")]
+    ConanNotFound,
+    #[error("Invalid Unicode in path")]
+    InvalidUnicodeInPath,
+    #[error("Output directory not found")]
+    OutputDirNotFound,
+    #[error("Conan search failed with {0}")]
+    CommandFailed(ExitStatus),
+    #[error("Search failed on {remote}: {message}")]
+    RemoteError { remote: String, message: String },
+    #[error("I/O error: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("Failed to parse Conan JSON output: {0}")]
+    Json(#[from] serde_json::Error),
+    #[error("Unexpected Conan JSON output: {0}")]
+    InvalidOutput(String),
+    #[error(transparent)]
+    InvalidReference(#[from] ReferenceError),
+    #[error(transparent)]
+    Context(#[from] ConanContextError),
+}
+
+/// Binary package of a recipe, as reported by "conan search" or "conan list"
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct PackageInfo {
+    pub package_id: String,
+    pub settings: BTreeMap<String, String>,
+    pub options: BTreeMap<String, String>,
+    pub requires: Vec<String>,
+}
+
+impl PackageInfo {
+    fn from_json(package_id: &str, info: &Value) -> Self {
+        PackageInfo {
+            package_id: package_id.to_string(),
+            settings: string_map(&info["settings"]),
+            options: string_map(&info["options"]),
+            requires: info["requires"]
+                .as_array()
+                .map(|requires| requires.iter().filter_map(|x| x.as_str()).map(str::to_string).collect())
+                .unwrap_or_default(),
+        }
+    }
+
+    /// Whether the binary was built with the given settings.
+    ///
+    /// Settings the package does not declare (e.g. the compiler of a header-only library) are ignored.
+    // NOTE: `Option::is_none_or` needs Rust 1.82
+    #[allow(clippy::unnecessary_map_or)]
+    pub fn matches_settings(&self, build_settings: &BuildSettings) -> bool {
+        build_settings
+            .args()
+            .iter()
+            .filter(|x| x.as_str() != "-s")
+            .filter_map(|x| {
+                let mut setting = x.splitn(2, '=');
+                Some((setting.next()?, setting.next()?))
+            })
+            .all(|(name, value)| self.settings.get(name).map_or(true, |x| x == value))
+    }
+}
+
+/// Recipe found by "conan search" or "conan list", with the binary packages that were listed
+#[derive(Clone, Debug, PartialEq)]
+pub struct RecipeReference {
+    pub reference: ConanReference,
+    /// Remote the recipe was found on, `None` for the local cache
+    pub remote: Option<String>,
+    pub packages: Vec<PackageInfo>,
+}
+
+/// Results of "conan search" (Conan 1) or "conan list" (Conan 2)
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct SearchResults {
+    pub recipes: Vec<RecipeReference>,
+}
+
+impl SearchResults {
+    /// Parse the file written by `conan search --json` (Conan 1).
+    pub fn from_v1_json(json: &str) -> Result<Self, ConanSearchError> {
+        let value: Value = serde_json::from_str(json)?;
+        let results = value["results"]
+            .as_array()
+            .ok_or_else(|| ConanSearchError::InvalidOutput("missing \"results\" list".to_string()))?;
+
+        let mut search_results = SearchResults::default();
+        for result in results {
+            let remote = result["remote"].as_str().map(str::to_string);
+            for item in result["items"].as_array().into_iter().flatten() {
+                let reference = item["recipe"]["id"]
+                    .as_str()
+                    .ok_or_else(|| ConanSearchError::InvalidOutput("missing recipe id".to_string()))?;
+                let packages = item["packages"]
+                    .as_array()
+                    .into_iter()
+                    .flatten()
+                    .filter_map(|x| Some(PackageInfo::from_json(x["id"].as_str()?, x)))
+                    .collect();
+
+                search_results.recipes.push(RecipeReference {
+                    reference: reference.parse()?,
+                    remote: remote.clone(),
+                    packages,
+                });
+            }
+        }
+
+        Ok(search_results)
+    }
+
+    /// Parse the package list printed by `conan list --format=json` (Conan 2).
+    // NOTE: `Option::is_none_or` needs Rust 1.82
+    #[allow(clippy::unnecessary_map_or)]
+    pub fn from_v2_json(json: &str) -> Result<Self, ConanSearchError> {
+        let value: Value = serde_json::from_str(json)?;
+        let origins = value
+            .as_object()
+            .ok_or_else(|| ConanSearchError::InvalidOutput("expected a package list".to_string()))?;
+
+        let mut search_results = SearchResults::default();
+        for (origin, references) in origins {
+            let remote = match origin.as_str() {
+                "Local Cache" => None,
+                remote => Some(remote.to_string()),
+            };
+
+            if let Some(message) = references["error"].as_str() {
+                return Err(ConanSearchError::RemoteError {
+                    remote: origin.clone(),
+                    message: message.to_string(),
+                });
+            }
+
+            for (reference, recipe) in references.as_object().into_iter().flatten() {
+                let revisions = recipe["revisions"].as_object();
+                if revisions.map_or(true, |x| x.is_empty()) {
+                    search_results.recipes.push(RecipeReference {
+                        reference: reference.parse()?,
+                        remote: remote.clone(),
+                        packages: Vec::new(),
+                    });
+                    continue;
+                }
+
+                for (revision, recipe) in revisions.into_iter().flatten() {
+                    let packages = recipe["packages"]
+                        .as_object()
+                        .into_iter()
+                        .flatten()
+                        .map(|(package_id, package)| PackageInfo::from_json(package_id, &package["info"]))
+                        .collect();
+
+                    search_results.recipes.push(RecipeReference {
+                        reference: format!("{}#{}", reference, revision).parse()?,
+                        remote: remote.clone(),
+                        packages,
+                    });
+                }
+            }
+        }
+
+        Ok(search_results)
+    }
+
+    /// First binary package built with the given settings.
+    pub fn find_binary(&self, build_settings: &BuildSettings) -> Option<(&RecipeReference, &PackageInfo)> {
+        self.recipes.iter().find_map(|recipe| {
+            recipe
+                .packages
+                .iter()
+                .find(|x| x.matches_settings(build_settings))
+                .map(|package| (recipe, package))
+        })
+    }
+}
+
+fn string_map(value: &Value) -> BTreeMap<String, String> {
+    value
+        .as_object()
+        .into_iter()
+        .flatten()
+        .map(|(key, value)| {
+            let value = match value {
+                Value::String(value) => value.clone(),
+                value => value.to_string(),
+            };
+            (key.clone(), value)
+        })
+        .collect()
+}
+
+/// "conan search" (Conan 1) or "conan list" (Conan 2) command runner
+pub struct SearchCommand<'a> {
+    pattern: &'a str,
+    remote: Option<&'a str>,
+    packages: bool,
+    query: Option<&'a str>,
+    output_dir: Option<PathBuf>,
+    context: ConanContext,
+}
+
+/// "conan search" / "conan list" command arguments builder
+pub struct SearchCommandBuilder<'a> {
+    pattern: &'a str,
+    remote: Option<&'a str>,
+    packages: bool,
+    query: Option<&'a str>,
+    output_dir: Option<PathBuf>,
+    context: ConanContext,
+}
+
+impl<'a> SearchCommandBuilder<'a> {
+    /// Search the recipes matching a reference or pattern, e.g. `zlib/*`.
+    pub fn new(pattern: &'a str) -> SearchCommandBuilder<'a> {
+        SearchCommandBuilder {
+            pattern,
+            remote: None,
+            packages: false,
+            query: None,
+            output_dir: None,
+            context: ConanContext::default(),
+        }
+    }
+
+    /// Search a remote instead of the local cache.
+    pub fn with_remote(mut self, remote: &'a str) -> Self {
+        self.remote = Some(remote);
+        self
+    }
+
+    /// List the binary packages of the matching recipes.
+    ///
+    /// Conan 1 only lists binaries when the pattern is a full reference.
+    pub fn with_packages(mut self) -> Self {
+        self.packages = true;
+        self
+    }
+
+    /// Filter the binary packages, e.g. `os=Linux AND arch=x86_64`.
+    pub fn with_query(mut self, query: &'a str) -> Self {
+        self.query = Some(query);
+        self
+    }
+
+    /// Directory receiving the Conan 1 JSON report, defaults to `OUT_DIR`.
+    pub fn output_dir(mut self, output_dir: &Path) -> Self {
+        self.output_dir = Some(output_dir.to_path_buf());
+        self
+    }
+
+    /// Run `conan search` through a shared context, keeping the timeout, home and version set on this builder.
+    pub fn with_context(mut self, context: ConanContext) -> Self {
+        self.context = context.merged_with(self.context);
+        self
+    }
+
+    /// Kill Conan and fail with a timeout error if it runs longer than `timeout`.
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.context = self.context.with_timeout(timeout);
+        self
+    }
+
+    /// Run Conan with an isolated home, e.g. `ConanHome::in_out_dir()?`.
+    pub fn with_conan_home(mut self, conan_home: ConanHome) -> Self {
+        self.context = self.context.with_home(conan_home);
+        self
+    }
+
+    pub fn conan_version(mut self, conan_version: ConanVersion) -> Self {
+        self.context = self.context.with_conan_version(conan_version);
+        self
+    }
+
+    pub fn build(self) -> SearchCommand<'a> {
+        SearchCommand {
+            pattern: self.pattern,
+            remote: self.remote,
+            packages: self.packages,
+            query: self.query,
+            output_dir: self.output_dir,
+            context: self.context,
+        }
+    }
+}
+
+impl<'a> SearchCommand<'a> {
+    pub fn args(&self) -> Result<Vec<String>, ConanSearchError> {
+        let conan_version = self.context.conan_version();
+        let mut args: Vec<String> = Vec::new();
+
+        match conan_version {
+            ConanVersion::V1 => {
+                args.extend(vec!["search".to_string(), self.pattern.to_string()]);
+                if let Some(query) = self.query {
+                    args.extend(vec!["-q".to_string(), query.to_string()]);
+                }
+            }
+            ConanVersion::V2 => {
+                args.push("list".to_string());
+                if self.packages || self.query.is_some() {
+                    args.push(format!("{}:*", self.pattern));
+                } else {
+                    args.push(self.pattern.to_string());
+                }
+                if let Some(query) = self.query {
+                    args.extend(vec!["-p".to_string(), query.to_string()]);
+                }
+            }
+        }
+
+        if let Some(remote) = self.remote {
+            args.extend(vec!["-r".to_string(), remote.to_string()]);
+        }
+
+        match conan_version {
+            ConanVersion::V1 => {
+                let output_file = self.output_file().ok_or(ConanSearchError::OutputDirNotFound)?;
+                let output_file = output_file.to_str().ok_or(ConanSearchError::InvalidUnicodeInPath)?;
+                args.extend(vec!["--json".to_string(), output_file.to_string()]);
+            }
+            ConanVersion::V2 => args.push("--format=json".to_string()),
+        }
+
+        Ok(args)
+    }
+
+    pub fn output_dir(&self) -> Option<PathBuf> {
+        self.output_dir
+            .clone()
+            .or_else(|| env::var("OUT_DIR").ok().map(PathBuf::from))
+            .or_else(|| env::current_dir().ok())
+    }
+
+    /// JSON report written by Conan 1.
+    pub fn output_file(&self) -> Option<PathBuf> {
+        let mut output_file = self.output_dir()?;
+        output_file.push("conan_search.json");
+        Some(output_file)
+    }
+
+    pub fn run(&self) -> Result<SearchResults, ConanSearchError> {
+        let conan_version = self.context.conan_version();
+        let args = self.args()?;
+        let output = self.context.execute(&args)?;
+        if !output.status.success() {
+            return Err(ConanSearchError::CommandFailed(output.status));
+        }
+
+        match conan_version {
+            ConanVersion::V1 => {
+                let output_file = self.output_file().ok_or(ConanSearchError::OutputDirNotFound)?;
+                SearchResults::from_v1_json(&fs::read_to_string(output_file)?)
+            }
+            // NOTE: Conan 2 prints the JSON package list on stdout and its logs on stderr
+            ConanVersion::V2 => SearchResults::from_v2_json(&String::from_utf8_lossy(&output.stdout)),
+        }
+    }
+}
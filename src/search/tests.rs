@@ -0,0 +1,93 @@
+use super::*;
+use crate::install::build_info::build_settings::BuildType;
+
+#[test]
+fn test_search_args() {
+    let command = SearchCommandBuilder::new("zlib/*")
+        .with_remote("conancenter")
+        .with_query("os=Linux")
+        .output_dir(Path::new("out"))
+        .conan_version(ConanVersion::V1)
+        .build();
+
+    let output_file = command.output_file().unwrap();
+    assert_eq!(
+        command.args().unwrap(),
+        [
+            "search",
+            "zlib/*",
+            "-q",
+            "os=Linux",
+            "-r",
+            "conancenter",
+            "--json",
+            output_file.to_str().unwrap()
+        ]
+    );
+
+    let command = SearchCommandBuilder::new("zlib/*")
+        .with_remote("conancenter")
+        .with_packages()
+        .conan_version(ConanVersion::V2)
+        .build();
+
+    assert_eq!(
+        command.args().unwrap(),
+        ["list", "zlib/*:*", "-r", "conancenter", "--format=json"]
+    );
+}
+
+#[test]
+fn test_search_results_v1() {
+    let results = SearchResults::from_v1_json(include_str!("../../test/conan_search_v1.json")).unwrap();
+    assert_eq!(results.recipes.len(), 1);
+
+    let zlib = &results.recipes[0];
+    assert_eq!(zlib.reference.to_string(), "zlib/1.2.13");
+    assert_eq!(zlib.remote.as_deref(), Some("conancenter"));
+    assert_eq!(zlib.packages.len(), 2);
+    assert_eq!(zlib.packages[0].options["shared"], "False");
+    assert_eq!(zlib.packages[1].settings["compiler"], "Visual Studio");
+
+    let settings = BuildSettings::new()
+        .os("Linux".to_string())
+        .arch("x86_64".to_string())
+        .build_type(BuildType::Release);
+    let (recipe, package) = results.find_binary(&settings).unwrap();
+    assert_eq!(recipe.reference.to_string(), "zlib/1.2.13");
+    assert_eq!(package.package_id, "6af9cc7cb931c5ad942174fd7838eb655717c709");
+
+    let settings = BuildSettings::new()
+        .os("Macos".to_string())
+        .build_type(BuildType::Release);
+    assert!(results.find_binary(&settings).is_none());
+}
+
+#[test]
+fn test_search_results_v2() {
+    let results = SearchResults::from_v2_json(include_str!("../../test/conan_list_v2.json")).unwrap();
+    assert_eq!(results.recipes.len(), 2);
+
+    let openssl = &results.recipes[0];
+    assert_eq!(
+        openssl.reference.to_string(),
+        "openssl/3.2.0#1e1e2c1b9bd3ba7b3fb1a1bf5b2d7e8c"
+    );
+    assert_eq!(openssl.remote, None);
+    assert_eq!(openssl.packages[0].requires, ["zlib/1.3.Z"]);
+
+    let zlib = &results.recipes[1];
+    assert_eq!(zlib.reference.to_string(), "zlib/1.3.1");
+    assert!(zlib.packages.is_empty());
+
+    let settings = BuildSettings::new()
+        .os("Linux".to_string())
+        .arch("x86_64".to_string())
+        .build_type(BuildType::Release);
+    let (_, package) = results.find_binary(&settings).unwrap();
+    assert_eq!(package.package_id, "b8c4e6f1a2d3c5b7e9f0a1b2c3d4e5f6a7b8c9d0");
+    assert_eq!(package.options["shared"], "True");
+
+    let error = SearchResults::from_v2_json(r#"{"conancenter": {"error": "Recipe 'foo' not found"}}"#);
+    assert!(matches!(error, Err(ConanSearchError::RemoteError { .. })));
+}
@@ -0,0 +1,305 @@
+//! Fake `conan` executable, to test build scripts and the commands without a Conan install
+//!
+//! `FakeConan` writes a shell script that records its invocations and answers them
+//! with canned output, exit codes and files. Point `CONAN` at `FakeConanProgram::program`,
+//! or run the commands through `FakeConanProgram::context`.
+//!
+//! Enabled by the `test-support` feature, the script needs a Unix shell.
+
+#[cfg(test)]
+mod tests;
+
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use crate::context::ConanContext;
+
+/// File written by a response: at a given path, or in the output folder of the invocation
+#[derive(Clone, Debug, PartialEq)]
+enum FakeFile {
+    Path(PathBuf),
+    Output(String),
+}
+
+/// Answer of the fake Conan: exit code, output, and files Conan would write
+#[derive(Clone, Debug, PartialEq)]
+pub struct FakeResponse {
+    code: i32,
+    stdout: Vec<u8>,
+    stderr: Vec<u8>,
+    files: Vec<(FakeFile, Vec<u8>)>,
+    delay: Option<Duration>,
+}
+
+impl FakeResponse {
+    pub fn success() -> Self {
+        FakeResponse::failure(0)
+    }
+
+    pub fn failure(code: i32) -> Self {
+        FakeResponse {
+            code,
+            stdout: Vec::new(),
+            stderr: Vec::new(),
+            files: Vec::new(),
+            delay: None,
+        }
+    }
+
+    pub fn with_stdout(mut self, stdout: &str) -> Self {
+        self.stdout = stdout.as_bytes().to_vec();
+        self
+    }
+
+    pub fn with_stderr(mut self, stderr: &str) -> Self {
+        self.stderr = stderr.as_bytes().to_vec();
+        self
+    }
+
+    /// Sleep after printing the output, e.g. to test timeouts.
+    pub fn with_delay(mut self, delay: Duration) -> Self {
+        self.delay = Some(delay);
+        self
+    }
+
+    /// Write a file, relative paths being resolved against the working directory.
+    pub fn with_file(mut self, path: &Path, contents: &[u8]) -> Self {
+        self.files.push((FakeFile::Path(path.to_path_buf()), contents.to_vec()));
+        self
+    }
+
+    /// Write a file in the output folder of the invocation, given by `-if`, `--install-folder`,
+    /// `-of` or `--output-folder`, e.g. the `conanbuildinfo.json` of `conan install`.
+    pub fn with_output_file(mut self, name: &str, contents: &[u8]) -> Self {
+        self.files.push((FakeFile::Output(name.to_string()), contents.to_vec()));
+        self
+    }
+}
+
+/// Builder of a fake `conan` executable
+///
+/// An invocation gets the response of the rule with the longest matching argument prefix.
+/// A rule given several responses answers with them in turn, repeating the last one.
+/// `conan --version` answers with the configured version unless scripted otherwise,
+/// and invocations without a rule fail.
+///
+/// ```
+/// use conan::test_support::{FakeConan, FakeResponse};
+/// use conan::InstallCommandBuilder;
+///
+/// let dir = std::env::temp_dir().join(format!("conan-rs-fake-doc-{}", std::process::id()));
+/// let fake = FakeConan::new(&dir)
+///     .on(
+///         &["install"],
+///         FakeResponse::success()
+///             .with_output_file("conanbuildinfo.json", include_bytes!("../test/conanbuildinfo1.json")),
+///     )
+///     .build()
+///     .unwrap();
+/// let command = InstallCommandBuilder::new()
+///     .with_context(fake.context())
+///     .output_dir(&dir.join("out"))
+///     .build();
+/// assert!(command.generate().is_some());
+/// assert_eq!(fake.invocations().unwrap().last().unwrap()[0], "install");
+/// # std::fs::remove_dir_all(&dir).unwrap();
+/// ```
+#[derive(Clone, Debug, PartialEq)]
+pub struct FakeConan {
+    dir: PathBuf,
+    version: String,
+    rules: Vec<(Vec<String>, Vec<FakeResponse>)>,
+}
+
+impl FakeConan {
+    /// Fake Conan written in `dir`, which holds the script and its data.
+    pub fn new(dir: &Path) -> Self {
+        FakeConan {
+            dir: dir.to_path_buf(),
+            version: "1.66.0".to_string(),
+            rules: Vec::new(),
+        }
+    }
+
+    /// Version printed by `conan --version`, Conan 1 by default.
+    pub fn with_version(mut self, version: &str) -> Self {
+        self.version = version.to_string();
+        self
+    }
+
+    /// Answer the invocations whose arguments start with `args`.
+    pub fn on(mut self, args: &[&str], response: FakeResponse) -> Self {
+        let args: Vec<String> = args.iter().map(|arg| arg.to_string()).collect();
+        match self.rules.iter_mut().find(|(prefix, _)| *prefix == args) {
+            Some((_, responses)) => responses.push(response),
+            None => self.rules.push((args, vec![response])),
+        }
+        self
+    }
+
+    /// Write the script, clearing the invocations recorded by a previous one.
+    pub fn build(self) -> io::Result<FakeConanProgram> {
+        let data_dir = self.dir.join("conan-data");
+        if data_dir.exists() {
+            fs::remove_dir_all(&data_dir)?;
+        }
+        fs::create_dir_all(&data_dir)?;
+
+        let mut rules = self.rules;
+        if !rules.iter().any(|(prefix, _)| prefix == &["--version"]) {
+            let version = FakeResponse::success().with_stdout(&format!("Conan version {}\n", self.version));
+            rules.push((vec!["--version".to_string()], vec![version]));
+        }
+        // NOTE: The script takes the first matching rule, so the longest prefixes go first
+        rules.sort_by_key(|(prefix, _)| std::cmp::Reverse(prefix.len()));
+
+        let mut script = format!("#!/bin/sh\nDATA={}\n", quote(&data_dir.to_string_lossy()));
+        script.push_str(RECORD_AND_FIND_OUTPUT);
+        for (index, (prefix, responses)) in rules.iter().enumerate() {
+            for (turn, response) in responses.iter().enumerate() {
+                script.push_str(&format!("respond_{}_{}() {{\n", index, turn));
+                script.push_str(&write_response(&data_dir, &format!("{}.{}", index, turn), response)?);
+                script.push_str("}\n");
+            }
+
+            let mut condition = format!("[ \"$#\" -ge {} ]", prefix.len());
+            for (position, arg) in prefix.iter().enumerate() {
+                condition.push_str(&format!(" && [ \"${{{}}}\" = {} ]", position + 1, quote(arg)));
+            }
+            script.push_str(&format!("if {}; then\n", condition));
+            if responses.len() == 1 {
+                script.push_str(&format!("  respond_{}_0\n", index));
+            } else {
+                script.push_str(&format!(
+                    "  turn=$(cat \"$DATA/{0}.turn\" 2>/dev/null || echo 0)\n  echo $((turn + 1)) > \"$DATA/{0}.turn\"\n  case \"$turn\" in\n",
+                    index
+                ));
+                for turn in 0..responses.len() - 1 {
+                    script.push_str(&format!("    {}) respond_{}_{} ;;\n", turn, index, turn));
+                }
+                script.push_str(&format!(
+                    "    *) respond_{}_{} ;;\n  esac\n",
+                    index,
+                    responses.len() - 1
+                ));
+            }
+            script.push_str("fi\n");
+        }
+        script.push_str("echo \"fake conan: no response for $*\" >&2\nexit 1\n");
+
+        let program = self.dir.join("conan");
+        fs::write(&program, script)?;
+        make_executable(&program)?;
+
+        Ok(FakeConanProgram {
+            program,
+            log: data_dir.join("invocations"),
+        })
+    }
+}
+
+/// Record the arguments, tab separated, and find the output folder.
+const RECORD_AND_FIND_OUTPUT: &str = r#"tab=$(printf '\t')
+sep=''
+for arg in "$@"; do
+  printf '%s%s' "$sep" "$arg" >> "$DATA/invocations"
+  sep="$tab"
+done
+printf '\n' >> "$DATA/invocations"
+out=.
+prev=''
+for arg in "$@"; do
+  case "$prev" in
+    -if|--install-folder|-of|--output-folder) out="$arg" ;;
+  esac
+  prev="$arg"
+done
+"#;
+
+/// Write the data of a response, returning the body of the shell function replaying it.
+fn write_response(data_dir: &Path, name: &str, response: &FakeResponse) -> io::Result<String> {
+    let stdout = data_dir.join(format!("{}.stdout", name));
+    let stderr = data_dir.join(format!("{}.stderr", name));
+    fs::write(&stdout, &response.stdout)?;
+    fs::write(&stderr, &response.stderr)?;
+
+    let mut body = format!(
+        "  cat {}\n  cat {} >&2\n",
+        quote(&stdout.to_string_lossy()),
+        quote(&stderr.to_string_lossy())
+    );
+    if let Some(delay) = response.delay {
+        body.push_str(&format!("  sleep {}\n", delay.as_secs_f64()));
+    }
+    for (file, (destination, contents)) in response.files.iter().enumerate() {
+        let source = data_dir.join(format!("{}.file{}", name, file));
+        fs::write(&source, contents)?;
+        let destination = match destination {
+            FakeFile::Path(path) => quote(&path.to_string_lossy()),
+            FakeFile::Output(file_name) => format!("\"$out\"/{}", quote(file_name)),
+        };
+        body.push_str(&format!(
+            "  mkdir -p \"$(dirname {1})\" && cp {0} {1}\n",
+            quote(&source.to_string_lossy()),
+            destination
+        ));
+    }
+    body.push_str(&format!("  exit {}\n", response.code));
+    Ok(body)
+}
+
+/// Quote a string for the shell.
+fn quote(value: &str) -> String {
+    format!("'{}'", value.replace('\'', r"'\''"))
+}
+
+#[cfg(unix)]
+fn make_executable(program: &Path) -> io::Result<()> {
+    use std::os::unix::fs::PermissionsExt;
+    fs::set_permissions(program, fs::Permissions::from_mode(0o755))
+}
+
+#[cfg(not(unix))]
+fn make_executable(_program: &Path) -> io::Result<()> {
+    Err(io::Error::new(
+        io::ErrorKind::Unsupported,
+        "the fake conan script needs a Unix shell",
+    ))
+}
+
+/// Fake `conan` executable written by `FakeConan`
+#[derive(Clone, Debug, PartialEq)]
+pub struct FakeConanProgram {
+    program: PathBuf,
+    log: PathBuf,
+}
+
+impl FakeConanProgram {
+    /// Path of the script, e.g. for the `CONAN` variable.
+    pub fn program(&self) -> &Path {
+        &self.program
+    }
+
+    /// Context running the commands with the script.
+    pub fn context(&self) -> ConanContext {
+        ConanContext::new().with_program(&self.program)
+    }
+
+    /// Arguments of the invocations so far, in order.
+    pub fn invocations(&self) -> io::Result<Vec<Vec<String>>> {
+        let log = match fs::read_to_string(&self.log) {
+            Ok(log) => log,
+            Err(error) if error.kind() == io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(error) => return Err(error),
+        };
+        Ok(log
+            .lines()
+            .map(|line| match line {
+                "" => Vec::new(),
+                line => line.split('\t').map(str::to_string).collect(),
+            })
+            .collect())
+    }
+}
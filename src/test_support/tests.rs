@@ -0,0 +1,104 @@
+use super::*;
+use crate::util::ConanVersion;
+
+fn fake_dir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("conan-rs-fake-{}-{}", name, std::process::id()));
+    let _ = fs::remove_dir_all(&dir);
+    dir
+}
+
+fn args(args: &[&str]) -> Vec<String> {
+    args.iter().map(|arg| arg.to_string()).collect()
+}
+
+#[cfg(unix)]
+#[test]
+fn test_fake_conan() -> Result<(), Box<dyn std::error::Error>> {
+    let dir = fake_dir("rules");
+    let fake = FakeConan::new(&dir)
+        .with_version("2.0.5")
+        .on(&["remote"], FakeResponse::success().with_stdout("conancenter\n"))
+        .on(&["remote", "login"], FakeResponse::failure(3).with_stderr("denied\n"))
+        .on(&["remote", "login"], FakeResponse::success())
+        .on(
+            &["install"],
+            FakeResponse::success().with_output_file("conanbuildinfo.json", b"{}"),
+        )
+        .build()?;
+    let context = fake.context();
+
+    assert_eq!(context.conan_version(), ConanVersion::V2);
+    let output = context.execute(&args(&["remote", "list"]))?;
+    assert_eq!(output.stdout, b"conancenter\n");
+
+    // NOTE: The longest prefix wins, and a rule repeats its last response
+    let output = context.execute(&args(&["remote", "login", "it's me"]))?;
+    assert_eq!(output.status.code(), Some(3));
+    assert_eq!(output.stderr, b"denied\n");
+    assert!(context.execute(&args(&["remote", "login"]))?.status.success());
+    assert!(context.execute(&args(&["remote", "login"]))?.status.success());
+
+    let out_dir = dir.join("out dir");
+    let output_dir = out_dir.to_str().unwrap();
+    assert!(context
+        .execute(&args(&["install", ".", "-if", output_dir]))?
+        .status
+        .success());
+    assert_eq!(fs::read_to_string(out_dir.join("conanbuildinfo.json"))?, "{}");
+
+    assert_eq!(context.execute(&args(&["upload"]))?.status.code(), Some(1));
+
+    let invocations = fake.invocations()?;
+    assert_eq!(invocations.len(), 7);
+    assert_eq!(invocations[0], ["--version"]);
+    assert_eq!(invocations[2], ["remote", "login", "it's me"]);
+    assert_eq!(invocations[5], ["install", ".", "-if", output_dir]);
+
+    fs::remove_dir_all(&dir)?;
+    Ok(())
+}
+
+#[cfg(unix)]
+#[test]
+fn test_fake_conan_rebuild_clears_invocations() -> Result<(), Box<dyn std::error::Error>> {
+    let dir = fake_dir("rebuild");
+    let fake = FakeConan::new(&dir).build()?;
+    assert!(fake.invocations()?.is_empty());
+    fake.context().execute(&args(&["--version"]))?;
+    assert_eq!(fake.invocations()?.len(), 1);
+
+    let fake = FakeConan::new(&dir).build()?;
+    assert!(fake.invocations()?.is_empty());
+
+    fs::remove_dir_all(&dir)?;
+    Ok(())
+}
+
+#[cfg(unix)]
+#[test]
+fn test_fake_conan_timeout() -> Result<(), Box<dyn std::error::Error>> {
+    use crate::context::ConanContextError;
+    use std::time::Duration;
+
+    let dir = fake_dir("timeout");
+    let fake = FakeConan::new(&dir)
+        .on(
+            &["install"],
+            FakeResponse::success()
+                .with_stdout("Connecting to remote\n")
+                .with_delay(Duration::from_secs(10)),
+        )
+        .build()?;
+    let context = fake
+        .context()
+        .with_conan_version(ConanVersion::V1)
+        .with_timeout(Duration::from_millis(500));
+
+    match context.execute(&args(&["install", "."])) {
+        Err(ConanContextError::Timeout(timeout)) => assert_eq!(timeout.stdout, b"Connecting to remote\n"),
+        result => panic!("unexpected result {:?}", result),
+    }
+
+    fs::remove_dir_all(&dir)?;
+    Ok(())
+}
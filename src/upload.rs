@@ -0,0 +1,435 @@
+#[cfg(test)]
+mod tests;
+
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::ExitStatus;
+use std::time::Duration;
+
+use serde_json::Value;
+use thiserror::Error;
+
+use crate::context::{ConanContext, ConanContextError};
+use crate::home::ConanHome;
+use crate::reference::{ConanReference, ReferenceError};
+use crate::retry::RetryPolicy;
+use crate::util::ConanVersion;
+
+#[derive(Debug, Error)]
+pub enum ConanUploadError {
+    #[error("Conan binary not found")]
+    ConanNotFound,
+    #[error("Invalid Unicode in path")]
+    InvalidUnicodeInPath,
+    #[error("{0} is not supported by this Conan version")]
+    UnsupportedArgument(&'static str),
+    #[error("Output directory not found")]
+    OutputDirNotFound,
+    #[error("Conan upload failed with {0}")]
+    CommandFailed(ExitStatus),
+    #[error("I/O error: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("Failed to parse Conan JSON output: {0}")]
+    Json(#[from] serde_json::Error),
+    #[error("Unexpected Conan JSON output: {0}")]
+    InvalidOutput(String),
+    #[error(transparent)]
+    InvalidReference(#[from] ReferenceError),
+    #[error(transparent)]
+    Context(#[from] ConanContextError),
+}
+
+/// Binary package handled by "conan upload"
+#[derive(Clone, Debug, PartialEq)]
+pub struct UploadedPackage {
+    pub package_id: String,
+    /// `false` when the package was skipped because the remote is already up to date
+    pub uploaded: bool,
+}
+
+/// Recipe handled by "conan upload", with its binary packages
+#[derive(Clone, Debug, PartialEq)]
+pub struct UploadedRecipe {
+    pub reference: ConanReference,
+    /// `false` when the recipe was skipped because the remote is already up to date
+    pub uploaded: bool,
+    pub packages: Vec<UploadedPackage>,
+}
+
+/// Report of an upload, parsed from the Conan JSON output
+///
+/// Conan 1 does not record skipped items: every recipe and package it lists is reported as uploaded.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct UploadReport {
+    pub remote: Option<String>,
+    pub recipes: Vec<UploadedRecipe>,
+}
+
+impl UploadReport {
+    /// Parse the file written by `conan upload --json` (Conan 1).
+    pub fn from_v1_json(json: &str) -> Result<Self, ConanUploadError> {
+        let value: Value = serde_json::from_str(json)?;
+        let uploaded = value["uploaded"]
+            .as_array()
+            .ok_or_else(|| ConanUploadError::InvalidOutput("missing \"uploaded\" list".to_string()))?;
+
+        let mut report = UploadReport::default();
+        for item in uploaded {
+            let reference = item["recipe"]["id"]
+                .as_str()
+                .ok_or_else(|| ConanUploadError::InvalidOutput("missing recipe id".to_string()))?;
+            if report.remote.is_none() {
+                report.remote = item["recipe"]["remote_name"].as_str().map(str::to_string);
+            }
+
+            let packages = item["packages"]
+                .as_array()
+                .map(|packages| {
+                    packages
+                        .iter()
+                        .filter_map(|x| x["id"].as_str())
+                        .map(|id| UploadedPackage {
+                            package_id: id.to_string(),
+                            uploaded: true,
+                        })
+                        .collect()
+                })
+                .unwrap_or_default();
+
+            report.recipes.push(UploadedRecipe {
+                reference: reference.parse()?,
+                uploaded: true,
+                packages,
+            });
+        }
+
+        Ok(report)
+    }
+
+    /// Parse the package list printed by `conan upload --format=json` (Conan 2).
+    pub fn from_v2_json(json: &str) -> Result<Self, ConanUploadError> {
+        let value: Value = serde_json::from_str(json)?;
+        let remotes = value
+            .as_object()
+            .ok_or_else(|| ConanUploadError::InvalidOutput("expected a package list".to_string()))?;
+
+        let mut report = UploadReport::default();
+        for (remote, references) in remotes {
+            report.remote = Some(remote.clone());
+            let references = match references.as_object() {
+                Some(references) => references,
+                None => continue,
+            };
+
+            for (reference, recipe) in references {
+                let revisions = recipe["revisions"].as_object().into_iter().flatten();
+                for (revision, recipe) in revisions {
+                    let packages = recipe["packages"].as_object().into_iter().flatten();
+                    let packages = packages
+                        .map(|(package_id, package)| UploadedPackage {
+                            package_id: package_id.clone(),
+                            uploaded: package["revisions"]
+                                .as_object()
+                                .into_iter()
+                                .flatten()
+                                .any(|(_, x)| x["upload"].as_bool().unwrap_or(false)),
+                        })
+                        .collect();
+
+                    report.recipes.push(UploadedRecipe {
+                        reference: format!("{}#{}", reference, revision).parse()?,
+                        uploaded: recipe["upload"].as_bool().unwrap_or(false),
+                        packages,
+                    });
+                }
+            }
+        }
+
+        Ok(report)
+    }
+
+    pub fn uploaded_recipes(&self) -> impl Iterator<Item = &UploadedRecipe> {
+        self.recipes.iter().filter(|x| x.uploaded)
+    }
+
+    pub fn skipped_recipes(&self) -> impl Iterator<Item = &UploadedRecipe> {
+        self.recipes.iter().filter(|x| !x.uploaded)
+    }
+
+    pub fn uploaded_packages(&self) -> impl Iterator<Item = &UploadedPackage> {
+        self.recipes
+            .iter()
+            .flat_map(|x| x.packages.iter())
+            .filter(|x| x.uploaded)
+    }
+
+    pub fn skipped_packages(&self) -> impl Iterator<Item = &UploadedPackage> {
+        self.recipes
+            .iter()
+            .flat_map(|x| x.packages.iter())
+            .filter(|x| !x.uploaded)
+    }
+}
+
+/// Recipes selected by "conan upload"
+enum UploadPattern {
+    Pattern(String),
+    Reference(ConanReference),
+}
+
+/// "conan upload" command runner
+pub struct UploadCommand<'a> {
+    pattern: UploadPattern,
+    remote: Option<&'a str>,
+    all: bool,
+    only_recipe: bool,
+    confirm: bool,
+    parallel: bool,
+    check: bool,
+    conan_retry: Option<u32>,
+    conan_retry_wait: Option<u32>,
+    output_dir: Option<PathBuf>,
+    context: ConanContext,
+}
+
+/// "conan upload" command arguments builder
+pub struct UploadCommandBuilder<'a> {
+    pattern: UploadPattern,
+    remote: Option<&'a str>,
+    all: bool,
+    only_recipe: bool,
+    confirm: bool,
+    parallel: bool,
+    check: bool,
+    conan_retry: Option<u32>,
+    conan_retry_wait: Option<u32>,
+    output_dir: Option<PathBuf>,
+    context: ConanContext,
+}
+
+impl<'a> UploadCommandBuilder<'a> {
+    /// Upload the recipes matching a reference or pattern, e.g. `bridge/0.1.0@devolutions/stable` or `bridge/*`.
+    pub fn new(pattern: &str) -> UploadCommandBuilder<'a> {
+        Self::with_pattern(UploadPattern::Pattern(pattern.to_string()))
+    }
+
+    /// Upload a single recipe.
+    pub fn from_reference(reference: &ConanReference) -> UploadCommandBuilder<'a> {
+        Self::with_pattern(UploadPattern::Reference(reference.clone()))
+    }
+
+    fn with_pattern(pattern: UploadPattern) -> UploadCommandBuilder<'a> {
+        UploadCommandBuilder {
+            pattern,
+            remote: None,
+            all: false,
+            only_recipe: false,
+            confirm: false,
+            parallel: false,
+            check: false,
+            conan_retry: None,
+            conan_retry_wait: None,
+            output_dir: None,
+            context: ConanContext::default(),
+        }
+    }
+
+    pub fn with_remote(mut self, remote: &'a str) -> Self {
+        self.remote = Some(remote);
+        self
+    }
+
+    /// Upload the binary packages along with the recipes (Conan 1), which is the default on Conan 2.
+    pub fn all(mut self) -> Self {
+        self.all = true;
+        self
+    }
+
+    pub fn only_recipe(mut self) -> Self {
+        self.only_recipe = true;
+        self
+    }
+
+    /// Upload without asking for confirmation.
+    pub fn confirm(mut self) -> Self {
+        self.confirm = true;
+        self
+    }
+
+    /// Conan 1 only: upload files in parallel.
+    pub fn parallel(mut self) -> Self {
+        self.parallel = true;
+        self
+    }
+
+    /// Check the integrity of the files before uploading them.
+    pub fn check(mut self) -> Self {
+        self.check = true;
+        self
+    }
+
+    /// Conan 1 only: number of times Conan retries a failed file transfer, its `--retry` flag.
+    ///
+    /// Conan retries within a single run, while the `RetryPolicy` of `with_retry` runs the whole
+    /// command again once Conan gives up: with both, a transfer may be attempted up to
+    /// `(conan_retry + 1) * max_attempts` times.
+    pub fn conan_retry(mut self, retry: u32) -> Self {
+        self.conan_retry = Some(retry);
+        self
+    }
+
+    /// Conan 1 only: seconds Conan waits between the retries of `conan_retry`, its `--retry-wait` flag.
+    pub fn conan_retry_wait(mut self, seconds: u32) -> Self {
+        self.conan_retry_wait = Some(seconds);
+        self
+    }
+
+    /// Directory receiving the Conan 1 JSON report, defaults to `OUT_DIR`.
+    pub fn output_dir(mut self, output_dir: &Path) -> Self {
+        self.output_dir = Some(output_dir.to_path_buf());
+        self
+    }
+
+    /// Run `conan upload` through a shared context, keeping the timeout, retry policy, home and version
+    /// set on this builder.
+    pub fn with_context(mut self, context: ConanContext) -> Self {
+        self.context = context.merged_with(self.context);
+        self
+    }
+
+    /// Kill Conan and fail with a timeout error if it runs longer than `timeout`.
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.context = self.context.with_timeout(timeout);
+        self
+    }
+
+    /// Run the command again on network failures, see `RetryPolicy` and `conan_retry`.
+    pub fn with_retry(mut self, retry: RetryPolicy) -> Self {
+        self.context = self.context.with_retry(retry);
+        self
+    }
+
+    /// Run Conan with an isolated home, e.g. `ConanHome::in_out_dir()?`.
+    pub fn with_conan_home(mut self, conan_home: ConanHome) -> Self {
+        self.context = self.context.with_home(conan_home);
+        self
+    }
+
+    pub fn conan_version(mut self, conan_version: ConanVersion) -> Self {
+        self.context = self.context.with_conan_version(conan_version);
+        self
+    }
+
+    pub fn build(self) -> UploadCommand<'a> {
+        UploadCommand {
+            pattern: self.pattern,
+            remote: self.remote,
+            all: self.all,
+            only_recipe: self.only_recipe,
+            confirm: self.confirm,
+            parallel: self.parallel,
+            check: self.check,
+            conan_retry: self.conan_retry,
+            conan_retry_wait: self.conan_retry_wait,
+            output_dir: self.output_dir,
+            context: self.context,
+        }
+    }
+}
+
+impl<'a> UploadCommand<'a> {
+    pub fn args(&self) -> Result<Vec<String>, ConanUploadError> {
+        let conan_version = self.context.conan_version();
+        let pattern = match &self.pattern {
+            UploadPattern::Pattern(pattern) => pattern.clone(),
+            UploadPattern::Reference(reference) => reference.to_cli_string(conan_version),
+        };
+        let mut args: Vec<String> = vec!["upload".to_string(), pattern];
+
+        if let Some(remote) = self.remote {
+            args.extend(vec!["-r".to_string(), remote.to_string()]);
+        }
+
+        // NOTE: Conan 2 uploads the binary packages unless told otherwise
+        if self.all && conan_version == ConanVersion::V1 {
+            args.push("--all".to_string());
+        }
+
+        if self.only_recipe {
+            args.push("--only-recipe".to_string());
+        }
+
+        if self.confirm {
+            args.push("--confirm".to_string());
+        }
+
+        if self.check {
+            args.push("--check".to_string());
+        }
+
+        if self.parallel {
+            if conan_version == ConanVersion::V2 {
+                return Err(ConanUploadError::UnsupportedArgument("--parallel"));
+            }
+            args.push("--parallel".to_string());
+        }
+
+        if let Some(retry) = self.conan_retry {
+            if conan_version == ConanVersion::V2 {
+                return Err(ConanUploadError::UnsupportedArgument("--retry"));
+            }
+            args.extend(vec!["--retry".to_string(), retry.to_string()]);
+        }
+
+        if let Some(retry_wait) = self.conan_retry_wait {
+            if conan_version == ConanVersion::V2 {
+                return Err(ConanUploadError::UnsupportedArgument("--retry-wait"));
+            }
+            args.extend(vec!["--retry-wait".to_string(), retry_wait.to_string()]);
+        }
+
+        match conan_version {
+            ConanVersion::V1 => {
+                let output_file = self.output_file().ok_or(ConanUploadError::OutputDirNotFound)?;
+                let output_file = output_file.to_str().ok_or(ConanUploadError::InvalidUnicodeInPath)?;
+                args.extend(vec!["--json".to_string(), output_file.to_string()]);
+            }
+            ConanVersion::V2 => args.push("--format=json".to_string()),
+        }
+
+        Ok(args)
+    }
+
+    pub fn output_dir(&self) -> Option<PathBuf> {
+        self.output_dir
+            .clone()
+            .or_else(|| env::var("OUT_DIR").ok().map(PathBuf::from))
+            .or_else(|| env::current_dir().ok())
+    }
+
+    /// JSON report written by Conan 1.
+    pub fn output_file(&self) -> Option<PathBuf> {
+        let mut output_file = self.output_dir()?;
+        output_file.push("conan_upload.json");
+        Some(output_file)
+    }
+
+    pub fn run(&self) -> Result<UploadReport, ConanUploadError> {
+        let conan_version = self.context.conan_version();
+        let args = self.args()?;
+        let output = self.context.execute(&args)?;
+        if !output.status.success() {
+            return Err(ConanUploadError::CommandFailed(output.status));
+        }
+
+        match conan_version {
+            ConanVersion::V1 => {
+                let output_file = self.output_file().ok_or(ConanUploadError::OutputDirNotFound)?;
+                UploadReport::from_v1_json(&fs::read_to_string(output_file)?)
+            }
+            // NOTE: Conan 2 prints the JSON package list on stdout and its logs on stderr
+            ConanVersion::V2 => UploadReport::from_v2_json(&String::from_utf8_lossy(&output.stdout)),
+        }
+    }
+}
@@ -0,0 +1,243 @@
+use super::*;
+use std::process::Command;
+
+#[test]
+fn test_upload_args_v1() {
+    let command = UploadCommandBuilder::new("bridge/0.1.0@devolutions/stable")
+        .with_remote("artifactory")
+        .all()
+        .confirm()
+        .parallel()
+        .check()
+        .conan_retry(3)
+        .conan_retry_wait(10)
+        .output_dir(Path::new("out"))
+        .conan_version(ConanVersion::V1)
+        .build();
+
+    let output_file = command.output_file().unwrap();
+    assert_eq!(
+        command.args().unwrap(),
+        [
+            "upload",
+            "bridge/0.1.0@devolutions/stable",
+            "-r",
+            "artifactory",
+            "--all",
+            "--confirm",
+            "--check",
+            "--parallel",
+            "--retry",
+            "3",
+            "--retry-wait",
+            "10",
+            "--json",
+            output_file.to_str().unwrap()
+        ]
+    );
+}
+
+#[test]
+fn test_upload_args_v2() {
+    let command = UploadCommandBuilder::new("bridge/*")
+        .with_remote("artifactory")
+        .all()
+        .only_recipe()
+        .confirm()
+        .conan_version(ConanVersion::V2)
+        .build();
+
+    assert_eq!(
+        command.args().unwrap(),
+        [
+            "upload",
+            "bridge/*",
+            "-r",
+            "artifactory",
+            "--only-recipe",
+            "--confirm",
+            "--format=json"
+        ]
+    );
+
+    let command = UploadCommandBuilder::new("bridge/*")
+        .conan_retry(3)
+        .conan_version(ConanVersion::V2)
+        .build();
+    assert!(matches!(
+        command.args(),
+        Err(ConanUploadError::UnsupportedArgument("--retry"))
+    ));
+}
+
+#[test]
+fn test_upload_report_from_json() {
+    let report = UploadReport::from_v1_json(include_str!("../../test/conan_upload_v1.json")).unwrap();
+    assert_eq!(report.remote.as_deref(), Some("artifactory"));
+    assert_eq!(report.recipes.len(), 1);
+    assert_eq!(
+        report.recipes[0].reference.to_string(),
+        "bridge/0.1.0@devolutions/stable"
+    );
+    assert_eq!(report.uploaded_packages().count(), 2);
+    assert_eq!(report.skipped_packages().count(), 0);
+
+    let report = UploadReport::from_v2_json(include_str!("../../test/conan_upload_v2.json")).unwrap();
+    assert_eq!(report.remote.as_deref(), Some("artifactory"));
+
+    let uploaded: Vec<_> = report.uploaded_recipes().map(|x| x.reference.to_string()).collect();
+    assert_eq!(
+        uploaded,
+        ["bridge/0.1.0@devolutions/stable#a2b7c3d0f1e4e5a6b7c8d9e0f1a2b3c4"]
+    );
+    let skipped: Vec<_> = report.skipped_recipes().map(|x| x.reference.to_string()).collect();
+    assert_eq!(skipped, ["zlib/1.2.13#97d5730b529b4224045fe7090592d4c1"]);
+
+    let uploaded: Vec<_> = report.uploaded_packages().map(|x| x.package_id.as_str()).collect();
+    assert_eq!(uploaded, ["3fb49604f9c2f729b85ba3115852006824e72cab"]);
+    let skipped: Vec<_> = report.skipped_packages().map(|x| x.package_id.as_str()).collect();
+    assert_eq!(skipped, ["6af9cc7cb931c5ad942174fd7838eb655717c709"]);
+}
+
+#[cfg(unix)]
+#[test]
+fn test_upload_with_fake_conan() {
+    use crate::test_support::{FakeConan, FakeResponse};
+
+    let dir = env::temp_dir().join(format!("conan-rs-upload-{}", std::process::id()));
+    let _ = fs::remove_dir_all(&dir);
+
+    // NOTE: Conan 1 writes the report to the --json path
+    let report = include_bytes!("../../test/conan_upload_v1.json");
+    let fake = FakeConan::new(&dir)
+        .on(
+            &["upload"],
+            FakeResponse::success().with_file(&dir.join("conan_upload.json"), report),
+        )
+        .build()
+        .unwrap();
+    let command = UploadCommandBuilder::new("bridge/*")
+        .with_context(fake.context())
+        .with_remote("artifactory")
+        .all()
+        .confirm()
+        .output_dir(&dir)
+        .conan_version(ConanVersion::V1)
+        .build();
+    assert_eq!(command.output_file(), Some(dir.join("conan_upload.json")));
+
+    let report = command.run().unwrap();
+    assert_eq!(report.uploaded_recipes().count(), 1);
+    assert_eq!(report.uploaded_packages().count(), 2);
+    let invocations = fake.invocations().unwrap();
+    assert_eq!(invocations, [command.args().unwrap()]);
+    assert_eq!(invocations[0][..2], ["upload", "bridge/*"]);
+
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+/// Uploads a trivial package to a `conan_server` started for the test.
+///
+/// Requires Conan 1 and `conan_server` on the `PATH`: `cargo test -- --ignored`
+/// The remote is added to an isolated Conan home, leaving the user configuration alone.
+#[test]
+#[ignore]
+fn test_upload_to_conan_server() {
+    use crate::context::ConanContext;
+    use crate::export::ExportCommandBuilder;
+    use crate::home::ConanHome;
+    use crate::install::remote::{RemoteCredentials, RemoteLoginCommandBuilder};
+
+    let dir = env::temp_dir().join(format!("conan-rs-server-{}", std::process::id()));
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(&dir).unwrap();
+    fs::write(
+        dir.join("conanfile.py"),
+        "from conans import ConanFile\n\nclass Hello(ConanFile):\n    name = \"hello\"\n    version = \"0.1\"\n",
+    )
+    .unwrap();
+    let context = ConanContext::new()
+        .with_home(ConanHome::new(&dir.join("home")))
+        .with_conan_version(ConanVersion::V1);
+
+    let mut server = Command::new("conan_server").spawn().expect("conan_server not found");
+    std::thread::sleep(std::time::Duration::from_secs(2));
+
+    let result = (|| -> Result<UploadReport, Box<dyn std::error::Error>> {
+        let remote_add = ["remote", "add", "conan-rs-test", "http://localhost:9300", "--force"];
+        let remote_add: Vec<String> = remote_add.iter().map(|arg| arg.to_string()).collect();
+        if !context.execute(&remote_add)?.status.success() {
+            return Err("conan remote add failed".into());
+        }
+        ExportCommandBuilder::new()
+            .with_context(context.clone())
+            .with_recipe_path(dir.clone())
+            .with_user("demo")
+            .with_channel("testing")
+            .build()
+            .run()?;
+        RemoteLoginCommandBuilder::new("conan-rs-test")
+            .with_context(context.clone())
+            .with_credentials(RemoteCredentials::new("demo", "demo"))
+            .build()
+            .run()?;
+        Ok(UploadCommandBuilder::new("hello/0.1@demo/testing")
+            .with_context(context.clone())
+            .with_remote("conan-rs-test")
+            .confirm()
+            .output_dir(&dir)
+            .build()
+            .run()?)
+    })();
+
+    let _ = server.kill();
+    let _ = server.wait();
+    let _ = fs::remove_dir_all(&dir);
+
+    let report = result.unwrap();
+    assert_eq!(report.remote.as_deref(), Some("conan-rs-test"));
+    assert_eq!(report.recipes[0].reference.to_string(), "hello/0.1@demo/testing");
+}
+
+#[test]
+fn test_upload_reference_args() {
+    let reference = ConanReference::new("zlib", "1.2.13").unwrap();
+    let command = UploadCommandBuilder::from_reference(&reference)
+        .conan_version(ConanVersion::V2)
+        .build();
+    assert_eq!(command.args().unwrap(), ["upload", "zlib/1.2.13", "--format=json"]);
+
+    let command = UploadCommandBuilder::from_reference(&reference)
+        .output_dir(Path::new("out"))
+        .conan_version(ConanVersion::V1)
+        .build();
+    assert_eq!(command.args().unwrap()[..2], ["upload", "zlib/1.2.13@"]);
+}
+
+#[test]
+fn test_upload_retry() {
+    use crate::context::ConanContext;
+    use crate::executor::{ScriptedExecutor, ScriptedResponse};
+    use crate::retry::RetryPolicy;
+    use std::path::Path;
+    use std::time::Duration;
+
+    let executor = ScriptedExecutor::new()
+        .on(
+            &["upload"],
+            ScriptedResponse::failure(1).with_stderr("ERROR: ('Connection aborted.', RemoteDisconnected())"),
+        )
+        .on(&["upload"], ScriptedResponse::success().with_stdout("{}"));
+    let context = ConanContext::new()
+        .with_program(Path::new("conan"))
+        .with_conan_version(ConanVersion::V2)
+        .with_executor(executor.clone());
+    let command = UploadCommandBuilder::new("zlib/1.3.1")
+        .with_remote("artifactory")
+        .with_context(context)
+        .with_retry(RetryPolicy::new(2).with_backoff(Duration::ZERO, Duration::ZERO))
+        .build();
+
+    assert!(command.run().is_ok());
+    assert_eq!(executor.invocations().len(), 2);
+}
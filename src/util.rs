@@ -4,13 +4,83 @@ mod test;
 use lazy_static::lazy_static;
 use regex::Regex;
 use std::env;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::process::Command;
 
 lazy_static! {
     static ref REGEX_CONAN_VERSION: Regex = Regex::new(r"version (\d+)\.(\d+).(\d+)$").unwrap();
 }
 
+/// Conan major version, which determines the command line syntax
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum ConanVersion {
+    #[default]
+    V1,
+    V2,
+}
+
+impl ConanVersion {
+    /// Detect the major version of the Conan executable returned by `find_program`.
+    pub fn detect() -> Option<ConanVersion> {
+        ConanVersion::from_version(&find_version()?)
+    }
+
+    /// Detect the major version of the given Conan executable.
+    pub fn detect_program(program: &Path) -> Option<ConanVersion> {
+        ConanVersion::from_version(&program_version(program)?)
+    }
+
+    pub(crate) fn from_version(version: &str) -> Option<ConanVersion> {
+        match version.split('.').next()? {
+            "1" => Some(ConanVersion::V1),
+            "2" => Some(ConanVersion::V2),
+            _ => None,
+        }
+    }
+}
+
+/// Package pattern for the given Conan version.
+///
+/// Conan 2 matches patterns against references, so a bare `zlib` becomes `zlib/*`,
+/// while Conan 1 only matches package names.
+pub(crate) fn package_pattern(package: &str, conan_version: ConanVersion) -> String {
+    match conan_version {
+        ConanVersion::V1 => package.strip_suffix("/*").unwrap_or(package).to_string(),
+        ConanVersion::V2 if package.contains('/') || package.contains('*') => package.to_string(),
+        ConanVersion::V2 => format!("{}/*", package),
+    }
+}
+
+/// Match a Conan pattern, where `*` matches any sequence of characters and `?` any single character.
+pub(crate) fn glob_match(pattern: &str, text: &str) -> bool {
+    let pattern: Vec<char> = pattern.chars().collect();
+    let text: Vec<char> = text.chars().collect();
+    let (mut p, mut t) = (0, 0);
+    let mut backtrack: Option<(usize, usize)> = None;
+
+    while t < text.len() {
+        match pattern.get(p) {
+            Some('*') => {
+                backtrack = Some((p, t));
+                p += 1;
+            }
+            Some(&c) if c == '?' || c == text[t] => {
+                p += 1;
+                t += 1;
+            }
+            _ => match backtrack {
+                Some((star, matched)) => {
+                    p = star + 1;
+                    t = matched + 1;
+                    backtrack = Some((star, matched + 1));
+                }
+                None => return false,
+            },
+        }
+    }
+    pattern[p..].iter().all(|&c| c == '*')
+}
+
 pub fn find_program() -> Option<PathBuf> {
     if let Ok(conan) = env::var("CONAN") {
         return Some(PathBuf::from(conan));
@@ -18,29 +88,26 @@ pub fn find_program() -> Option<PathBuf> {
     which::which("conan").ok()
 }
 
-// NOTE: Will be used in the future
-#[allow(dead_code)]
 pub fn find_version() -> Option<String> {
-    let conan_program = find_program()?;
-    let conan_program = conan_program.as_path().to_str().unwrap().to_string();
+    program_version(&find_program()?)
+}
 
-    let output = Command::new(&conan_program).arg("--version").output();
+/// Version of the given Conan executable.
+pub(crate) fn program_version(program: &Path) -> Option<String> {
+    let output = Command::new(program).arg("--version").output().ok()?;
+    parse_version(&String::from_utf8(output.stdout).ok()?)
+}
 
+/// Version printed by `conan --version`.
+pub(crate) fn parse_version(output: &str) -> Option<String> {
     // $ conan --version
     // Conan version 1.14.3
 
-    if let Ok(output) = output {
-        let output_stdout = String::from_utf8(output.stdout).unwrap();
-        let captures = REGEX_CONAN_VERSION.captures(output_stdout.as_str().trim()).unwrap();
-
-        let version_major = captures[1].parse::<u8>().unwrap();
-        let version_minor = captures[2].parse::<u8>().unwrap();
-        let version_micro = captures[3].parse::<u8>().unwrap();
+    let captures = REGEX_CONAN_VERSION.captures(output.trim())?;
 
-        let version = format!("{}.{}.{}", version_major, version_minor, version_micro);
-
-        return Some(version);
-    }
+    let version_major = captures[1].parse::<u8>().ok()?;
+    let version_minor = captures[2].parse::<u8>().ok()?;
+    let version_micro = captures[3].parse::<u8>().ok()?;
 
-    None
+    Some(format!("{}.{}.{}", version_major, version_minor, version_micro))
 }
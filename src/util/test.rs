@@ -1,4 +1,4 @@
-use super::{find_program, find_version};
+use super::{find_program, find_version, glob_match};
 
 #[test]
 fn test_find_program() {
@@ -12,3 +12,17 @@ fn test_find_version() {
         println!("Conan version: {}", version);
     }
 }
+
+#[test]
+fn test_glob_match() {
+    assert!(glob_match("*", "zlib"));
+    assert!(glob_match("zlib", "zlib"));
+    assert!(!glob_match("zlib", "zlib-ng"));
+    assert!(glob_match("zlib*", "zlib-ng"));
+    assert!(glob_match("zlib/*", "zlib/1.3.1"));
+    assert!(glob_match("*ssl*", "openssl"));
+    assert!(glob_match("lib?url", "libcurl"));
+    assert!(glob_match("a*b*c", "aXbYbZc"));
+    assert!(!glob_match("a*b*c", "aXbYbZ"));
+    assert!(!glob_match("", "zlib"));
+}
@@ -0,0 +1,362 @@
+#[cfg(test)]
+mod tests;
+
+use std::cmp::Ordering;
+use std::fmt;
+use std::str::FromStr;
+
+use thiserror::Error;
+
+#[derive(Debug, Error, Clone, PartialEq)]
+pub enum VersionError {
+    #[error("Empty version")]
+    EmptyVersion,
+    #[error("Invalid version range \"{0}\"")]
+    InvalidRange(String),
+    #[error("Dependency {0} not found in the build info")]
+    MissingDependency(String),
+    #[error("Dependency {name}/{version} does not satisfy {range}")]
+    Unsatisfied {
+        name: String,
+        version: String,
+        range: String,
+    },
+}
+
+#[derive(Clone, Debug)]
+enum VersionItem {
+    Number(u64),
+    Text(String),
+}
+
+impl VersionItem {
+    fn parse(item: &str) -> Self {
+        match item.parse::<u64>() {
+            Ok(number) if item.bytes().all(|x| x.is_ascii_digit()) => VersionItem::Number(number),
+            _ => VersionItem::Text(item.to_string()),
+        }
+    }
+
+    fn is_zero(&self) -> bool {
+        matches!(self, VersionItem::Number(0))
+    }
+}
+
+impl fmt::Display for VersionItem {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            VersionItem::Number(number) => write!(f, "{}", number),
+            VersionItem::Text(text) => f.write_str(text),
+        }
+    }
+}
+
+impl Ord for VersionItem {
+    fn cmp(&self, other: &Self) -> Ordering {
+        match (self, other) {
+            (VersionItem::Number(a), VersionItem::Number(b)) => a.cmp(b),
+            (VersionItem::Text(a), VersionItem::Text(b)) => a.cmp(b),
+            // NOTE: Like Conan, mixed items are compared as strings
+            (a, b) => a.to_string().cmp(&b.to_string()),
+        }
+    }
+}
+
+impl PartialOrd for VersionItem {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl PartialEq for VersionItem {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for VersionItem {}
+
+/// Package version, ordered with the Conan 2 rules
+///
+/// A version is a list of dot-separated items, optionally followed by a `-prerelease` and a `+build`.
+/// Numeric items compare as numbers, trailing zeros are ignored (`1.2 == 1.2.0`),
+/// and a pre-release sorts before the release it precedes (`1.2-beta < 1.2`).
+#[derive(Clone, Debug)]
+pub struct Version {
+    value: String,
+    items: Vec<VersionItem>,
+    pre: Option<Box<Version>>,
+    build: Option<Box<Version>>,
+}
+
+impl Version {
+    fn parse(value: &str) -> Self {
+        let (main, build) = match value.rfind('+') {
+            Some(index) => (&value[..index], Some(Box::new(Version::parse(&value[index + 1..])))),
+            None => (value, None),
+        };
+        let (main, pre) = match main.rfind('-') {
+            Some(index) => (&main[..index], Some(Box::new(Version::parse(&main[index + 1..])))),
+            None => (main, None),
+        };
+
+        Version {
+            value: value.to_string(),
+            items: main.split('.').map(VersionItem::parse).collect(),
+            pre,
+            build,
+        }
+    }
+
+    pub fn pre(&self) -> Option<&Version> {
+        self.pre.as_deref()
+    }
+
+    pub fn build(&self) -> Option<&Version> {
+        self.build.as_deref()
+    }
+
+    pub fn is_prerelease(&self) -> bool {
+        self.pre.is_some()
+    }
+
+    fn nonzero_items(&self) -> &[VersionItem] {
+        let len = self.items.iter().rposition(|x| !x.is_zero()).map_or(0, |x| x + 1);
+        &self.items[..len]
+    }
+
+    /// Lowest version above every version sharing the first `index` items, pre-releases included.
+    fn upper_bound(&self, index: usize) -> Result<Version, VersionError> {
+        let mut items: Vec<String> = self.items.iter().take(index).map(|x| x.to_string()).collect();
+        match self.items.get(index) {
+            Some(VersionItem::Number(number)) => items.push((number + 1).to_string()),
+            Some(VersionItem::Text(_)) => return Err(VersionError::InvalidRange(self.value.clone())),
+            None => items.push("1".to_string()),
+        }
+        Ok(Version::parse(&format!("{}-", items.join("."))))
+    }
+}
+
+impl FromStr for Version {
+    type Err = VersionError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        if s.is_empty() {
+            return Err(VersionError::EmptyVersion);
+        }
+        Ok(Version::parse(s))
+    }
+}
+
+impl fmt::Display for Version {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(&self.value)
+    }
+}
+
+impl Ord for Version {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.nonzero_items()
+            .cmp(other.nonzero_items())
+            .then_with(|| match (&self.pre, &other.pre) {
+                (Some(a), Some(b)) => a.cmp(b),
+                (Some(_), None) => Ordering::Less,
+                (None, Some(_)) => Ordering::Greater,
+                (None, None) => Ordering::Equal,
+            })
+            .then_with(|| self.build.cmp(&other.build))
+    }
+}
+
+impl PartialOrd for Version {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl PartialEq for Version {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for Version {}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Operator {
+    Greater,
+    GreaterEqual,
+    Less,
+    LessEqual,
+    Equal,
+}
+
+#[derive(Clone, Debug)]
+struct Condition {
+    operator: Operator,
+    version: Version,
+}
+
+impl Condition {
+    fn new(operator: Operator, version: Version) -> Self {
+        // NOTE: ">=1.2" also accepts the 1.2 pre-releases, "<2.0" also rejects the 2.0 pre-releases
+        let version = match operator {
+            Operator::GreaterEqual | Operator::Less if version.pre.is_none() && version.build.is_none() => {
+                Version::parse(&format!("{}-", version.value))
+            }
+            _ => version,
+        };
+        Condition { operator, version }
+    }
+
+    fn parse(expression: &str) -> Result<Vec<Condition>, VersionError> {
+        if expression.is_empty() || expression == "*" {
+            return Ok(vec![Condition::new(Operator::GreaterEqual, Version::parse("0.0.0-"))]);
+        }
+
+        let (operator, version) = if let Some(version) = expression.strip_prefix(">=") {
+            (">=", version)
+        } else if let Some(version) = expression.strip_prefix("<=") {
+            ("<=", version)
+        } else if let Some(version) = expression.strip_prefix('>') {
+            (">", version)
+        } else if let Some(version) = expression.strip_prefix('<') {
+            ("<", version)
+        } else if let Some(version) = expression.strip_prefix('~') {
+            ("~", version)
+        } else if let Some(version) = expression.strip_prefix('^') {
+            ("^", version)
+        } else if let Some(version) = expression.strip_prefix('=') {
+            ("=", version)
+        } else {
+            ("=", expression)
+        };
+
+        if version.is_empty() {
+            return Err(VersionError::InvalidRange(expression.to_string()));
+        }
+
+        let conditions = match operator {
+            "~" => {
+                let version = if version.contains('-') {
+                    Version::parse(version)
+                } else {
+                    Version::parse(&format!("{}-", version))
+                };
+                let index = if version.items.len() > 1 { 1 } else { 0 };
+                let upper_bound = version.upper_bound(index)?;
+                vec![
+                    Condition::new(Operator::GreaterEqual, version),
+                    Condition::new(Operator::Less, upper_bound),
+                ]
+            }
+            "^" => {
+                let version = Version::parse(version);
+                let index = version
+                    .items
+                    .iter()
+                    .position(|x| !x.is_zero())
+                    .unwrap_or(version.items.len());
+                let upper_bound = version.upper_bound(index)?;
+                vec![
+                    Condition::new(Operator::GreaterEqual, version),
+                    Condition::new(Operator::Less, upper_bound),
+                ]
+            }
+            operator => {
+                let operator = match operator {
+                    ">=" => Operator::GreaterEqual,
+                    "<=" => Operator::LessEqual,
+                    ">" => Operator::Greater,
+                    "<" => Operator::Less,
+                    _ => Operator::Equal,
+                };
+                vec![Condition::new(operator, Version::parse(version))]
+            }
+        };
+
+        Ok(conditions)
+    }
+
+    fn matches(&self, version: &Version) -> bool {
+        match self.operator {
+            Operator::Greater => version > &self.version,
+            Operator::GreaterEqual => version >= &self.version,
+            Operator::Less => version < &self.version,
+            Operator::LessEqual => version <= &self.version,
+            Operator::Equal => version == &self.version,
+        }
+    }
+}
+
+/// Version range, e.g. `[>=1.2 <2.0]`, `[~1.2]` or `[^3.1, include_prerelease]`
+///
+/// Space-separated conditions must all match, `||` separates alternatives.
+/// Pre-releases only match when the range has the `include_prerelease` option.
+#[derive(Clone, Debug)]
+pub struct VersionRange {
+    expression: String,
+    condition_sets: Vec<Vec<Condition>>,
+    include_prerelease: bool,
+}
+
+impl VersionRange {
+    /// Whether a version string is a range, i.e. is enclosed in brackets.
+    pub fn is_range(version: &str) -> bool {
+        let version = version.trim();
+        version.starts_with('[') && version.ends_with(']')
+    }
+
+    pub fn contains(&self, version: &Version) -> bool {
+        if version.is_prerelease() && !self.include_prerelease {
+            return false;
+        }
+        self.condition_sets
+            .iter()
+            .any(|conditions| conditions.iter().all(|x| x.matches(version)))
+    }
+}
+
+impl FromStr for VersionRange {
+    type Err = VersionError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let expression = s.trim();
+        let inner = expression
+            .strip_prefix('[')
+            .and_then(|x| x.strip_suffix(']'))
+            .unwrap_or(expression);
+
+        let mut tokens = inner.split(',');
+        let version_expression = tokens.next().unwrap_or_default();
+        let include_prerelease = tokens.any(|x| x.trim() == "include_prerelease");
+
+        let condition_sets = version_expression
+            .split("||")
+            .map(|alternative| {
+                let expressions: Vec<&str> = alternative.split_whitespace().collect();
+                if expressions.is_empty() {
+                    return Condition::parse("");
+                }
+                let mut conditions = Vec::new();
+                for expression in expressions {
+                    conditions.extend(Condition::parse(expression)?);
+                }
+                Ok(conditions)
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(VersionRange {
+            expression: expression.to_string(),
+            condition_sets,
+            include_prerelease,
+        })
+    }
+}
+
+impl fmt::Display for VersionRange {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(&self.expression)
+    }
+}
@@ -0,0 +1,101 @@
+use super::*;
+
+fn v(version: &str) -> Version {
+    version.parse().unwrap()
+}
+
+fn range(range: &str) -> VersionRange {
+    range.parse().unwrap()
+}
+
+#[test]
+fn test_version_ordering() {
+    assert_eq!(v("1.2"), v("1.2.0"));
+    assert_eq!(v("1.2.0.0"), v("1.2"));
+    assert!(v("1.2") < v("1.10"));
+    assert!(v("1.2.3") < v("1.3"));
+    assert!(v("1.2-beta") < v("1.2"));
+    assert!(v("1.2-alpha") < v("1.2-beta"));
+    assert!(v("1.2-beta") > v("1.1.9"));
+    assert!(v("1.2") < v("1.2+build1"));
+    assert!(v("1.2+build1") < v("1.2+build2"));
+    assert!(v("1.2-rc.2") < v("1.2-rc.10"));
+    assert!(v("1.1a") < v("1.1b"));
+    assert!(v("1.1") < v("1.1a"));
+    assert_eq!(v("3.2.0").to_string(), "3.2.0");
+    assert_eq!("".parse::<Version>(), Err(VersionError::EmptyVersion));
+}
+
+#[test]
+fn test_version_range_conditions() {
+    let r = range("[>=1.2 <2.0]");
+    assert!(r.contains(&v("1.2")));
+    assert!(r.contains(&v("1.9.9")));
+    assert!(!r.contains(&v("2.0")));
+    assert!(!r.contains(&v("1.1")));
+    assert!(!r.contains(&v("1.5-beta")));
+
+    let r = range("[>1.0 <=1.5]");
+    assert!(!r.contains(&v("1.0")));
+    assert!(r.contains(&v("1.5")));
+    assert!(r.contains(&v("1.5.0")));
+    assert!(!r.contains(&v("1.5.1")));
+
+    let r = range("[1.2.3]");
+    assert!(r.contains(&v("1.2.3")));
+    assert!(!r.contains(&v("1.2.4")));
+
+    let r = range("[<1.0 || >=2.0]");
+    assert!(r.contains(&v("0.9")));
+    assert!(r.contains(&v("2.1")));
+    assert!(!r.contains(&v("1.5")));
+
+    let r = range("[*]");
+    assert!(r.contains(&v("0.1")));
+    assert!(r.contains(&v("100")));
+    assert!(range("[]").contains(&v("1.0")));
+}
+
+#[test]
+fn test_version_range_tilde_caret() {
+    let r = range("[~1.2]");
+    assert!(r.contains(&v("1.2")));
+    assert!(r.contains(&v("1.2.9")));
+    assert!(!r.contains(&v("1.3")));
+    assert!(!r.contains(&v("1.1")));
+
+    let r = range("[~1]");
+    assert!(r.contains(&v("1.9")));
+    assert!(!r.contains(&v("2.0")));
+
+    let r = range("[^3.1]");
+    assert!(r.contains(&v("3.1")));
+    assert!(r.contains(&v("3.9.2")));
+    assert!(!r.contains(&v("4.0")));
+    assert!(!r.contains(&v("3.0")));
+
+    let r = range("[^0.1.2]");
+    assert!(r.contains(&v("0.1.5")));
+    assert!(!r.contains(&v("0.2.0")));
+
+    assert!("[~1.a]".parse::<VersionRange>().is_err());
+    assert!("[>=]".parse::<VersionRange>().is_err());
+}
+
+#[test]
+fn test_version_range_prerelease() {
+    let r = range("[^3.1, include_prerelease]");
+    assert!(r.contains(&v("3.2-beta")));
+    assert!(r.contains(&v("3.1")));
+    assert!(!r.contains(&v("4.0-beta")));
+
+    let r = range("[^3.1]");
+    assert!(!r.contains(&v("3.2-beta")));
+    assert_eq!(r.to_string(), "[^3.1]");
+}
+
+#[test]
+fn test_is_range() {
+    assert!(VersionRange::is_range("[>=1.0]"));
+    assert!(!VersionRange::is_range("1.0"));
+}